@@ -0,0 +1,170 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Error, Result};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Fields, Lit, Meta, NestedMeta};
+
+/// Generates the implementations of `FromCommandOptions` and
+/// `FromCommandOption` for a struct or enum.
+pub fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let module = quote!(serenity::model::application::interaction::application_command);
+    let from_options = match &input.data {
+        Data::Struct(data) => expand_struct(&data.fields)?,
+        Data::Enum(data) => {
+            let mut arms = Vec::with_capacity(data.variants.len());
+
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let option_name = match rename(&variant.attrs)? {
+                    Some(name) => name,
+                    None => to_snake_case(&ident.to_string()),
+                };
+
+                let value = match &variant.fields {
+                    Fields::Unit => quote!(Self::#ident),
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        let ty = &fields.unnamed[0].ty;
+
+                        quote! {
+                            Self::#ident(<#ty as #module::FromCommandOptions>::from_options(&option.options)?)
+                        }
+                    },
+                    fields => {
+                        return Err(Error::new(
+                            fields.span(),
+                            "expected a unit variant or a variant with a single unnamed field",
+                        ));
+                    },
+                };
+
+                arms.push(quote!(#option_name => Ok(#value)));
+            }
+
+            quote! {
+                let option = options
+                    .iter()
+                    .find(|option| matches!(
+                        option.kind,
+                        serenity::model::application::command::CommandOptionType::SubCommand
+                            | serenity::model::application::command::CommandOptionType::SubCommandGroup
+                    ))
+                    .ok_or(#module::CommandOptionError::MissingSubCommand)?;
+
+                match option.name.as_str() {
+                    #(#arms,)*
+                    name => Err(#module::CommandOptionError::UnknownSubCommand(name.to_string())),
+                }
+            }
+        },
+        Data::Union(data) => {
+            return Err(Error::new(data.union_token.span(), "unions are not supported"));
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #module::FromCommandOptions for #name #ty_generics #where_clause {
+            fn from_options(
+                options: &[#module::CommandDataOption],
+            ) -> std::result::Result<Self, #module::CommandOptionError> {
+                #from_options
+            }
+        }
+
+        impl #impl_generics #module::FromCommandOption for #name #ty_generics #where_clause {
+            fn from_option(
+                option: &#module::CommandDataOption,
+            ) -> std::result::Result<Self, #module::CommandOptionError> {
+                <Self as #module::FromCommandOptions>::from_options(&option.options)
+            }
+        }
+    })
+}
+
+fn expand_struct(fields: &Fields) -> Result<TokenStream2> {
+    let module = quote!(serenity::model::application::interaction::application_command);
+
+    let fields = match fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unit => {
+            return Ok(quote! {
+                let _ = options;
+
+                Ok(Self)
+            });
+        },
+        Fields::Unnamed(fields) => {
+            return Err(Error::new(fields.span(), "tuple structs are not supported"));
+        },
+    };
+
+    let mut values = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        // Named fields always have an identifier.
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let option_name = match rename(&field.attrs)? {
+            Some(name) => name,
+            None => ident.to_string().trim_start_matches("r#").to_string(),
+        };
+
+        values.push(quote! {
+            #ident: match options.iter().find(|option| option.name == #option_name) {
+                Some(option) => <#ty as #module::FromCommandOption>::from_option(option)?,
+                None => <#ty as #module::FromCommandOption>::missing(#option_name)?,
+            }
+        });
+    }
+
+    Ok(quote! {
+        Ok(Self {
+            #(#values,)*
+        })
+    })
+}
+
+/// Parses the name given via `#[option(name = "...")]`, if any.
+fn rename(attrs: &[Attribute]) -> Result<Option<String>> {
+    let mut name = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("option")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[option(name = \"...\")]`")),
+        };
+
+        for nested in &list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("name") => {
+                    match &pair.lit {
+                        Lit::Str(lit) => name = Some(lit.value()),
+                        lit => return Err(Error::new(lit.span(), "expected a string")),
+                    }
+                },
+                nested => {
+                    return Err(Error::new(nested.span(), "expected `name = \"...\"`"));
+                },
+            }
+        }
+    }
+
+    Ok(name)
+}
+
+/// Converts the name of a variant, such as `AddRole`, to `add_role`.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            snake.push('_');
+        }
+
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
@@ -9,6 +9,7 @@ use syn::spanned::Spanned;
 use syn::{parse_macro_input, parse_quote, Ident, Lit, Token};
 
 pub(crate) mod attributes;
+pub(crate) mod command_option;
 pub(crate) mod consts;
 pub(crate) mod structures;
 
@@ -941,3 +942,22 @@ pub fn hook(_attr: TokenStream, input: TokenStream) -> TokenStream {
         },
     }
 }
+
+/// Derives `FromCommandOption` and `FromCommandOptions`, extracting the options
+/// of an application command interaction into a struct or enum.
+///
+/// The fields of a struct are mapped onto options by name, and the variants of
+/// an enum onto subcommands or subcommand groups. Names can be changed via
+/// `#[option(name = "...")]`.
+///
+/// Refer to the documentation of `FromCommandOption` in serenity for more
+/// information.
+#[proc_macro_derive(FromCommandOption, attributes(option))]
+pub fn from_command_option(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    match command_option::expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,540 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+use dashmap::mapref::entry::Entry;
+use dashmap::mapref::multiple::RefMulti;
+use dashmap::DashMap;
+
+use crate::model::prelude::*;
+
+/// The storage used by a [`Cache`] for the data received from the gateway.
+///
+/// Every [`CacheUpdate`] implementation and every getter on the [`Cache`]
+/// goes through this trait, allowing the data to live somewhere other than
+/// the process itself, such as Redis or a file. [`DashMapBackend`] is used if
+/// no other backend is set via [`Settings::backend`].
+///
+/// Methods taking a closure give access to the stored value without cloning
+/// it. Backends which cannot hand out references may load the value, call the
+/// closure, and then store the value again. These methods return whether the
+/// value existed.
+///
+/// Members and roles are stored inside their [`Guild`] by default; a backend
+/// may override those methods to store them separately.
+///
+/// **Note**: The [`Cache`] is synchronous, so the methods of this trait must
+/// not block for longer than necessary.
+///
+/// [`Cache`]: super::Cache
+/// [`CacheUpdate`]: super::CacheUpdate
+/// [`Settings::backend`]: super::Settings::backend
+pub trait CacheBackend: fmt::Debug + Send + Sync {
+    /// Retrieves a guild.
+    fn guild(&self, guild_id: GuildId) -> Option<Guild>;
+
+    /// Retrieves the Ids of all guilds.
+    fn guild_ids(&self) -> Vec<GuildId>;
+
+    /// The number of guilds.
+    fn guild_count(&self) -> usize;
+
+    /// Retrieves the first guild matching the predicate.
+    fn find_guild(&self, predicate: &mut dyn FnMut(&Guild) -> bool) -> Option<Guild>;
+
+    /// Calls the closure with a reference to the guild.
+    fn with_guild(&self, guild_id: GuildId, f: &mut dyn FnMut(&Guild)) -> bool;
+
+    /// Calls the closure with a mutable reference to the guild, storing the
+    /// changes made to it.
+    fn update_guild(&self, guild_id: GuildId, f: &mut dyn FnMut(&mut Guild)) -> bool;
+
+    /// Inserts a guild, returning the previous one.
+    fn insert_guild(&self, guild: Guild) -> Option<Guild>;
+
+    /// Removes a guild.
+    fn remove_guild(&self, guild_id: GuildId) -> Option<Guild>;
+
+    /// Whether a guild is stored.
+    fn contains_guild(&self, guild_id: GuildId) -> bool {
+        self.with_guild(guild_id, &mut |_| {})
+    }
+
+    /// Retrieves a guild channel.
+    fn channel(&self, channel_id: ChannelId) -> Option<GuildChannel>;
+
+    /// The number of guild channels.
+    fn channel_count(&self) -> usize;
+
+    /// Retrieves the first guild channel matching the predicate.
+    fn find_channel(
+        &self,
+        predicate: &mut dyn FnMut(&GuildChannel) -> bool,
+    ) -> Option<GuildChannel>;
+
+    /// Calls the closure with a reference to the guild channel.
+    fn with_channel(&self, channel_id: ChannelId, f: &mut dyn FnMut(&GuildChannel)) -> bool;
+
+    /// Calls the closure with a mutable reference to the guild channel,
+    /// storing the changes made to it.
+    fn update_channel(&self, channel_id: ChannelId, f: &mut dyn FnMut(&mut GuildChannel)) -> bool;
+
+    /// Inserts a guild channel, returning the previous one.
+    fn insert_channel(&self, channel: GuildChannel) -> Option<GuildChannel>;
+
+    /// Removes a guild channel.
+    fn remove_channel(&self, channel_id: ChannelId) -> Option<GuildChannel>;
+
+    /// Retrieves a channel category.
+    fn category(&self, channel_id: ChannelId) -> Option<ChannelCategory>;
+
+    /// Retrieves all channel categories.
+    fn categories(&self) -> Vec<ChannelCategory>;
+
+    /// The number of channel categories.
+    fn category_count(&self) -> usize;
+
+    /// Inserts a channel category, returning the previous one.
+    fn insert_category(&self, category: ChannelCategory) -> Option<ChannelCategory>;
+
+    /// Removes a channel category.
+    fn remove_category(&self, channel_id: ChannelId) -> Option<ChannelCategory>;
+
+    /// Retrieves a private channel.
+    fn private_channel(&self, channel_id: ChannelId) -> Option<PrivateChannel>;
+
+    /// Retrieves all private channels.
+    fn private_channels(&self) -> Vec<PrivateChannel>;
+
+    /// Calls the closure with a mutable reference to the private channel,
+    /// storing the changes made to it.
+    fn update_private_channel(
+        &self,
+        channel_id: ChannelId,
+        f: &mut dyn FnMut(&mut PrivateChannel),
+    ) -> bool;
+
+    /// Inserts a private channel, returning the previous one.
+    fn insert_private_channel(&self, channel: PrivateChannel) -> Option<PrivateChannel>;
+
+    /// Removes a private channel.
+    fn remove_private_channel(&self, channel_id: ChannelId) -> Option<PrivateChannel>;
+
+    /// Retrieves a member of a guild.
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> Option<Member> {
+        let mut member = None;
+        self.with_guild(guild_id, &mut |guild| member = guild.members.get(&user_id).cloned());
+        member
+    }
+
+    /// Calls the closure with a reference to the member.
+    fn with_member(&self, guild_id: GuildId, user_id: UserId, f: &mut dyn FnMut(&Member)) -> bool {
+        let mut found = false;
+        self.with_guild(guild_id, &mut |guild| {
+            if let Some(member) = guild.members.get(&user_id) {
+                found = true;
+                f(member);
+            }
+        });
+        found
+    }
+
+    /// Calls the closure with a mutable reference to the member, storing the
+    /// changes made to it.
+    fn update_member(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        f: &mut dyn FnMut(&mut Member),
+    ) -> bool {
+        let mut found = false;
+        self.update_guild(guild_id, &mut |guild| {
+            if let Some(member) = guild.members.get_mut(&user_id) {
+                found = true;
+                f(member);
+            }
+        });
+        found
+    }
+
+    /// Inserts a member into its guild, returning the previous one.
+    ///
+    /// Nothing is inserted if the guild is not stored.
+    fn insert_member(&self, member: Member) -> Option<Member> {
+        let mut old = None;
+        self.update_guild(member.guild_id, &mut |guild| {
+            old = guild.members.insert(member.user.id, member.clone());
+        });
+        old
+    }
+
+    /// Removes a member from a guild.
+    fn remove_member(&self, guild_id: GuildId, user_id: UserId) -> Option<Member> {
+        let mut old = None;
+        self.update_guild(guild_id, &mut |guild| old = guild.members.remove(&user_id));
+        old
+    }
+
+    /// Retrieves a role of a guild.
+    fn role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role> {
+        let mut role = None;
+        self.with_guild(guild_id, &mut |guild| role = guild.roles.get(&role_id).cloned());
+        role
+    }
+
+    /// Retrieves all roles of a guild.
+    fn roles(&self, guild_id: GuildId) -> Option<HashMap<RoleId, Role>> {
+        let mut roles = None;
+        self.with_guild(guild_id, &mut |guild| roles = Some(guild.roles.clone()));
+        roles
+    }
+
+    /// Inserts a role into its guild, returning the previous one.
+    ///
+    /// Nothing is inserted if the guild is not stored.
+    fn insert_role(&self, role: Role) -> Option<Role> {
+        let mut old = None;
+        self.update_guild(role.guild_id, &mut |guild| {
+            old = guild.roles.insert(role.id, role.clone());
+        });
+        old
+    }
+
+    /// Removes a role from a guild.
+    fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role> {
+        let mut old = None;
+        self.update_guild(guild_id, &mut |guild| old = guild.roles.remove(&role_id));
+        old
+    }
+
+    /// Retrieves a user.
+    fn user(&self, user_id: UserId) -> Option<User>;
+
+    /// Retrieves all users.
+    fn users(&self) -> Vec<User>;
+
+    /// Retrieves the Ids of all users.
+    fn user_ids(&self) -> Vec<UserId>;
+
+    /// The number of users.
+    fn user_count(&self) -> usize;
+
+    /// Retrieves the first user matching the predicate.
+    fn find_user(&self, predicate: &mut dyn FnMut(&User) -> bool) -> Option<User>;
+
+    /// Inserts a user, returning the previous one.
+    fn insert_user(&self, user: User) -> Option<User>;
+
+    /// Inserts a user, or updates the stored one in place, without returning
+    /// the previous one.
+    fn update_user_entry(&self, user: &User) {
+        self.insert_user(user.clone());
+    }
+
+    /// Removes a user.
+    fn remove_user(&self, user_id: UserId) -> Option<User>;
+
+    /// Retrieves a message.
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message>;
+
+    /// Retrieves all messages of a channel, if any are stored.
+    fn channel_messages(&self, channel_id: ChannelId) -> Option<Vec<Message>>;
+
+    /// Calls the closure with references to all messages of a channel,
+    /// returning whether any are stored.
+    fn with_channel_messages(&self, channel_id: ChannelId, f: &mut dyn FnMut(&[&Message])) -> bool {
+        self.channel_messages(channel_id)
+            .map(|messages| f(&messages.iter().collect::<Vec<_>>()))
+            .is_some()
+    }
+
+    /// Calls the closure with a mutable reference to the message, storing the
+    /// changes made to it.
+    fn update_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        f: &mut dyn FnMut(&mut Message),
+    ) -> bool;
+
+    /// Inserts a message into its channel.
+    ///
+    /// If the channel already holds `max_messages` messages, the oldest one
+    /// is removed and returned.
+    fn insert_message(&self, message: Message, max_messages: usize) -> Option<Message>;
+
+    /// Removes all messages of a channel.
+    fn remove_channel_messages(&self, channel_id: ChannelId);
+
+    /// Retrieves the presence of a user, outside of any guild.
+    fn presence(&self, user_id: UserId) -> Option<Presence>;
+
+    /// Inserts a presence, returning the previous one.
+    fn insert_presence(&self, presence: Presence) -> Option<Presence>;
+
+    /// Removes the presence of a user.
+    fn remove_presence(&self, user_id: UserId) -> Option<Presence>;
+}
+
+/// The default, in-process [`CacheBackend`] storing everything in [`DashMap`]s.
+#[derive(Debug)]
+pub struct DashMapBackend {
+    channels: DashMap<ChannelId, GuildChannel>,
+    categories: DashMap<ChannelId, ChannelCategory>,
+    guilds: DashMap<GuildId, Guild>,
+    messages: DashMap<ChannelId, DashMap<MessageId, Message>>,
+    /// Queue of message IDs for each channel.
+    ///
+    /// This is simply a vecdeque so we can keep track of the order of messages
+    /// inserted into the cache. When a maximum number of messages are in a
+    /// channel's cache, we can pop the front and remove that ID from the cache.
+    message_queue: DashMap<ChannelId, VecDeque<MessageId>>,
+    presences: DashMap<UserId, Presence>,
+    private_channels: DashMap<ChannelId, PrivateChannel>,
+    users: DashMap<UserId, User>,
+}
+
+impl DashMapBackend {
+    /// Creates a new, empty backend.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for DashMapBackend {
+    fn default() -> Self {
+        Self {
+            channels: DashMap::default(),
+            categories: DashMap::default(),
+            guilds: DashMap::default(),
+            messages: DashMap::default(),
+            message_queue: DashMap::default(),
+            presences: DashMap::default(),
+            private_channels: DashMap::with_capacity(128),
+            users: DashMap::default(),
+        }
+    }
+}
+
+impl CacheBackend for DashMapBackend {
+    fn guild(&self, guild_id: GuildId) -> Option<Guild> {
+        self.guilds.get(&guild_id).map(|g| g.clone())
+    }
+
+    fn guild_ids(&self) -> Vec<GuildId> {
+        self.guilds.iter().map(|g| *g.key()).collect()
+    }
+
+    fn guild_count(&self) -> usize {
+        self.guilds.len()
+    }
+
+    fn find_guild(&self, predicate: &mut dyn FnMut(&Guild) -> bool) -> Option<Guild> {
+        self.guilds.iter().find(|g| predicate(g.value())).map(|g| g.clone())
+    }
+
+    fn with_guild(&self, guild_id: GuildId, f: &mut dyn FnMut(&Guild)) -> bool {
+        self.guilds.get(&guild_id).map(|g| f(&g)).is_some()
+    }
+
+    fn update_guild(&self, guild_id: GuildId, f: &mut dyn FnMut(&mut Guild)) -> bool {
+        self.guilds.get_mut(&guild_id).map(|mut g| f(&mut g)).is_some()
+    }
+
+    fn insert_guild(&self, guild: Guild) -> Option<Guild> {
+        self.guilds.insert(guild.id, guild)
+    }
+
+    fn remove_guild(&self, guild_id: GuildId) -> Option<Guild> {
+        self.guilds.remove(&guild_id).map(|(_, g)| g)
+    }
+
+    fn channel(&self, channel_id: ChannelId) -> Option<GuildChannel> {
+        self.channels.get(&channel_id).map(|c| c.clone())
+    }
+
+    fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    fn find_channel(
+        &self,
+        predicate: &mut dyn FnMut(&GuildChannel) -> bool,
+    ) -> Option<GuildChannel> {
+        self.channels.iter().find(|c| predicate(c.value())).map(|c| c.clone())
+    }
+
+    fn with_channel(&self, channel_id: ChannelId, f: &mut dyn FnMut(&GuildChannel)) -> bool {
+        self.channels.get(&channel_id).map(|c| f(&c)).is_some()
+    }
+
+    fn update_channel(&self, channel_id: ChannelId, f: &mut dyn FnMut(&mut GuildChannel)) -> bool {
+        self.channels.get_mut(&channel_id).map(|mut c| f(&mut c)).is_some()
+    }
+
+    fn insert_channel(&self, channel: GuildChannel) -> Option<GuildChannel> {
+        self.channels.insert(channel.id, channel)
+    }
+
+    fn remove_channel(&self, channel_id: ChannelId) -> Option<GuildChannel> {
+        self.channels.remove(&channel_id).map(|(_, c)| c)
+    }
+
+    fn category(&self, channel_id: ChannelId) -> Option<ChannelCategory> {
+        self.categories.get(&channel_id).map(|c| c.clone())
+    }
+
+    fn categories(&self) -> Vec<ChannelCategory> {
+        self.categories.iter().map(|c| c.clone()).collect()
+    }
+
+    fn category_count(&self) -> usize {
+        self.categories.len()
+    }
+
+    fn insert_category(&self, category: ChannelCategory) -> Option<ChannelCategory> {
+        self.categories.insert(category.id, category)
+    }
+
+    fn remove_category(&self, channel_id: ChannelId) -> Option<ChannelCategory> {
+        self.categories.remove(&channel_id).map(|(_, c)| c)
+    }
+
+    fn private_channel(&self, channel_id: ChannelId) -> Option<PrivateChannel> {
+        self.private_channels.get(&channel_id).map(|c| c.clone())
+    }
+
+    fn private_channels(&self) -> Vec<PrivateChannel> {
+        self.private_channels.iter().map(|c| c.clone()).collect()
+    }
+
+    fn update_private_channel(
+        &self,
+        channel_id: ChannelId,
+        f: &mut dyn FnMut(&mut PrivateChannel),
+    ) -> bool {
+        self.private_channels.get_mut(&channel_id).map(|mut c| f(&mut c)).is_some()
+    }
+
+    fn insert_private_channel(&self, channel: PrivateChannel) -> Option<PrivateChannel> {
+        self.private_channels.insert(channel.id, channel)
+    }
+
+    fn remove_private_channel(&self, channel_id: ChannelId) -> Option<PrivateChannel> {
+        self.private_channels.remove(&channel_id).map(|(_, c)| c)
+    }
+
+    fn user(&self, user_id: UserId) -> Option<User> {
+        self.users.get(&user_id).map(|u| u.clone())
+    }
+
+    fn users(&self) -> Vec<User> {
+        self.users.iter().map(|u| u.clone()).collect()
+    }
+
+    fn user_ids(&self) -> Vec<UserId> {
+        self.users.iter().map(|u| *u.key()).collect()
+    }
+
+    fn user_count(&self) -> usize {
+        self.users.len()
+    }
+
+    fn find_user(&self, predicate: &mut dyn FnMut(&User) -> bool) -> Option<User> {
+        self.users.iter().find(|u| predicate(u.value())).map(|u| u.clone())
+    }
+
+    fn insert_user(&self, user: User) -> Option<User> {
+        match self.users.entry(user.id) {
+            Entry::Vacant(e) => {
+                e.insert(user);
+
+                None
+            },
+            Entry::Occupied(mut e) => Some(std::mem::replace(e.get_mut(), user)),
+        }
+    }
+
+    fn update_user_entry(&self, user: &User) {
+        match self.users.entry(user.id) {
+            Entry::Vacant(e) => {
+                e.insert(user.clone());
+            },
+            Entry::Occupied(mut e) => {
+                e.get_mut().clone_from(user);
+            },
+        }
+    }
+
+    fn remove_user(&self, user_id: UserId) -> Option<User> {
+        self.users.remove(&user_id).map(|(_, u)| u)
+    }
+
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        self.messages
+            .get(&channel_id)
+            .and_then(|messages| messages.get(&message_id).map(|m| m.clone()))
+    }
+
+    fn channel_messages(&self, channel_id: ChannelId) -> Option<Vec<Message>> {
+        self.messages.get(&channel_id).map(|messages| messages.iter().map(|m| m.clone()).collect())
+    }
+
+    fn with_channel_messages(&self, channel_id: ChannelId, f: &mut dyn FnMut(&[&Message])) -> bool {
+        self.messages
+            .get(&channel_id)
+            .map(|messages| {
+                let guards = messages.iter().collect::<Vec<_>>();
+                f(&guards.iter().map(RefMulti::value).collect::<Vec<_>>());
+            })
+            .is_some()
+    }
+
+    fn update_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        f: &mut dyn FnMut(&mut Message),
+    ) -> bool {
+        self.messages
+            .get(&channel_id)
+            .and_then(|messages| messages.get_mut(&message_id).map(|mut m| f(&mut m)))
+            .is_some()
+    }
+
+    fn insert_message(&self, message: Message, max_messages: usize) -> Option<Message> {
+        let messages = self.messages.entry(message.channel_id).or_default();
+        let mut queue = self.message_queue.entry(message.channel_id).or_default();
+
+        let mut removed_msg = None;
+
+        if messages.len() == max_messages {
+            if let Some(id) = queue.pop_front() {
+                removed_msg = messages.remove(&id);
+            }
+        }
+
+        queue.push_back(message.id);
+        messages.insert(message.id, message);
+
+        removed_msg.map(|(_, m)| m)
+    }
+
+    fn remove_channel_messages(&self, channel_id: ChannelId) {
+        self.messages.remove(&channel_id);
+        self.message_queue.remove(&channel_id);
+    }
+
+    fn presence(&self, user_id: UserId) -> Option<Presence> {
+        self.presences.get(&user_id).map(|p| p.clone())
+    }
+
+    fn insert_presence(&self, presence: Presence) -> Option<Presence> {
+        self.presences.insert(presence.user.id, presence)
+    }
+
+    fn remove_presence(&self, user_id: UserId) -> Option<Presence> {
+        self.presences.remove(&user_id).map(|(_, p)| p)
+    }
+}
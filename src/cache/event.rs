@@ -43,45 +43,37 @@ impl CacheUpdate for ChannelCreateEvent {
             Channel::Guild(ref channel) => {
                 let (guild_id, channel_id) = (channel.guild_id, channel.id);
 
-                let old_channel = cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .and_then(|mut g| g.channels.insert(channel_id, self.channel.clone()));
+                let mut old_channel = None;
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    old_channel = g.channels.insert(channel_id, self.channel.clone());
+                });
 
-                cache.channels.insert(channel_id, channel.clone());
+                cache.backend.insert_channel(channel.clone());
 
                 old_channel
             },
             Channel::Private(ref mut channel) => {
-                if let Some(channel) = cache.private_channels.get(&channel.id) {
-                    return Some(Channel::Private(channel.clone()));
+                if let Some(channel) = cache.backend.private_channel(channel.id) {
+                    return Some(Channel::Private(channel));
                 }
 
-                let id = {
-                    let user_id = {
-                        cache.update_user_entry(&channel.recipient);
-
-                        channel.recipient.id
-                    };
-
-                    if let Some(u) = cache.users.get(&user_id) {
-                        channel.recipient = u.clone();
-                    }
+                cache.update_user_entry(&channel.recipient);
 
-                    channel.id
-                };
+                if let Some(u) = cache.backend.user(channel.recipient.id) {
+                    channel.recipient = u;
+                }
 
-                cache.private_channels.insert(id, channel.clone()).map(Channel::Private)
+                cache.backend.insert_private_channel(channel.clone()).map(Channel::Private)
             },
             Channel::Category(ref category) => {
                 let (guild_id, channel_id) = (category.guild_id, category.id);
 
-                let old_channel = cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .and_then(|mut g| g.channels.insert(channel_id, self.channel.clone()));
+                let mut old_channel = None;
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    old_channel = g.channels.insert(channel_id, self.channel.clone());
+                });
 
-                cache.categories.insert(channel_id, category.clone());
+                cache.backend.insert_category(category.clone());
 
                 old_channel
             },
@@ -97,26 +89,28 @@ impl CacheUpdate for ChannelDeleteEvent {
             Channel::Guild(ref channel) => {
                 let (guild_id, channel_id) = (channel.guild_id, channel.id);
 
-                cache.channels.remove(&channel_id);
+                cache.backend.remove_channel(channel_id);
 
-                cache.guilds.get_mut(&guild_id).map(|mut g| g.channels.remove(&channel_id));
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    g.channels.remove(&channel_id);
+                });
             },
             Channel::Category(ref category) => {
                 let (guild_id, channel_id) = (category.guild_id, category.id);
 
-                cache.categories.remove(&channel_id);
+                cache.backend.remove_category(channel_id);
 
-                cache.guilds.get_mut(&guild_id).map(|mut g| g.channels.remove(&channel_id));
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    g.channels.remove(&channel_id);
+                });
             },
             Channel::Private(ref channel) => {
-                let id = { channel.id };
-
-                cache.private_channels.remove(&id);
+                cache.backend.remove_private_channel(channel.id);
             },
         };
 
         // Remove the cached messages for the channel.
-        cache.messages.remove(&self.channel.id());
+        cache.backend.remove_channel_messages(self.channel.id());
 
         None
     }
@@ -130,27 +124,23 @@ impl CacheUpdate for ChannelUpdateEvent {
             Channel::Guild(ref channel) => {
                 let (guild_id, channel_id) = (channel.guild_id, channel.id);
 
-                cache.channels.insert(channel_id, channel.clone());
+                cache.backend.insert_channel(channel.clone());
 
-                cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .map(|mut g| g.channels.insert(channel_id, self.channel.clone()));
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    g.channels.insert(channel_id, self.channel.clone());
+                });
             },
             Channel::Private(ref channel) => {
-                if let Some(mut c) = cache.private_channels.get_mut(&channel.id) {
-                    c.clone_from(channel);
-                }
+                cache.backend.update_private_channel(channel.id, &mut |c| c.clone_from(channel));
             },
             Channel::Category(ref category) => {
                 let (guild_id, channel_id) = (category.guild_id, category.id);
 
-                cache.categories.insert(channel_id, category.clone());
+                cache.backend.insert_category(category.clone());
 
-                cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .map(|mut g| g.channels.insert(channel_id, self.channel.clone()));
+                cache.backend.update_guild(guild_id, &mut |g| {
+                    g.channels.insert(channel_id, self.channel.clone());
+                });
             },
         }
 
@@ -162,17 +152,17 @@ impl CacheUpdate for ChannelPinsUpdateEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
-        if let Some(mut channel) = cache.channels.get_mut(&self.channel_id) {
-            channel.last_pin_timestamp = self.last_pin_timestamp;
+        let last_pin_timestamp = self.last_pin_timestamp;
 
+        if cache.backend.update_channel(self.channel_id, &mut |channel| {
+            channel.last_pin_timestamp = last_pin_timestamp;
+        }) {
             return None;
         }
 
-        if let Some(mut channel) = cache.private_channels.get_mut(&self.channel_id) {
-            channel.last_pin_timestamp = self.last_pin_timestamp;
-
-            return None;
-        }
+        cache.backend.update_private_channel(self.channel_id, &mut |channel| {
+            channel.last_pin_timestamp = last_pin_timestamp;
+        });
 
         None
     }
@@ -192,19 +182,45 @@ impl CacheUpdate for GuildCreateEvent {
             }
         }
 
+        let settings = cache.settings();
+
+        if settings.cache_members {
+            for user_id in guild.members.keys() {
+                cache.touch_member(guild.id, *user_id);
+            }
+        } else {
+            guild.members.clear();
+        }
+
+        if !settings.cache_presences {
+            guild.presences.clear();
+        }
+
+        if !settings.cache_emojis {
+            guild.emojis.clear();
+        }
+
+        if !settings.cache_stickers {
+            guild.stickers.clear();
+        }
+
+        if !settings.cache_voice_states {
+            guild.voice_states.clear();
+        }
+
         for pair in guild.channels.clone() {
             if let Channel::Guild(channel) = pair.1 {
-                cache.channels.insert(pair.0, channel);
+                cache.backend.insert_channel(channel);
             }
         }
 
         for pair in guild.channels.clone() {
             if let Channel::Category(category) = pair.1 {
-                cache.categories.insert(pair.0, category);
+                cache.backend.insert_category(category);
             }
         }
 
-        cache.guilds.insert(self.guild.id, guild);
+        cache.backend.insert_guild(guild);
 
         None
     }
@@ -214,29 +230,26 @@ impl CacheUpdate for GuildDeleteEvent {
     type Output = Guild;
 
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
-        match cache.guilds.remove(&self.guild.id) {
-            Some(guild) => {
-                for (channel_id, channel) in &guild.1.channels {
-                    match channel {
-                        Channel::Guild(_) => {
-                            // Remove the channel from the cache.
-                            cache.channels.remove(channel_id);
-
-                            // Remove the channel's cached messages.
-                            cache.messages.remove(channel_id);
-                        },
-                        Channel::Category(_) => {
-                            // Remove the category from the cache
-                            cache.categories.remove(channel_id);
-                        },
-                        _ => {},
-                    }
-                }
-
-                Some(guild.1)
-            },
-            None => None,
+        let guild = cache.backend.remove_guild(self.guild.id)?;
+
+        for (channel_id, channel) in &guild.channels {
+            match channel {
+                Channel::Guild(_) => {
+                    // Remove the channel from the cache.
+                    cache.backend.remove_channel(*channel_id);
+
+                    // Remove the channel's cached messages.
+                    cache.backend.remove_channel_messages(*channel_id);
+                },
+                Channel::Category(_) => {
+                    // Remove the category from the cache
+                    cache.backend.remove_category(*channel_id);
+                },
+                _ => {},
+            }
         }
+
+        Some(guild)
     }
 }
 
@@ -244,10 +257,14 @@ impl CacheUpdate for GuildEmojisUpdateEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
-        if let Some(mut guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.emojis.clone_from(&self.emojis);
+        if !cache.settings.read().cache_emojis {
+            return None;
         }
 
+        cache.backend.update_guild(self.guild_id, &mut |guild| {
+            guild.emojis.clone_from(&self.emojis);
+        });
+
         None
     }
 }
@@ -262,9 +279,8 @@ impl CacheUpdate for GuildMemberAddEvent {
             self.member.user = u;
         }
 
-        if let Some(mut guild) = cache.guilds.get_mut(&self.member.guild_id) {
-            guild.member_count += 1;
-            guild.members.insert(user_id, self.member.clone());
+        if cache.backend.update_guild(self.member.guild_id, &mut |guild| guild.member_count += 1) {
+            cache.update_member_entry(&self.member);
         }
 
         None
@@ -275,9 +291,10 @@ impl CacheUpdate for GuildMemberRemoveEvent {
     type Output = Member;
 
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
-        if let Some(mut guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.member_count -= 1;
-            return guild.members.remove(&self.user.id);
+        if cache.backend.update_guild(self.guild_id, &mut |guild| guild.member_count -= 1) {
+            cache.member_last_seen.remove(&(self.guild_id, self.user.id));
+
+            return cache.backend.remove_member(self.guild_id, self.user.id);
         }
 
         None
@@ -290,47 +307,46 @@ impl CacheUpdate for GuildMemberUpdateEvent {
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
         cache.update_user_entry(&self.user);
 
-        if let Some(mut guild) = cache.guilds.get_mut(&self.guild_id) {
-            let item = if let Some(member) = guild.members.get_mut(&self.user.id) {
-                let item = Some(member.clone());
-
-                member.joined_at.clone_from(&Some(self.joined_at));
-                member.nick.clone_from(&self.nick);
-                member.roles.clone_from(&self.roles);
-                member.user.clone_from(&self.user);
-                member.pending.clone_from(&self.pending);
-                member.premium_since.clone_from(&self.premium_since);
-                member.deaf.clone_from(&self.deaf);
-                member.mute.clone_from(&self.mute);
-                member.avatar.clone_from(&self.avatar);
-                member.communication_disabled_until.clone_from(&self.communication_disabled_until);
-
-                item
-            } else {
-                None
-            };
-
-            if item.is_none() {
-                guild.members.insert(self.user.id, Member {
-                    deaf: false,
-                    guild_id: self.guild_id,
-                    joined_at: Some(self.joined_at),
-                    mute: false,
-                    nick: self.nick.clone(),
-                    roles: self.roles.clone(),
-                    user: self.user.clone(),
-                    pending: self.pending,
-                    premium_since: self.premium_since,
-                    permissions: None,
-                    avatar: self.avatar.clone(),
-                    communication_disabled_until: self.communication_disabled_until,
-                });
-            }
+        if !cache.backend.contains_guild(self.guild_id) {
+            return None;
+        }
 
-            item
+        let mut item = None;
+        cache.backend.update_member(self.guild_id, self.user.id, &mut |member| {
+            item = Some(member.clone());
+
+            member.joined_at.clone_from(&Some(self.joined_at));
+            member.nick.clone_from(&self.nick);
+            member.roles.clone_from(&self.roles);
+            member.user.clone_from(&self.user);
+            member.pending.clone_from(&self.pending);
+            member.premium_since.clone_from(&self.premium_since);
+            member.deaf.clone_from(&self.deaf);
+            member.mute.clone_from(&self.mute);
+            member.avatar.clone_from(&self.avatar);
+            member.communication_disabled_until.clone_from(&self.communication_disabled_until);
+        });
+
+        if item.is_some() {
+            cache.touch_member(self.guild_id, self.user.id);
         } else {
-            None
+            cache.update_member_entry(&Member {
+                deaf: false,
+                guild_id: self.guild_id,
+                joined_at: Some(self.joined_at),
+                mute: false,
+                nick: self.nick.clone(),
+                roles: self.roles.clone(),
+                user: self.user.clone(),
+                pending: self.pending,
+                premium_since: self.premium_since,
+                permissions: None,
+                avatar: self.avatar.clone(),
+                communication_disabled_until: self.communication_disabled_until,
+            });
         }
+
+        item
     }
 }
 
@@ -342,8 +358,10 @@ impl CacheUpdate for GuildMembersChunkEvent {
             cache.update_user_entry(&member.user);
         }
 
-        if let Some(mut g) = cache.guilds.get_mut(&self.guild_id) {
-            g.members.extend(self.members.clone());
+        if cache.backend.contains_guild(self.guild_id) {
+            for member in self.members.values() {
+                cache.update_member_entry(member);
+            }
         }
 
         None
@@ -354,10 +372,7 @@ impl CacheUpdate for GuildRoleCreateEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
-        cache
-            .guilds
-            .get_mut(&self.role.guild_id)
-            .map(|mut g| g.roles.insert(self.role.id, self.role.clone()));
+        cache.backend.insert_role(self.role.clone());
 
         None
     }
@@ -367,7 +382,7 @@ impl CacheUpdate for GuildRoleDeleteEvent {
     type Output = Role;
 
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
-        cache.guilds.get_mut(&self.guild_id).and_then(|mut g| g.roles.remove(&self.role_id))
+        cache.backend.remove_role(self.guild_id, self.role_id)
     }
 }
 
@@ -375,13 +390,9 @@ impl CacheUpdate for GuildRoleUpdateEvent {
     type Output = Role;
 
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
-        if let Some(mut guild) = cache.guilds.get_mut(&self.role.guild_id) {
-            if let Some(role) = guild.roles.get_mut(&self.role.id) {
-                return Some(std::mem::replace(role, self.role.clone()));
-            }
-        }
+        cache.backend.role(self.role.guild_id, self.role.id)?;
 
-        None
+        cache.backend.insert_role(self.role.clone())
     }
 }
 
@@ -389,10 +400,14 @@ impl CacheUpdate for GuildStickersUpdateEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
-        if let Some(mut guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.stickers.clone_from(&self.stickers);
+        if !cache.settings.read().cache_stickers {
+            return None;
         }
 
+        cache.backend.update_guild(self.guild_id, &mut |guild| {
+            guild.stickers.clone_from(&self.stickers);
+        });
+
         None
     }
 }
@@ -402,7 +417,7 @@ impl CacheUpdate for GuildUnavailableEvent {
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
         cache.unavailable_guilds.insert(self.guild_id);
-        cache.guilds.remove(&self.guild_id);
+        cache.backend.remove_guild(self.guild_id);
 
         None
     }
@@ -412,7 +427,7 @@ impl CacheUpdate for GuildUpdateEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
-        if let Some(mut guild) = cache.guilds.get_mut(&self.guild.id) {
+        cache.backend.update_guild(self.guild.id, &mut |guild| {
             guild.afk_channel_id.clone_from(&self.guild.afk_channel_id);
             guild.afk_timeout = self.guild.afk_timeout;
             guild.banner.clone_from(&self.guild.banner);
@@ -440,7 +455,7 @@ impl CacheUpdate for GuildUpdateEvent {
             guild.verification_level = self.guild.verification_level;
             guild.widget_channel_id = self.guild.widget_channel_id;
             guild.widget_enabled = self.guild.widget_enabled;
-        }
+        });
 
         None
     }
@@ -451,29 +466,14 @@ impl CacheUpdate for MessageCreateEvent {
     type Output = Message;
 
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
-        let max = cache.settings().max_messages;
+        let settings = cache.settings.read();
+        let max = settings.max_messages;
 
-        if max == 0 {
+        if max == 0 || !settings.cache_messages {
             return None;
         }
 
-        let messages =
-            cache.messages.entry(self.message.channel_id).or_insert_with(Default::default);
-        let mut queue =
-            cache.message_queue.entry(self.message.channel_id).or_insert_with(Default::default);
-
-        let mut removed_msg = None;
-
-        if messages.len() == max {
-            if let Some(id) = queue.pop_front() {
-                removed_msg = messages.remove(&id);
-            }
-        }
-
-        queue.push_back(self.message.id);
-        messages.insert(self.message.id, self.message.clone());
-
-        removed_msg.map(|i| i.1)
+        cache.backend.insert_message(self.message.clone(), max)
     }
 }
 
@@ -493,26 +493,27 @@ impl CacheUpdate for MessageUpdateEvent {
             author: _, timestamp: _,  nonce: _, kind: _, stickers: _,  guild_id: _,
         } = &self;
 
-        let messages = cache.messages.get_mut(channel_id)?;
-        let mut message = messages.get_mut(id)?;
-        let old_message = message.clone();
-
-        if let Some(x) = attachments { message.attachments = x.clone() }
-        if let Some(x) = content { message.content = x.clone() }
-        if let Some(x) = edited_timestamp { message.edited_timestamp = Some(*x) }
-        if let Some(x) = mentions { message.mentions = x.clone() }
-        if let Some(x) = mention_everyone { message.mention_everyone = *x }
-        if let Some(x) = mention_roles { message.mention_roles = x.clone() }
-        if let Some(x) = mention_channels { message.mention_channels = x.clone() }
-        if let Some(x) = pinned { message.pinned = *x }
-        if let Some(x) = flags { message.flags = Some(*x) }
-        if let Some(x) = tts { message.tts = *x }
-        if let Some(x) = embeds { message.embeds = x.clone() }
-        if let Some(x) = reactions { message.reactions = x.clone() }
-        if let Some(x) = components { message.components = x.clone() }
-        if let Some(x) = sticker_items { message.sticker_items = x.clone() }
-
-        Some(old_message)
+        let mut old_message = None;
+        cache.backend.update_message(*channel_id, *id, &mut |message| {
+            old_message = Some(message.clone());
+
+            if let Some(x) = attachments { message.attachments = x.clone() }
+            if let Some(x) = content { message.content = x.clone() }
+            if let Some(x) = edited_timestamp { message.edited_timestamp = Some(*x) }
+            if let Some(x) = mentions { message.mentions = x.clone() }
+            if let Some(x) = mention_everyone { message.mention_everyone = *x }
+            if let Some(x) = mention_roles { message.mention_roles = x.clone() }
+            if let Some(x) = mention_channels { message.mention_channels = x.clone() }
+            if let Some(x) = pinned { message.pinned = *x }
+            if let Some(x) = flags { message.flags = Some(*x) }
+            if let Some(x) = tts { message.tts = *x }
+            if let Some(x) = embeds { message.embeds = x.clone() }
+            if let Some(x) = reactions { message.reactions = x.clone() }
+            if let Some(x) = components { message.components = x.clone() }
+            if let Some(x) = sticker_items { message.sticker_items = x.clone() }
+        });
+
+        old_message
     }
 }
 
@@ -528,19 +529,26 @@ impl CacheUpdate for PresenceUpdateEvent {
             self.presence.user.update_with_user(user);
         }
 
+        let cache_presences = cache.settings.read().cache_presences;
+
         if let Some(guild_id) = self.presence.guild_id {
-            if let Some(mut guild) = cache.guilds.get_mut(&guild_id) {
+            let user_id = self.presence.user.id;
+            let presence = &self.presence;
+
+            let guild_exists = cache.backend.update_guild(guild_id, &mut |guild| {
                 // If the member went offline, remove them from the presence list.
-                if self.presence.status == OnlineStatus::Offline {
-                    guild.presences.remove(&self.presence.user.id);
-                } else {
-                    guild.presences.insert(self.presence.user.id, self.presence.clone());
+                if presence.status == OnlineStatus::Offline {
+                    guild.presences.remove(&user_id);
+                } else if cache_presences {
+                    guild.presences.insert(user_id, presence.clone());
                 }
+            });
 
-                // Create a partial member instance out of the presence update
-                // data.
-                if let Some(user) = self.presence.user.to_user() {
-                    guild.members.entry(self.presence.user.id).or_insert_with(|| Member {
+            // Create a partial member instance out of the presence update
+            // data.
+            if let Some(user) = self.presence.user.to_user() {
+                if guild_exists && cache.backend.member(guild_id, user_id).is_none() {
+                    cache.update_member_entry(&Member {
                         deaf: false,
                         guild_id,
                         joined_at: None,
@@ -557,9 +565,9 @@ impl CacheUpdate for PresenceUpdateEvent {
                 }
             }
         } else if self.presence.status == OnlineStatus::Offline {
-            cache.presences.remove(&self.presence.user.id);
-        } else {
-            cache.presences.insert(self.presence.user.id, self.presence.clone());
+            cache.backend.remove_presence(self.presence.user.id);
+        } else if cache_presences {
+            cache.backend.insert_presence(self.presence.clone());
         }
 
         None
@@ -570,8 +578,12 @@ impl CacheUpdate for PresencesReplaceEvent {
     type Output = ();
 
     fn update(&mut self, cache: &Cache) -> Option<()> {
+        if !cache.settings.read().cache_presences {
+            return None;
+        }
+
         for presence in &self.presences {
-            cache.presences.insert(presence.user.id, presence.clone());
+            cache.backend.insert_presence(presence.clone());
         }
 
         None
@@ -585,7 +597,7 @@ impl CacheUpdate for ReadyEvent {
         let mut ready = self.ready.clone();
 
         for unavailable in ready.guilds {
-            cache.guilds.remove(&unavailable.id);
+            cache.backend.remove_guild(unavailable.id);
             cache.unavailable_guilds.insert(unavailable.id);
         }
 
@@ -594,24 +606,25 @@ impl CacheUpdate for ReadyEvent {
         let ready_guilds_hashset =
             self.ready.guilds.iter().map(|status| status.id).collect::<HashSet<_>>();
         let shard_data = self.ready.shard.unwrap_or([1, 1]);
-        for guild_entry in cache.guilds.iter() {
-            let guild = guild_entry.key();
+        for guild in cache.backend.guild_ids() {
             // Only handle data for our shard.
             if crate::utils::shard_id(guild.0, shard_data[1]) == shard_data[0]
-                && !ready_guilds_hashset.contains(guild)
+                && !ready_guilds_hashset.contains(&guild)
             {
-                guilds_to_remove.push(*guild);
+                guilds_to_remove.push(guild);
             }
         }
         if !guilds_to_remove.is_empty() {
             for guild in guilds_to_remove {
-                cache.guilds.remove(&guild);
+                cache.backend.remove_guild(guild);
             }
         }
 
         // `ready.private_channels` will always be empty, and possibly be removed in the future.
         // So don't handle it at all.
 
+        let cache_presences = cache.settings.read().cache_presences;
+
         for (user_id, presence) in &mut ready.presences {
             if let Some(user) = presence.user.to_user() {
                 cache.update_user_entry(&user);
@@ -620,7 +633,9 @@ impl CacheUpdate for ReadyEvent {
                 presence.user.update_with_user(user);
             }
 
-            cache.presences.insert(*user_id, presence.clone());
+            if cache_presences {
+                cache.backend.insert_presence(presence.clone());
+            }
         }
 
         *cache.shard_count.write() = ready.shard.map_or(1, |s| s[1]);
@@ -636,14 +651,16 @@ impl CacheUpdate for ThreadCreateEvent {
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
         let (guild_id, thread_id) = (self.thread.guild_id, self.thread.id);
 
-        cache.guilds.get_mut(&guild_id).and_then(|mut g| {
+        let mut old_thread = None;
+        cache.backend.update_guild(guild_id, &mut |g| {
             if let Some(i) = g.threads.iter().position(|e| e.id == thread_id) {
-                Some(std::mem::replace(&mut g.threads[i], self.thread.clone()))
+                old_thread = Some(std::mem::replace(&mut g.threads[i], self.thread.clone()));
             } else {
                 g.threads.push(self.thread.clone());
-                None
             }
-        })
+        });
+
+        old_thread
     }
 }
 
@@ -653,14 +670,16 @@ impl CacheUpdate for ThreadUpdateEvent {
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
         let (guild_id, thread_id) = (self.thread.guild_id, self.thread.id);
 
-        cache.guilds.get_mut(&guild_id).and_then(|mut g| {
+        let mut old_thread = None;
+        cache.backend.update_guild(guild_id, &mut |g| {
             if let Some(i) = g.threads.iter().position(|e| e.id == thread_id) {
-                Some(std::mem::replace(&mut g.threads[i], self.thread.clone()))
+                old_thread = Some(std::mem::replace(&mut g.threads[i], self.thread.clone()));
             } else {
                 g.threads.push(self.thread.clone());
-                None
             }
-        })
+        });
+
+        old_thread
     }
 }
 
@@ -670,9 +689,13 @@ impl CacheUpdate for ThreadDeleteEvent {
     fn update(&mut self, cache: &Cache) -> Option<Self::Output> {
         let (guild_id, thread_id) = (self.thread.guild_id, self.thread.id);
 
-        cache.guilds.get_mut(&guild_id).and_then(|mut g| {
-            g.threads.iter().position(|e| e.id == thread_id).map(|i| g.threads.remove(i))
-        })
+        let mut old_thread = None;
+        cache.backend.update_guild(guild_id, &mut |g| {
+            old_thread =
+                g.threads.iter().position(|e| e.id == thread_id).map(|i| g.threads.remove(i));
+        });
+
+        old_thread
     }
 }
 
@@ -689,24 +712,30 @@ impl CacheUpdate for VoiceStateUpdateEvent {
     type Output = VoiceState;
 
     fn update(&mut self, cache: &Cache) -> Option<VoiceState> {
-        if let Some(guild_id) = self.voice_state.guild_id {
-            if let Some(mut guild) = cache.guilds.get_mut(&guild_id) {
-                if let Some(member) = &self.voice_state.member {
-                    guild.members.insert(member.user.id, member.clone());
-                }
+        let guild_id = self.voice_state.guild_id?;
+        let voice_state = &self.voice_state;
 
-                if self.voice_state.channel_id.is_some() {
-                    // Update or add to the voice state list
-                    guild.voice_states.insert(self.voice_state.user_id, self.voice_state.clone())
-                } else {
-                    // Remove the user from the voice state list
-                    guild.voice_states.remove(&self.voice_state.user_id)
-                }
+        if !cache.settings.read().cache_voice_states {
+            return None;
+        }
+
+        let mut old_state = None;
+        let guild_exists = cache.backend.update_guild(guild_id, &mut |guild| {
+            old_state = if voice_state.channel_id.is_some() {
+                // Update or add to the voice state list
+                guild.voice_states.insert(voice_state.user_id, voice_state.clone())
             } else {
-                None
+                // Remove the user from the voice state list
+                guild.voice_states.remove(&voice_state.user_id)
+            };
+        });
+
+        if guild_exists {
+            if let Some(member) = &self.voice_state.member {
+                cache.update_member_entry(member);
             }
-        } else {
-            None
         }
+
+        old_state
     }
 }
@@ -31,14 +31,13 @@
 //! [`http`]: crate::http
 
 use std::collections::hash_map::RandomState;
-use std::collections::{HashMap, VecDeque};
-use std::hash::BuildHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
 use std::str::FromStr;
-#[cfg(feature = "temp_cache")]
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dashmap::iter::Iter;
-use dashmap::mapref::entry::Entry;
 use dashmap::mapref::multiple::RefMulti;
 use dashmap::{DashMap, DashSet};
 #[cfg(feature = "temp_cache")]
@@ -47,15 +46,15 @@ use parking_lot::RwLock;
 use tracing::instrument;
 
 use crate::model::prelude::*;
+mod backend;
 mod cache_update;
 mod event;
 mod settings;
 
+pub use self::backend::{CacheBackend, DashMapBackend};
 pub use self::cache_update::CacheUpdate;
 pub use self::settings::Settings;
 
-type MessageCache = DashMap<ChannelId, DashMap<MessageId, Message>>;
-
 pub trait FromStrAndCache: Sized {
     type Err;
 
@@ -114,6 +113,25 @@ impl<'a, S: 'a + BuildHasher + Clone> Iterator for MessageIterator<'a, S> {
     }
 }
 
+/// Iterator given to the selector closure in [`Cache::channel_message_refs_field`].
+///
+/// Unlike [`MessageIterator`], it yields plain references to the messages, and
+/// works with any [`CacheBackend`].
+#[derive(Clone)]
+pub struct MessageRefIterator<'a>(std::iter::Copied<std::slice::Iter<'a, &'a Message>>);
+
+impl<'a> Iterator for MessageRefIterator<'a> {
+    type Item = &'a Message;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
 /// A cache containing data received from [`Shard`]s.
 ///
 /// Using the cache allows to avoid REST API requests via the [`http`] module
@@ -126,31 +144,16 @@ impl<'a, S: 'a + BuildHasher + Clone> Iterator for MessageIterator<'a, S> {
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Cache {
-    /// A map of channels in [`Guild`]s that the current user has received data
-    /// for.
+    /// The backend storing guilds, channels, members, users, messages,
+    /// presences and roles.
     ///
-    /// When a [`Event::GuildDelete`] or [`Event::GuildUnavailable`] is
-    /// received and processed by the cache, the relevant channels are also
-    /// removed from this map.
-    pub(crate) channels: DashMap<ChannelId, GuildChannel>,
+    /// Refer to [`CacheBackend`] for more information.
+    pub(crate) backend: Arc<dyn CacheBackend>,
     /// Cache of channels that have been fetched via to_channel.
     ///
     /// Each value has a maximum TTL of 1 hour.
     #[cfg(feature = "temp_cache")]
     pub(crate) temp_channels: DashCache<ChannelId, GuildChannel>,
-    /// A map of channel categories.
-    pub(crate) categories: DashMap<ChannelId, ChannelCategory>,
-    /// A map of guilds with full data available. This includes data like
-    /// [`Role`]s and [`Emoji`]s that are not available through the REST API.
-    pub(crate) guilds: DashMap<GuildId, Guild>,
-    pub(crate) messages: MessageCache,
-    /// A map of users' presences. This is updated in real-time. Note that
-    /// status updates are often "eaten" by the gateway, and this should not
-    /// be treated as being entirely 100% accurate.
-    pub(crate) presences: DashMap<UserId, Presence>,
-    /// A map of direct message channels that the current user has open with
-    /// other users.
-    pub(crate) private_channels: DashMap<ChannelId, PrivateChannel>,
     /// The total number of shards being used by the bot.
     pub(crate) shard_count: RwLock<u64>,
     /// A list of guilds which are "unavailable". Refer to the documentation for
@@ -168,32 +171,17 @@ pub struct Cache {
     ///
     /// Refer to the documentation for [`CurrentUser`] for more information.
     pub(crate) user: RwLock<CurrentUser>,
-    /// A map of users that the current user sees.
-    ///
-    /// Users are added to - and updated from - this map via the following
-    /// received events:
-    ///
-    /// - [`GuildMemberAdd`][`GuildMemberAddEvent`]
-    /// - [`GuildMemberRemove`][`GuildMemberRemoveEvent`]
-    /// - [`GuildMembersChunk`][`GuildMembersChunkEvent`]
-    /// - [`PresenceUpdate`][`PresenceUpdateEvent`]
-    /// - [`Ready`][`ReadyEvent`]
-    ///
-    /// Note, however, that users are _not_ removed from the map on removal
-    /// events such as [`GuildMemberRemove`][`GuildMemberRemoveEvent`], as other
-    /// structs such as members or recipients may still exist.
-    pub(crate) users: DashMap<UserId, User>,
-    /// Queue of message IDs for each channel.
-    ///
-    /// This is simply a vecdeque so we can keep track of the order of messages
-    /// inserted into the cache. When a maximum number of messages are in a
-    /// channel's cache, we can pop the front and remove that ID from the cache.
-    pub(crate) message_queue: DashMap<ChannelId, VecDeque<MessageId>>,
     /// Cache of users who have been fetched from `to_user`.
     ///
     /// Each value has a max TTL of 1 hour.
     #[cfg(feature = "temp_cache")]
     pub(crate) temp_users: DashCache<UserId, User>,
+    /// When each user was last updated, tracked if [`Settings::user_ttl`] or
+    /// [`Settings::max_users`] is set.
+    pub(crate) user_last_seen: DashMap<UserId, Instant>,
+    /// When each member was last updated, tracked if [`Settings::member_ttl`]
+    /// or [`Settings::max_members`] is set.
+    pub(crate) member_last_seen: DashMap<(GuildId, UserId), Instant>,
     /// The settings for the cache.
     settings: RwLock<Settings>,
 }
@@ -220,7 +208,10 @@ impl Cache {
     /// ```
     #[instrument]
     pub fn new_with_settings(settings: Settings) -> Self {
+        let backend = settings.backend.clone().unwrap_or_else(|| Arc::new(DashMapBackend::new()));
+
         Self {
+            backend,
             settings: RwLock::new(settings),
             ..Default::default()
         }
@@ -276,14 +267,14 @@ impl Cache {
     pub fn unknown_members(&self) -> u64 {
         let mut total = 0;
 
-        for guild_entry in self.guilds.iter() {
-            let guild = guild_entry.value();
-
-            let members = guild.members.len() as u64;
+        for guild_id in self.backend.guild_ids() {
+            self.backend.with_guild(guild_id, &mut |guild| {
+                let members = guild.members.len() as u64;
 
-            if guild.member_count > members {
-                total += guild.member_count - members;
-            }
+                if guild.member_count > members {
+                    total += guild.member_count - members;
+                }
+            });
         }
 
         total
@@ -308,7 +299,7 @@ impl Cache {
     /// println!("There are {} private channels", amount);
     /// ```
     pub fn private_channels(&self) -> DashMap<ChannelId, PrivateChannel> {
-        self.private_channels.clone()
+        self.backend.private_channels().into_iter().map(|c| (c.id, c)).collect()
     }
 
     /// Fetches a vector of all [`Guild`]s' Ids that are stored in the cache.
@@ -341,7 +332,7 @@ impl Cache {
     /// [`Shard`]: crate::gateway::Shard
     pub fn guilds(&self) -> Vec<GuildId> {
         let chain = self.unavailable_guilds.clone().into_iter();
-        self.guilds.iter().map(|i| *i.key()).chain(chain).collect()
+        self.backend.guild_ids().into_iter().chain(chain).collect()
     }
 
     /// Retrieves a [`Channel`] from the cache based on the given Id.
@@ -359,8 +350,7 @@ impl Cache {
     }
 
     fn _channel(&self, id: ChannelId) -> Option<Channel> {
-        if let Some(channel) = self.channels.get(&id) {
-            let channel = channel.clone();
+        if let Some(channel) = self.backend.channel(id) {
             return Some(Channel::Guild(channel));
         }
 
@@ -371,8 +361,8 @@ impl Cache {
             }
         }
 
-        if let Some(private_channel) = self.private_channels.get(&id) {
-            return Some(Channel::Private(private_channel.clone()));
+        if let Some(private_channel) = self.backend.private_channel(id) {
+            return Some(Channel::Private(private_channel));
         }
 
         None
@@ -390,15 +380,46 @@ impl Cache {
     ///         .collect::<Vec<_>>()
     /// });
     /// ```
+    ///
+    /// **Note**: The messages are cloned out of the [`CacheBackend`] first.
+    /// Use [`Self::channel_message_refs_field`] to avoid this.
     pub fn channel_messages_field<T>(
         &self,
         channel_id: impl Into<ChannelId>,
         selector: impl FnOnce(MessageIterator<'_, RandomState>) -> T,
     ) -> Option<T> {
-        let msg = self.messages.get(&channel_id.into())?;
-        let message_iter = MessageIterator(msg.iter());
+        let messages = self.backend.channel_messages(channel_id.into())?;
+        let messages = messages.into_iter().map(|m| (m.id, m)).collect::<DashMap<_, _>>();
 
-        Some(selector(message_iter))
+        Some(selector(MessageIterator(messages.iter())))
+    }
+
+    /// This method allows to extract specific data from the cached messages of a channel by
+    /// providing a `selector` closure picking what you want to extract from the messages
+    /// iterator of a given channel, without cloning them.
+    ///
+    /// ```rust,no_run
+    /// # let cache: serenity::cache::Cache = todo!();
+    /// // Find all messages by user ID 8 in channel ID 7
+    /// let messages_by_user = cache.channel_message_refs_field(7, |msgs| {
+    ///     msgs.filter(|m| m.author.id == 8).cloned().collect::<Vec<_>>()
+    /// });
+    /// ```
+    pub fn channel_message_refs_field<T>(
+        &self,
+        channel_id: impl Into<ChannelId>,
+        selector: impl FnOnce(MessageRefIterator<'_>) -> T,
+    ) -> Option<T> {
+        let mut selector = Some(selector);
+        let mut selected = None;
+
+        self.backend.with_channel_messages(channel_id.into(), &mut |messages| {
+            if let Some(selector) = selector.take() {
+                selected = Some(selector(MessageRefIterator(messages.iter().copied())));
+            }
+        });
+
+        selected
     }
 
     /// Clones an entire guild from the cache based on the given `id`.
@@ -425,7 +446,7 @@ impl Cache {
     }
 
     fn _guild(&self, id: GuildId) -> Option<Guild> {
-        self.guilds.get(&id).map(|i| i.clone())
+        self.backend.guild(id)
     }
 
     /// This method allows to select a field of the guild instead of
@@ -456,14 +477,12 @@ impl Cache {
     where
         Fun: FnOnce(&Guild) -> Ret,
     {
-        let guild = self.guilds.get(&id)?;
-
-        Some(field_accessor(&guild))
+        select(field_accessor, |f| self.backend.with_guild(id, f))
     }
 
     /// Returns the number of cached guilds.
     pub fn guild_count(&self) -> usize {
-        self.guilds.len()
+        self.backend.guild_count()
     }
 
     /// Retrieves a reference to a [`Guild`]'s channel. Unlike [`Self::channel`],
@@ -520,7 +539,7 @@ impl Cache {
     }
 
     fn _guild_channel(&self, id: ChannelId) -> Option<GuildChannel> {
-        self.channels.get(&id).map(|i| i.clone())
+        self.backend.channel(id)
     }
 
     /// This method allows to only clone a field of the guild channel instead of
@@ -555,9 +574,7 @@ impl Cache {
     where
         Fun: FnOnce(&GuildChannel) -> Ret,
     {
-        let channel = self.channels.get(&id)?;
-
-        Some(field_selector(&channel))
+        select(field_selector, |f| self.backend.with_channel(id, f))
     }
 
     /// Retrieves a [`Guild`]'s member from the cache based on the guild's and
@@ -620,10 +637,7 @@ impl Cache {
     }
 
     fn _member(&self, guild_id: GuildId, user_id: UserId) -> Option<Member> {
-        match self.guilds.get(&guild_id) {
-            Some(guild) => guild.members.get(&user_id).cloned(),
-            None => None,
-        }
+        self.backend.member(guild_id, user_id)
     }
 
     /// This method allows to only clone a field of a member instead of
@@ -664,10 +678,7 @@ impl Cache {
     where
         Fun: FnOnce(&Member) -> Ret,
     {
-        let guild = self.guilds.get(&guild_id)?;
-        let member = guild.members.get(&user_id)?;
-
-        Some(field_selector(member))
+        select(field_selector, |f| self.backend.with_member(guild_id, user_id, f))
     }
 
     #[inline]
@@ -676,7 +687,7 @@ impl Cache {
     }
 
     fn _guild_roles(&self, guild_id: GuildId) -> Option<HashMap<RoleId, Role>> {
-        self.guilds.get(&guild_id).map(|g| g.roles.clone())
+        self.backend.roles(guild_id)
     }
 
     /// This method clones and returns all unavailable guilds.
@@ -695,7 +706,7 @@ impl Cache {
     }
 
     fn _guild_channels(&self, guild_id: GuildId) -> Option<DashMap<ChannelId, GuildChannel>> {
-        self.guilds.get(&guild_id).map(|g| {
+        self.guild_field(guild_id, |g| {
             g.channels
                 .iter()
                 .filter_map(|c| match c.1 {
@@ -708,7 +719,7 @@ impl Cache {
 
     /// Returns the number of guild channels in the cache.
     pub fn guild_channel_count(&self) -> usize {
-        self.channels.len()
+        self.backend.channel_count()
     }
 
     /// This method returns all categories from a guild of with the given `guild_id`.
@@ -721,7 +732,7 @@ impl Cache {
     }
 
     fn _guild_categories(&self, guild_id: GuildId) -> Option<DashMap<ChannelId, ChannelCategory>> {
-        self.guilds.get(&guild_id).map(|g| {
+        self.guild_field(guild_id, |g| {
             g.channels
                 .iter()
                 .filter_map(|c| match c.1 {
@@ -772,9 +783,7 @@ impl Cache {
     }
 
     fn _message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
-        self.messages
-            .get(&channel_id)
-            .and_then(|messages| messages.get(&message_id).map(|i| i.clone()))
+        self.backend.message(channel_id, message_id)
     }
 
     /// Retrieves a [`PrivateChannel`] from the cache's [`Self::private_channels`]
@@ -804,7 +813,7 @@ impl Cache {
     }
 
     fn _private_channel(&self, channel_id: ChannelId) -> Option<PrivateChannel> {
-        self.private_channels.get(&channel_id).map(|i| i.clone())
+        self.backend.private_channel(channel_id)
     }
 
     /// Retrieves a [`Guild`]'s role by their Ids.
@@ -838,7 +847,7 @@ impl Cache {
     }
 
     fn _role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role> {
-        self.guilds.get(&guild_id).and_then(|g| g.roles.get(&role_id).cloned())
+        self.backend.role(guild_id, role_id)
     }
 
     /// Returns the settings.
@@ -894,28 +903,24 @@ impl Cache {
 
     #[cfg(feature = "temp_cache")]
     fn _user(&self, user_id: UserId) -> Option<User> {
-        if let Some(user) = self.users.get(&user_id) {
-            Some(user.clone())
-        } else {
-            self.temp_users.get(&user_id)
-        }
+        self.backend.user(user_id).or_else(|| self.temp_users.get(&user_id))
     }
 
     #[cfg(not(feature = "temp_cache"))]
     fn _user(&self, user_id: UserId) -> Option<User> {
-        self.users.get(&user_id).map(|u| u.clone())
+        self.backend.user(user_id)
     }
 
     /// Clones all users and returns them.
     #[inline]
     pub fn users(&self) -> DashMap<UserId, User> {
-        self.users.clone()
+        self.backend.users().into_iter().map(|u| (u.id, u)).collect()
     }
 
     /// Returns the amount of cached users.
     #[inline]
     pub fn user_count(&self) -> usize {
-        self.users.len()
+        self.backend.user_count()
     }
 
     /// Clones a category matching the `channel_id` and returns it.
@@ -925,25 +930,25 @@ impl Cache {
     }
 
     fn _category(&self, channel_id: ChannelId) -> Option<ChannelCategory> {
-        self.categories.get(&channel_id).map(|i| i.clone())
+        self.backend.category(channel_id)
     }
 
     /// Clones all categories and returns them.
     #[inline]
     pub fn categories(&self) -> DashMap<ChannelId, ChannelCategory> {
-        self.categories.clone()
+        self.backend.categories().into_iter().map(|c| (c.id, c)).collect()
     }
 
     /// Returns the amount of cached categories.
     #[inline]
     pub fn category_count(&self) -> usize {
-        self.categories.len()
+        self.backend.category_count()
     }
 
     /// Returns the optional category ID of a channel.
     #[inline]
     pub fn channel_category_id(&self, channel_id: ChannelId) -> Option<ChannelId> {
-        self.categories.get(&channel_id).map(|category| category.id)
+        self.backend.category(channel_id).map(|category| category.id)
     }
 
     /// This method clones and returns the user used by the bot.
@@ -998,37 +1003,191 @@ impl Cache {
         e.update(self)
     }
 
+    /// Removes users and members which expired according to the cache's
+    /// [`Settings`].
+    ///
+    /// This removes, in order:
+    ///
+    /// 1. Members which were not updated within [`Settings::member_ttl`], as
+    /// well as the least recently updated members of each guild beyond
+    /// [`Settings::max_members`];
+    /// 2. Users which are neither a member of a cached guild nor the recipient
+    /// of a cached private channel, such as users left behind by a
+    /// [`GuildMemberRemoveEvent`];
+    /// 3. Users which were not updated within [`Settings::user_ttl`], as well
+    /// as the least recently updated users beyond [`Settings::max_users`].
+    ///
+    /// The current user is never removed.
+    ///
+    /// The [`Client`] calls this periodically if
+    /// [`Settings::sweep_interval`] is set.
+    ///
+    /// [`Client`]: crate::Client
+    #[instrument(skip(self))]
+    pub fn sweep(&self) {
+        let settings = self.settings();
+        let current_user_id = self.current_user_id();
+        let guild_ids = self.backend.guild_ids();
+
+        if settings.member_ttl.is_some() || settings.max_members.is_some() {
+            for &guild_id in &guild_ids {
+                let members: Vec<_> = self
+                    .guild_field(guild_id, |g| {
+                        g.members.keys().copied().filter(|id| *id != current_user_id).collect()
+                    })
+                    .unwrap_or_default();
+
+                let expired = expired(
+                    members.into_iter().map(|user_id| (guild_id, user_id)),
+                    &self.member_last_seen,
+                    settings.member_ttl,
+                    settings.max_members,
+                );
+
+                for key in expired {
+                    self.backend.remove_member(key.0, key.1);
+                    self.member_last_seen.remove(&key);
+                }
+            }
+
+            let guilds = guild_ids.iter().copied().collect::<HashSet<_>>();
+            self.member_last_seen.retain(|key, _| guilds.contains(&key.0));
+        }
+
+        let mut referenced = HashSet::new();
+        referenced.insert(current_user_id);
+
+        for &guild_id in &guild_ids {
+            self.backend.with_guild(guild_id, &mut |g| referenced.extend(g.members.keys()));
+        }
+
+        for channel in self.backend.private_channels() {
+            referenced.insert(channel.recipient.id);
+        }
+
+        let (users, orphans): (Vec<_>, Vec<_>) =
+            self.backend.user_ids().into_iter().partition(|id| referenced.contains(id));
+
+        for user_id in orphans {
+            self.backend.remove_user(user_id);
+            self.user_last_seen.remove(&user_id);
+        }
+
+        if settings.user_ttl.is_some() || settings.max_users.is_some() {
+            let users = users.into_iter().filter(|id| *id != current_user_id);
+
+            for user_id in
+                expired(users, &self.user_last_seen, settings.user_ttl, settings.max_users)
+            {
+                self.backend.remove_user(user_id);
+                self.user_last_seen.remove(&user_id);
+            }
+        }
+    }
+
     pub(crate) fn update_user_entry(&self, user: &User) {
-        match self.users.entry(user.id) {
-            Entry::Vacant(e) => {
-                e.insert(user.clone());
-            },
-            Entry::Occupied(mut e) => {
-                e.get_mut().clone_from(user);
-            },
+        let settings = self.settings.read();
+
+        if !settings.cache_users {
+            return;
+        }
+
+        if settings.user_ttl.is_some() || settings.max_users.is_some() {
+            self.user_last_seen.insert(user.id, Instant::now());
+        }
+
+        self.backend.update_user_entry(user);
+    }
+
+    /// Records that a member was updated, for [`Settings::member_ttl`] and
+    /// [`Settings::max_members`].
+    pub(crate) fn touch_member(&self, guild_id: GuildId, user_id: UserId) {
+        let settings = self.settings.read();
+
+        if settings.member_ttl.is_some() || settings.max_members.is_some() {
+            self.member_last_seen.insert((guild_id, user_id), Instant::now());
+        }
+    }
+
+    /// Inserts a member into its guild, if members are cached.
+    pub(crate) fn update_member_entry(&self, member: &Member) -> Option<Member> {
+        if !self.settings.read().cache_members {
+            return None;
+        }
+
+        self.touch_member(member.guild_id, member.user.id);
+        self.backend.insert_member(member.clone())
+    }
+}
+
+/// Returns the keys which were last seen longer ago than `ttl`, as well as the
+/// least recently seen keys beyond `max`.
+///
+/// Keys which were never seen are recorded as seen now.
+fn expired<K: Copy + Eq + Hash>(
+    keys: impl Iterator<Item = K>,
+    last_seen: &DashMap<K, Instant>,
+    ttl: Option<Duration>,
+    max: Option<usize>,
+) -> Vec<K> {
+    let now = Instant::now();
+    let mut expired = Vec::new();
+    let mut kept = Vec::new();
+
+    for key in keys {
+        let seen = *last_seen.entry(key).or_insert(now);
+
+        if ttl.map_or(false, |ttl| now.duration_since(seen) > ttl) {
+            expired.push(key);
+        } else {
+            kept.push((seen, key));
+        }
+    }
+
+    if let Some(max) = max {
+        if kept.len() > max {
+            kept.sort_unstable_by_key(|(seen, _)| *seen);
+
+            let excess = kept.len() - max;
+            expired.extend(kept.drain(..excess).map(|(_, key)| key));
         }
     }
+
+    expired
+}
+
+/// Calls a [`FnOnce`] selector through one of the [`FnMut`] visitors taken by
+/// [`CacheBackend`], returning its result if the visitor was called.
+fn select<T, Ret>(
+    selector: impl FnOnce(&T) -> Ret,
+    visit: impl FnOnce(&mut dyn FnMut(&T)) -> bool,
+) -> Option<Ret> {
+    let mut selector = Some(selector);
+    let mut ret = None;
+
+    visit(&mut |value| {
+        if let Some(selector) = selector.take() {
+            ret = Some(selector(value));
+        }
+    });
+
+    ret
 }
 
 impl Default for Cache {
     fn default() -> Cache {
         Cache {
-            channels: DashMap::default(),
+            backend: Arc::new(DashMapBackend::new()),
             #[cfg(feature = "temp_cache")]
             temp_channels: DashCache::builder().time_to_live(Duration::from_secs(60 * 60)).build(),
-            categories: DashMap::default(),
-            guilds: DashMap::default(),
-            messages: DashMap::default(),
-            presences: DashMap::default(),
-            private_channels: DashMap::with_capacity(128),
             settings: RwLock::new(Settings::default()),
             shard_count: RwLock::new(1),
             unavailable_guilds: DashSet::default(),
             user: RwLock::new(CurrentUser::default()),
-            users: DashMap::default(),
             #[cfg(feature = "temp_cache")]
             temp_users: DashCache::builder().time_to_live(Duration::from_secs(60 * 60)).build(),
-            message_queue: DashMap::default(),
+            user_last_seen: DashMap::default(),
+            member_last_seen: DashMap::default(),
         }
     }
 }
@@ -1036,8 +1195,9 @@ impl Default for Cache {
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
+    use std::sync::Arc;
 
-    use crate::cache::{Cache, CacheUpdate, Settings};
+    use crate::cache::{Cache, CacheBackend, CacheUpdate, DashMapBackend, Settings};
     use crate::json::from_number;
     use crate::model::prelude::*;
 
@@ -1095,29 +1255,42 @@ mod test {
         };
 
         // Check that the channel cache doesn't exist.
-        assert!(!cache.messages.contains_key(&event.message.channel_id));
+        assert!(cache.backend.channel_messages(event.message.channel_id).is_none());
         // Add first message, none because message ID 2 doesn't already exist.
         assert!(event.update(&cache).is_none());
         // None, it only returns the oldest message if the cache was already full.
         assert!(event.update(&cache).is_none());
         // Assert there's only 1 message in the channel's message cache.
-        assert_eq!(cache.messages.get(&event.message.channel_id).unwrap().len(), 1);
+        assert_eq!(cache.backend.channel_messages(event.message.channel_id).unwrap().len(), 1);
 
         // Add a second message, assert that channel message cache length is 2.
         event.message.id = MessageId(4);
         assert!(event.update(&cache).is_none());
-        assert_eq!(cache.messages.get(&event.message.channel_id).unwrap().len(), 2);
+        assert_eq!(cache.backend.channel_messages(event.message.channel_id).unwrap().len(), 2);
 
         // Add a third message, the first should now be removed.
         event.message.id = MessageId(5);
         assert!(event.update(&cache).is_some());
 
         {
-            let channel = cache.messages.get(&event.message.channel_id).unwrap();
+            let ids = cache
+                .channel_messages_field(event.message.channel_id, |msgs| {
+                    msgs.map(|m| m.id).collect::<Vec<_>>()
+                })
+                .unwrap();
 
-            assert_eq!(channel.len(), 2);
+            assert_eq!(ids.len(), 2);
             // Check that the first message is now removed.
-            assert!(!channel.contains_key(&MessageId(3)));
+            assert!(!ids.contains(&MessageId(3)));
+
+            let ids = cache
+                .channel_message_refs_field(event.message.channel_id, |msgs| {
+                    msgs.map(|m| m.id).collect::<Vec<_>>()
+                })
+                .unwrap();
+
+            assert_eq!(ids.len(), 2);
+            assert!(!ids.contains(&MessageId(3)));
         }
 
         let channel = Channel::Guild(GuildChannel {
@@ -1158,7 +1331,7 @@ mod test {
             channel: channel.clone(),
         };
         assert!(cache.update(&mut delete).is_none());
-        assert!(!cache.messages.contains_key(&delete.channel.id()));
+        assert!(cache.backend.channel_messages(delete.channel.id()).is_none());
 
         // Test deletion of a guild channel's message cache when a GuildDeleteEvent
         // is received.
@@ -1231,6 +1404,169 @@ mod test {
         assert!(cache.update(&mut guild_delete).is_some());
 
         // Assert that the channel's message cache no longer exists.
-        assert!(!cache.messages.contains_key(&ChannelId(2)));
+        assert!(cache.backend.channel_messages(ChannelId(2)).is_none());
+    }
+
+    #[test]
+    fn test_cache_shared_backend() {
+        let backend = Arc::new(DashMapBackend::new());
+
+        let mut settings = Settings::new();
+        settings.backend_arc(Arc::clone(&backend));
+
+        let first = Cache::new_with_settings(settings.clone());
+        let second = Cache::new_with_settings(settings);
+
+        let user = User {
+            id: UserId(2),
+            avatar: None,
+            bot: false,
+            discriminator: 1,
+            name: "user 1".to_owned(),
+            public_flags: None,
+            banner: None,
+            member: None,
+            accent_colour: None,
+        };
+
+        first.update_user_entry(&user);
+
+        assert_eq!(second.user(UserId(2)).map(|u| u.name), Some(user.name));
+        assert_eq!(backend.user_count(), 1);
+        assert!(Cache::new().user(UserId(2)).is_none());
+    }
+
+    fn guild_with_members(user_ids: &[u64]) -> GuildCreateEvent {
+        let members = user_ids
+            .iter()
+            .map(|&id| {
+                let member = Member {
+                    deaf: false,
+                    guild_id: GuildId(1),
+                    joined_at: None,
+                    mute: false,
+                    nick: None,
+                    roles: vec![],
+                    user: User {
+                        id: UserId(id),
+                        avatar: None,
+                        bot: false,
+                        discriminator: 1,
+                        name: format!("user {}", id),
+                        public_flags: None,
+                        banner: None,
+                        member: None,
+                        accent_colour: None,
+                    },
+                    pending: false,
+                    premium_since: None,
+                    permissions: None,
+                    avatar: None,
+                    communication_disabled_until: None,
+                };
+
+                (UserId(id), member)
+            })
+            .collect();
+
+        GuildCreateEvent {
+            guild: Guild {
+                id: GuildId(1),
+                afk_channel_id: None,
+                afk_timeout: 0,
+                application_id: None,
+                default_message_notifications: DefaultMessageNotificationLevel::All,
+                emojis: HashMap::new(),
+                explicit_content_filter: ExplicitContentFilter::None,
+                features: vec![],
+                icon: None,
+                joined_at: Timestamp::now(),
+                large: false,
+                member_count: user_ids.len() as u64,
+                members,
+                mfa_level: MfaLevel::None,
+                name: String::new(),
+                owner_id: UserId(2),
+                presences: HashMap::new(),
+                roles: HashMap::new(),
+                splash: None,
+                discovery_splash: None,
+                system_channel_id: None,
+                system_channel_flags: SystemChannelFlags::default(),
+                rules_channel_id: None,
+                public_updates_channel_id: None,
+                verification_level: VerificationLevel::Low,
+                voice_states: HashMap::new(),
+                description: None,
+                premium_tier: PremiumTier::Tier0,
+                channels: HashMap::new(),
+                premium_subscription_count: 0,
+                banner: None,
+                vanity_url_code: None,
+                preferred_locale: "en-US".to_string(),
+                welcome_screen: None,
+                approximate_member_count: None,
+                approximate_presence_count: None,
+                nsfw_level: NsfwLevel::Default,
+                max_video_channel_users: None,
+                max_presences: None,
+                max_members: None,
+                widget_enabled: Some(false),
+                widget_channel_id: None,
+                stage_instances: vec![],
+                threads: vec![],
+                stickers: HashMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_cache_sweep() {
+        let mut settings = Settings::new();
+        settings.max_members(2);
+        let cache = Cache::new_with_settings(settings);
+
+        cache.update(&mut guild_with_members(&[2, 3, 4]));
+        assert_eq!(cache.user_count(), 3);
+
+        // The least recently updated member is evicted, then its user is no
+        // longer referenced by anything.
+        cache.sweep();
+        assert_eq!(cache.guild_field(GuildId(1), |g| g.members.len()), Some(2));
+        assert_eq!(cache.user_count(), 2);
+
+        let mut remaining = cache
+            .guild_field(GuildId(1), |g| {
+                g.members.values().map(|m| m.user.clone()).collect::<Vec<_>>()
+            })
+            .unwrap();
+        remaining.sort_by_key(|u| u.id);
+        assert_eq!(remaining.len(), 2);
+        let (removed, kept) = (remaining[0].clone(), remaining[1].id);
+
+        let mut event = GuildMemberRemoveEvent {
+            guild_id: GuildId(1),
+            user: removed.clone(),
+        };
+        cache.update(&mut event);
+
+        cache.sweep();
+        assert_eq!(cache.user_count(), 1);
+        assert!(cache.user(removed.id).is_none());
+        assert!(cache.user(kept).is_some());
+    }
+
+    #[test]
+    fn test_cache_members_disabled() {
+        let mut settings = Settings::new();
+        settings.cache_members(false);
+        let cache = Cache::new_with_settings(settings);
+
+        cache.update(&mut guild_with_members(&[2, 3]));
+        assert_eq!(cache.guild_field(GuildId(1), |g| g.members.len()), Some(0));
+        assert_eq!(cache.user_count(), 2);
+
+        cache.sweep();
+        assert_eq!(cache.user_count(), 0);
     }
 }
@@ -1,3 +1,8 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::CacheBackend;
+
 /// Settings for the cache.
 ///
 /// # Examples
@@ -10,13 +15,125 @@
 /// let mut settings = CacheSettings::new();
 /// settings.max_messages(10);
 /// ```
-#[derive(Clone, Debug, Default)]
+///
+/// Disable caching of presences and emojis, and remove users which were not
+/// updated for an hour every ten minutes:
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use serenity::cache::Settings as CacheSettings;
+///
+/// let mut settings = CacheSettings::new();
+/// settings
+///     .cache_presences(false)
+///     .cache_emojis(false)
+///     .user_ttl(Duration::from_secs(60 * 60))
+///     .sweep_interval(Duration::from_secs(10 * 60));
+/// ```
+#[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct Settings {
     /// The maximum number of messages to store in a channel's message cache.
     ///
     /// Defaults to 0.
     pub max_messages: usize,
+    /// Whether to cache messages. Messages are only cached if
+    /// [`max_messages`] is non-zero as well.
+    ///
+    /// [`max_messages`]: #structfield.max_messages
+    ///
+    /// Defaults to true.
+    pub cache_messages: bool,
+    /// Whether to cache user presences, both globally and per guild.
+    ///
+    /// Defaults to true.
+    pub cache_presences: bool,
+    /// Whether to cache users.
+    ///
+    /// Defaults to true.
+    pub cache_users: bool,
+    /// Whether to cache guild members.
+    ///
+    /// **Note**: Guild permission checks rely on cached members, and will
+    /// fail if members are not cached.
+    ///
+    /// Defaults to true.
+    pub cache_members: bool,
+    /// Whether to cache guild emojis.
+    ///
+    /// Defaults to true.
+    pub cache_emojis: bool,
+    /// Whether to cache guild stickers.
+    ///
+    /// Defaults to true.
+    pub cache_stickers: bool,
+    /// Whether to cache voice states.
+    ///
+    /// Defaults to true.
+    pub cache_voice_states: bool,
+    /// How long a user is kept after it was last updated, before being
+    /// removed by [`Cache::sweep`].
+    ///
+    /// Defaults to `None`, keeping users forever.
+    ///
+    /// [`Cache::sweep`]: super::Cache::sweep
+    pub user_ttl: Option<Duration>,
+    /// The maximum number of users to keep. [`Cache::sweep`] removes the least
+    /// recently updated users beyond this number.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`Cache::sweep`]: super::Cache::sweep
+    pub max_users: Option<usize>,
+    /// How long a member is kept after it was last updated, before being
+    /// removed by [`Cache::sweep`].
+    ///
+    /// Defaults to `None`, keeping members forever.
+    ///
+    /// [`Cache::sweep`]: super::Cache::sweep
+    pub member_ttl: Option<Duration>,
+    /// The maximum number of members to keep per guild. [`Cache::sweep`]
+    /// removes the least recently updated members beyond this number.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`Cache::sweep`]: super::Cache::sweep
+    pub max_members: Option<usize>,
+    /// How often the [`Client`] calls [`Cache::sweep`].
+    ///
+    /// Defaults to `None`, never sweeping the cache.
+    ///
+    /// [`Client`]: crate::Client
+    /// [`Cache::sweep`]: super::Cache::sweep
+    pub sweep_interval: Option<Duration>,
+    /// The backend to store cached data in.
+    ///
+    /// Defaults to a [`DashMapBackend`] if not set.
+    ///
+    /// [`DashMapBackend`]: super::DashMapBackend
+    pub backend: Option<Arc<dyn CacheBackend>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_messages: 0,
+            cache_messages: true,
+            cache_presences: true,
+            cache_users: true,
+            cache_members: true,
+            cache_emojis: true,
+            cache_stickers: true,
+            cache_voice_states: true,
+            user_ttl: None,
+            max_users: None,
+            member_ttl: None,
+            max_members: None,
+            sweep_interval: None,
+            backend: None,
+        }
+    }
 }
 
 impl Settings {
@@ -48,4 +165,156 @@ impl Settings {
 
         self
     }
+
+    /// Sets whether to cache messages.
+    ///
+    /// Refer to [`cache_messages`] for more information.
+    ///
+    /// [`cache_messages`]: #structfield.cache_messages
+    pub fn cache_messages(&mut self, enabled: bool) -> &mut Self {
+        self.cache_messages = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache user presences.
+    ///
+    /// Refer to [`cache_presences`] for more information.
+    ///
+    /// [`cache_presences`]: #structfield.cache_presences
+    pub fn cache_presences(&mut self, enabled: bool) -> &mut Self {
+        self.cache_presences = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache users.
+    ///
+    /// Refer to [`cache_users`] for more information.
+    ///
+    /// [`cache_users`]: #structfield.cache_users
+    pub fn cache_users(&mut self, enabled: bool) -> &mut Self {
+        self.cache_users = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache guild members.
+    ///
+    /// Refer to [`cache_members`] for more information.
+    ///
+    /// [`cache_members`]: #structfield.cache_members
+    pub fn cache_members(&mut self, enabled: bool) -> &mut Self {
+        self.cache_members = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache guild emojis.
+    ///
+    /// Refer to [`cache_emojis`] for more information.
+    ///
+    /// [`cache_emojis`]: #structfield.cache_emojis
+    pub fn cache_emojis(&mut self, enabled: bool) -> &mut Self {
+        self.cache_emojis = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache guild stickers.
+    ///
+    /// Refer to [`cache_stickers`] for more information.
+    ///
+    /// [`cache_stickers`]: #structfield.cache_stickers
+    pub fn cache_stickers(&mut self, enabled: bool) -> &mut Self {
+        self.cache_stickers = enabled;
+
+        self
+    }
+
+    /// Sets whether to cache voice states.
+    ///
+    /// Refer to [`cache_voice_states`] for more information.
+    ///
+    /// [`cache_voice_states`]: #structfield.cache_voice_states
+    pub fn cache_voice_states(&mut self, enabled: bool) -> &mut Self {
+        self.cache_voice_states = enabled;
+
+        self
+    }
+
+    /// Sets how long users are kept after they were last updated.
+    ///
+    /// Refer to [`user_ttl`] for more information.
+    ///
+    /// [`user_ttl`]: #structfield.user_ttl
+    pub fn user_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.user_ttl = Some(ttl);
+
+        self
+    }
+
+    /// Sets the maximum number of users to keep.
+    ///
+    /// Refer to [`max_users`] for more information.
+    ///
+    /// [`max_users`]: #structfield.max_users
+    pub fn max_users(&mut self, max: usize) -> &mut Self {
+        self.max_users = Some(max);
+
+        self
+    }
+
+    /// Sets how long members are kept after they were last updated.
+    ///
+    /// Refer to [`member_ttl`] for more information.
+    ///
+    /// [`member_ttl`]: #structfield.member_ttl
+    pub fn member_ttl(&mut self, ttl: Duration) -> &mut Self {
+        self.member_ttl = Some(ttl);
+
+        self
+    }
+
+    /// Sets the maximum number of members to keep per guild.
+    ///
+    /// Refer to [`max_members`] for more information.
+    ///
+    /// [`max_members`]: #structfield.max_members
+    pub fn max_members(&mut self, max: usize) -> &mut Self {
+        self.max_members = Some(max);
+
+        self
+    }
+
+    /// Sets how often the [`Client`] sweeps the cache.
+    ///
+    /// Refer to [`sweep_interval`] for more information.
+    ///
+    /// [`Client`]: crate::Client
+    /// [`sweep_interval`]: #structfield.sweep_interval
+    pub fn sweep_interval(&mut self, interval: Duration) -> &mut Self {
+        self.sweep_interval = Some(interval);
+
+        self
+    }
+
+    /// Sets the backend to store cached data in.
+    ///
+    /// Refer to [`CacheBackend`] for more information.
+    pub fn backend<B: CacheBackend + 'static>(&mut self, backend: B) -> &mut Self {
+        self.backend = Some(Arc::new(backend));
+
+        self
+    }
+
+    /// Sets the backend to store cached data in. Passed by Arc.
+    ///
+    /// This allows keeping a handle to the backend, for example to share it
+    /// with another cache.
+    pub fn backend_arc<B: CacheBackend + 'static>(&mut self, backend: Arc<B>) -> &mut Self {
+        self.backend = Some(backend);
+
+        self
+    }
 }
@@ -51,16 +51,24 @@ mod shard_queuer;
 mod shard_runner;
 mod shard_runner_message;
 
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use std::time::Duration as StdDuration;
 
+use tokio::sync::Mutex;
+
 pub use self::shard_manager::{ShardManager, ShardManagerOptions};
 pub use self::shard_manager_monitor::{ShardManagerError, ShardManagerMonitor};
 pub use self::shard_messenger::ShardMessenger;
 pub use self::shard_queuer::ShardQueuer;
 pub use self::shard_runner::{ShardRunner, ShardRunnerOptions};
 pub use self::shard_runner_message::{ChunkGuildFilter, ShardRunnerMessage};
-use crate::gateway::ConnectionStage;
+use crate::gateway::{ConnectionStage, ShardSessionState};
+
+/// The session states of shards that shut down, stored by their runners for
+/// [`ShardManager::shutdown_resumable`] to pick up.
+pub(crate) type ClosedSessions = Arc<Mutex<HashMap<ShardId, ShardSessionState>>>;
 
 /// A message either for a [`ShardManager`] or a [`ShardRunner`].
 // Once we can use `Box` as part of a pattern, we will reconsider boxing.
@@ -9,6 +9,7 @@ use tracing::{info, instrument, warn};
 use typemap_rev::TypeMap;
 
 use super::{
+    ClosedSessions,
     ShardId,
     ShardManagerMessage,
     ShardManagerMonitor,
@@ -18,9 +19,11 @@ use super::{
 };
 #[cfg(feature = "voice")]
 use crate::client::bridge::voice::VoiceGatewayManager;
-use crate::client::{EventHandler, RawEventHandler};
+use crate::client::recording::GatewayRecorder;
+use crate::client::{DispatchMode, EventHandler, EventMiddleware, RawEventHandler};
 #[cfg(feature = "framework")]
 use crate::framework::Framework;
+use crate::gateway::{GatewayEncoding, ShardSessionState, TransportCompression};
 use crate::internal::prelude::*;
 use crate::internal::tokio::spawn_named;
 use crate::model::gateway::GatewayIntents;
@@ -52,8 +55,9 @@ use crate::CacheAndHttp;
 /// use std::sync::Arc;
 ///
 /// use serenity::client::bridge::gateway::{ShardManager, ShardManagerOptions};
-/// use serenity::client::{EventHandler, RawEventHandler};
+/// use serenity::client::{DispatchMode, EventHandler, RawEventHandler};
 /// use serenity::framework::{Framework, StandardFramework};
+/// use serenity::gateway::{GatewayEncoding, TransportCompression};
 /// use serenity::http::Http;
 /// use serenity::model::gateway::GatewayIntents;
 /// use serenity::prelude::*;
@@ -77,6 +81,8 @@ use crate::CacheAndHttp;
 ///     data: &data,
 ///     event_handler: &Some(event_handler),
 ///     raw_event_handler: &None,
+///     middleware: &[],
+///     gateway_recorder: &None,
 ///     framework: &framework,
 ///     // the shard index to start initiating from
 ///     shard_index: 0,
@@ -89,6 +95,10 @@ use crate::CacheAndHttp;
 ///     ws_url: &gateway_url,
 ///     # cache_and_http: &cache_and_http,
 ///     intents: GatewayIntents::non_privileged(),
+///     dispatch_mode: DispatchMode::default(),
+///     compression: TransportCompression::default(),
+///     encoding: GatewayEncoding::default(),
+///     session_states: Vec::new(),
 /// });
 /// #     Ok(())
 /// # }
@@ -112,6 +122,7 @@ pub struct ShardManager {
     shard_total: u64,
     shard_queuer: Sender<ShardQueuerMessage>,
     shard_shutdown: Receiver<ShardId>,
+    closed_sessions: ClosedSessions,
 }
 
 impl ShardManager {
@@ -123,11 +134,14 @@ impl ShardManager {
 
         let runners = Arc::new(Mutex::new(HashMap::new()));
         let (shutdown_send, shutdown_recv) = mpsc::unbounded();
+        let closed_sessions = ClosedSessions::default();
 
         let mut shard_queuer = ShardQueuer {
             data: Arc::clone(opt.data),
             event_handler: opt.event_handler.as_ref().map(Arc::clone),
             raw_event_handler: opt.raw_event_handler.as_ref().map(Arc::clone),
+            middleware: opt.middleware.to_vec(),
+            gateway_recorder: opt.gateway_recorder.as_ref().map(Arc::clone),
             #[cfg(feature = "framework")]
             framework: Arc::clone(opt.framework),
             last_start: None,
@@ -140,6 +154,18 @@ impl ShardManager {
             ws_url: Arc::clone(opt.ws_url),
             cache_and_http: Arc::clone(opt.cache_and_http),
             intents: opt.intents,
+            dispatch_mode: opt.dispatch_mode,
+            compression: opt.compression,
+            encoding: opt.encoding,
+            sessions: opt
+                .session_states
+                .into_iter()
+                .map(|state| (state.shard_info[0], state))
+                .collect(),
+            closed_sessions: Arc::clone(&closed_sessions),
+            session_start_limit: None,
+            session_start_reset: None,
+            session_start_retry: None,
         };
 
         spawn_named("shard_queuer::run", async move {
@@ -153,6 +179,7 @@ impl ShardManager {
             shard_queuer: shard_queue_tx,
             shard_total: opt.shard_total,
             shard_shutdown: shutdown_recv,
+            closed_sessions,
             runners,
         }));
 
@@ -270,10 +297,33 @@ impl ShardManager {
     /// stopped.
     #[instrument(skip(self))]
     pub async fn shutdown(&mut self, shard_id: ShardId, code: u16) {
+        self.shutdown_with_session(shard_id, code).await;
+    }
+
+    /// Attempts to shut down the shard runner by Id, keeping its session valid.
+    ///
+    /// The shard closes its connection with code `4000`. Returns the state of
+    /// its session if the shard runner shut down cleanly while connected, which
+    /// can be passed to [`ClientBuilder::session_states`] to resume it later.
+    ///
+    /// [`ClientBuilder::session_states`]: crate::client::ClientBuilder::session_states
+    #[instrument(skip(self))]
+    pub async fn shutdown_resumable(&mut self, shard_id: ShardId) -> Option<ShardSessionState> {
+        self.shutdown_with_session(shard_id, 4000).await
+    }
+
+    async fn shutdown_with_session(
+        &mut self,
+        shard_id: ShardId,
+        code: u16,
+    ) -> Option<ShardSessionState> {
         const TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(5);
 
         info!("Shutting down shard {}", shard_id);
 
+        // Drop a session left behind by an earlier shutdown that timed out.
+        self.closed_sessions.lock().await.remove(&shard_id);
+
         drop(self.shard_queuer.unbounded_send(ShardQueuerMessage::ShutdownShard(shard_id, code)));
 
         match timeout(TIMEOUT, self.shard_shutdown.next()).await {
@@ -292,6 +342,8 @@ impl ShardManager {
         }
 
         self.runners.lock().await.remove(&shard_id);
+
+        self.closed_sessions.lock().await.remove(&shard_id)
     }
 
     /// Sends a shutdown message for all shards that the manager is responsible
@@ -299,13 +351,38 @@ impl ShardManager {
     ///
     /// If you only need to shutdown a select number of shards, prefer looping
     /// over the [`Self::shutdown`] method.
+    ///
+    /// The shards close their connections with code `1000`, invalidating their
+    /// sessions. To resume them after a restart instead, use
+    /// [`Self::shutdown_all_resumable`].
     #[instrument(skip(self))]
     pub async fn shutdown_all(&mut self) {
+        self.shutdown_all_with_code(1000).await;
+    }
+
+    /// Sends a shutdown message for all shards that the manager is responsible
+    /// for that are still known to be running, keeping their sessions valid.
+    ///
+    /// Returns the states of the sessions of the shards that shut down cleanly.
+    /// The shards close their connections with code `4000`, so these can be
+    /// passed to [`ClientBuilder::session_states`] to resume them after a
+    /// restart, as long as this happens before Discord expires them.
+    ///
+    /// **Note**: Until the sessions are resumed or expire, the bot keeps
+    /// appearing online.
+    ///
+    /// [`ClientBuilder::session_states`]: crate::client::ClientBuilder::session_states
+    #[instrument(skip(self))]
+    pub async fn shutdown_all_resumable(&mut self) -> Vec<ShardSessionState> {
+        self.shutdown_all_with_code(4000).await
+    }
+
+    async fn shutdown_all_with_code(&mut self, code: u16) -> Vec<ShardSessionState> {
         let keys = {
             let runners = self.runners.lock().await;
 
             if runners.is_empty() {
-                return;
+                return Vec::new();
             }
 
             runners.keys().copied().collect::<Vec<_>>()
@@ -313,12 +390,16 @@ impl ShardManager {
 
         info!("Shutting down all shards");
 
+        let mut sessions = Vec::with_capacity(keys.len());
+
         for shard_id in keys {
-            self.shutdown(shard_id, 1000).await;
+            sessions.extend(self.shutdown_with_session(shard_id, code).await);
         }
 
         drop(self.shard_queuer.unbounded_send(ShardQueuerMessage::Shutdown));
         drop(self.monitor_tx.unbounded_send(ShardManagerMessage::ShutdownInitiated));
+
+        sessions
     }
 
     #[instrument(skip(self))]
@@ -344,10 +425,16 @@ impl Drop for ShardManager {
     }
 }
 
+/// Options to be passed to [`ShardManager::new`].
+///
+/// Besides struct literals, they can be created via [`Self::new`], which uses
+/// the defaults for all optional fields.
 pub struct ShardManagerOptions<'a> {
     pub data: &'a Arc<RwLock<TypeMap>>,
     pub event_handler: &'a Option<Arc<dyn EventHandler>>,
     pub raw_event_handler: &'a Option<Arc<dyn RawEventHandler>>,
+    pub middleware: &'a [Arc<dyn EventMiddleware>],
+    pub gateway_recorder: &'a Option<Arc<dyn GatewayRecorder>>,
     #[cfg(feature = "framework")]
     pub framework: &'a Arc<dyn Framework + Send + Sync>,
     pub shard_index: u64,
@@ -358,4 +445,45 @@ pub struct ShardManagerOptions<'a> {
     pub ws_url: &'a Arc<Mutex<String>>,
     pub cache_and_http: &'a Arc<CacheAndHttp>,
     pub intents: GatewayIntents,
+    pub dispatch_mode: DispatchMode,
+    pub compression: TransportCompression,
+    pub encoding: GatewayEncoding,
+    /// The states of sessions to attempt to resume when starting the
+    /// respective shards, instead of identifying.
+    pub session_states: Vec<ShardSessionState>,
+}
+
+impl<'a> ShardManagerOptions<'a> {
+    /// Creates options for managing a single shard, which has no event
+    /// handlers and uses the defaults for everything else.
+    #[must_use]
+    pub fn new(
+        data: &'a Arc<RwLock<TypeMap>>,
+        #[cfg(feature = "framework")] framework: &'a Arc<dyn Framework + Send + Sync>,
+        ws_url: &'a Arc<Mutex<String>>,
+        cache_and_http: &'a Arc<CacheAndHttp>,
+        intents: GatewayIntents,
+    ) -> Self {
+        Self {
+            data,
+            event_handler: &None,
+            raw_event_handler: &None,
+            middleware: &[],
+            gateway_recorder: &None,
+            #[cfg(feature = "framework")]
+            framework,
+            shard_index: 0,
+            shard_init: 1,
+            shard_total: 1,
+            #[cfg(feature = "voice")]
+            voice_manager: &None,
+            ws_url,
+            cache_and_http,
+            intents,
+            dispatch_mode: DispatchMode::default(),
+            compression: TransportCompression::default(),
+            encoding: GatewayEncoding::default(),
+            session_states: Vec::new(),
+        }
+    }
 }
@@ -9,6 +9,7 @@ use tracing::{debug, info, instrument, warn};
 use typemap_rev::TypeMap;
 
 use super::{
+    ClosedSessions,
     ShardClientMessage,
     ShardId,
     ShardManagerMessage,
@@ -20,23 +21,36 @@ use super::{
 };
 #[cfg(feature = "voice")]
 use crate::client::bridge::voice::VoiceGatewayManager;
-use crate::client::{EventHandler, RawEventHandler};
+use crate::client::recording::GatewayRecorder;
+use crate::client::{DispatchMode, EventHandler, EventMiddleware, RawEventHandler};
 #[cfg(feature = "framework")]
 use crate::framework::Framework;
-use crate::gateway::{ConnectionStage, InterMessage, Shard};
+use crate::gateway::{
+    ConnectionStage,
+    GatewayEncoding,
+    InterMessage,
+    Shard,
+    ShardSessionState,
+    TransportCompression,
+};
 use crate::internal::prelude::*;
 use crate::internal::tokio::spawn_named;
-use crate::model::gateway::GatewayIntents;
+use crate::model::gateway::{GatewayIntents, SessionStartLimit};
 use crate::CacheAndHttp;
 
 const WAIT_BETWEEN_BOOTS_IN_SECONDS: u64 = 5;
+const WAIT_BETWEEN_SESSION_START_LIMIT_FETCHES_IN_SECONDS: u64 = 60;
 
 /// The shard queuer is a simple loop that runs indefinitely to manage the
 /// startup of shards.
 ///
+/// Shards are started in batches: Discord's `max_concurrency` determines how
+/// many shards may identify at once, and a 5 second sleep is kept between
+/// batches. The remaining session start budget is respected as well, keeping
+/// shards queued until it resets when it is exhausted.
+///
 /// A shard queuer instance _should_ be run in its own thread, due to the
-/// blocking nature of the loop itself as well as a 5 second thread sleep
-/// between shard starts.
+/// blocking nature of the loop itself as well as the sleeps between batches.
 pub struct ShardQueuer {
     /// A copy of [`Client::data`] to be given to runners for contextual
     /// dispatching.
@@ -53,10 +67,18 @@ pub struct ShardQueuer {
     ///
     /// [`Client`]: crate::Client
     pub raw_event_handler: Option<Arc<dyn RawEventHandler>>,
+    /// The middleware given to the [`Client`].
+    ///
+    /// [`Client`]: crate::Client
+    pub middleware: Vec<Arc<dyn EventMiddleware>>,
+    /// The gateway recorder given to the [`Client`].
+    ///
+    /// [`Client`]: crate::Client
+    pub gateway_recorder: Option<Arc<dyn GatewayRecorder>>,
     /// A copy of the framework
     #[cfg(feature = "framework")]
     pub framework: Arc<dyn Framework + Send + Sync>,
-    /// The instant that a shard was last started.
+    /// The instant that a batch of shards was last started.
     ///
     /// This is used to determine how long to wait between shard IDENTIFYs.
     pub last_start: Option<Instant>,
@@ -80,6 +102,33 @@ pub struct ShardQueuer {
     pub ws_url: Arc<Mutex<String>>,
     pub cache_and_http: Arc<CacheAndHttp>,
     pub intents: GatewayIntents,
+    /// How started shards dispatch events to the event handler.
+    pub dispatch_mode: DispatchMode,
+    /// The compression used by started shards.
+    pub compression: TransportCompression,
+    /// The encoding used by started shards.
+    pub encoding: GatewayEncoding,
+    /// The states of sessions to resume, keyed by shard Id.
+    ///
+    /// A session is removed once its shard is started, so that restarts of the
+    /// shard identify instead.
+    pub sessions: HashMap<u64, ShardSessionState>,
+    /// Where started shard runners store the state of their session when they
+    /// shut down.
+    pub(crate) closed_sessions: ClosedSessions,
+    /// The session start limit last retrieved via [`Http::get_bot_gateway`],
+    /// with the starts since then subtracted.
+    ///
+    /// This is fetched when the first shard is started, and again once it is
+    /// exhausted and has reset.
+    ///
+    /// [`Http::get_bot_gateway`]: crate::http::Http::get_bot_gateway
+    pub(crate) session_start_limit: Option<SessionStartLimit>,
+    /// The instant at which the session start limit resets.
+    pub(crate) session_start_reset: Option<Instant>,
+    /// The instant before which the session start limit isn't fetched again,
+    /// after fetching it failed.
+    pub(crate) session_start_retry: Option<Instant>,
 }
 
 impl ShardQueuer {
@@ -88,12 +137,20 @@ impl ShardQueuer {
     /// This will loop over the internal [`Self::rx`] for [`ShardQueuerMessage`]s,
     /// blocking for messages on what to do.
     ///
-    /// If a [`ShardQueuerMessage::Start`] is received, this will:
+    /// If a [`ShardQueuerMessage::Start`] is received for a shard with a
+    /// session to resume, the shard is started right away, since resuming
+    /// doesn't count towards the session start limit. Otherwise the shard is
+    /// queued. Shards belong to the bucket `shard_id % max_concurrency`, and
+    /// once a shard of every bucket is queued, or no message was received for
+    /// 5 seconds, this will:
     ///
-    /// 1. Check how much time has passed since the last shard was started
-    /// 2. If the amount of time is less than the ratelimit, it will sleep until
+    /// 1. Take the first queued shard of every bucket, as long as the session
+    /// start limit allows. If it is exhausted, the shards stay queued until
+    /// it resets
+    /// 2. Check how much time has passed since the last batch was started
+    /// 3. If the amount of time is less than the ratelimit, it will sleep until
     /// that time has passed
-    /// 3. Start the shard by ID
+    /// 4. Start the batch of shards by ID
     ///
     /// If a [`ShardQueuerMessage::Shutdown`] is received, this will return and
     /// the loop will be over.
@@ -121,12 +178,22 @@ impl ShardQueuer {
                 },
                 Ok(Some(ShardQueuerMessage::Start(id, total))) => {
                     debug!("[Shard Queuer] Received to start shard {} of {}.", id.0, total.0);
-                    self.checked_start(id.0, total.0).await;
+
+                    if let Some(state) = self.take_session(id.0, total.0) {
+                        self.resume(state).await;
+                    } else {
+                        self.queue.push_back((id.0, total.0));
+                        self.refresh_session_start_limit().await;
+
+                        if buckets_filled(&self.queue, self.max_concurrency()) {
+                            self.checked_start_batch().await;
+                        }
+                    }
                 },
                 Ok(None) => break,
                 Err(_) => {
-                    if let Some((id, total)) = self.queue.pop_front() {
-                        self.checked_start(id, total).await;
+                    if !self.queue.is_empty() {
+                        self.checked_start_batch().await;
                     }
                 },
             }
@@ -154,39 +221,179 @@ impl ShardQueuer {
         sleep(to_sleep).await;
     }
 
+    /// Fetches the session start limit if it is not yet known, or if it is
+    /// exhausted and has reset.
+    ///
+    /// After a failed fetch, the limit isn't fetched again for a while.
     #[instrument(skip(self))]
-    async fn checked_start(&mut self, id: u64, total: u64) {
-        debug!("[Shard Queuer] Checked start for shard {} out of {}", id, total);
+    async fn refresh_session_start_limit(&mut self) {
+        let now = Instant::now();
+
+        if self.session_start_retry.map_or(false, |retry| now < retry) {
+            return;
+        }
+
+        if let Some(limit) = &self.session_start_limit {
+            let reset = self.session_start_reset.map_or(true, |reset| reset <= now);
+
+            if limit.remaining > 0 || !reset {
+                return;
+            }
+        }
+
+        match self.cache_and_http.http.get_bot_gateway().await {
+            Ok(gateway) => {
+                let limit = gateway.session_start_limit;
+
+                debug!(
+                    "[Shard Queuer] Session start limit: {}/{}, max concurrency {}",
+                    limit.remaining, limit.total, limit.max_concurrency,
+                );
+
+                self.session_start_reset = Some(now + Duration::from_millis(limit.reset_after));
+                self.session_start_retry = None;
+                self.session_start_limit = Some(limit);
+                self.warn_if_exhausted();
+            },
+            Err(why) => {
+                warn!("[Shard Queuer] Err retrieving session start limit: {:?}", why);
+
+                let wait = Duration::from_secs(WAIT_BETWEEN_SESSION_START_LIMIT_FETCHES_IN_SECONDS);
+                self.session_start_retry = Some(now + wait);
+            },
+        }
+    }
+
+    fn warn_if_exhausted(&self) {
+        if self.session_start_limit.as_ref().map_or(false, |limit| limit.remaining == 0) {
+            let reset = self.session_start_reset.unwrap_or_else(Instant::now);
+
+            warn!(
+                "[Shard Queuer] Session start limit exhausted, waiting {:?} for it to reset",
+                reset.saturating_duration_since(Instant::now()),
+            );
+        }
+    }
+
+    /// The number of shards that may identify at once.
+    fn max_concurrency(&self) -> u64 {
+        self.session_start_limit.as_ref().map_or(1, |limit| limit.max_concurrency.max(1))
+    }
+
+    /// Removes the session to resume for a shard, if one was given for it.
+    fn take_session(&mut self, shard_id: u64, shard_total: u64) -> Option<ShardSessionState> {
+        let state = self.sessions.remove(&shard_id)?;
+
+        if state.shard_info == [shard_id, shard_total] {
+            Some(state)
+        } else {
+            debug!("[Shard Queuer] Discarding session of shard {} with different total", shard_id);
+
+            None
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn checked_start_batch(&mut self) {
+        self.refresh_session_start_limit().await;
+
+        // Without a known limit, shards are started anyway.
+        let available = self.session_start_limit.as_ref().map_or(u64::MAX, |limit| limit.remaining);
+
+        if available == 0 {
+            debug!("[Shard Queuer] Session start limit exhausted, keeping shards queued");
+
+            return;
+        }
+
+        let max_concurrency = self.max_concurrency();
+        let batch = pop_batch(&mut self.queue, max_concurrency, available);
+
+        if batch.is_empty() {
+            return;
+        }
+
+        debug!("[Shard Queuer] Checked start for batch of {} shards", batch.len());
         self.check_last_start().await;
 
-        if let Err(why) = self.start(id, total).await {
-            warn!("[Shard Queuer] Err starting shard {}: {:?}", id, why);
-            info!("[Shard Queuer] Re-queueing start of shard {}", id);
+        for (id, total) in batch {
+            match self.start(id, total).await {
+                Ok(()) => {
+                    if let Some(limit) = &mut self.session_start_limit {
+                        limit.remaining = limit.remaining.saturating_sub(1);
+                    }
 
-            self.queue.push_back((id, total));
+                    self.warn_if_exhausted();
+                },
+                Err(why) => {
+                    warn!("[Shard Queuer] Err starting shard {}: {:?}", id, why);
+                    info!("[Shard Queuer] Re-queueing start of shard {}", id);
+
+                    self.queue.push_back((id, total));
+                },
+            }
         }
 
         self.last_start = Some(Instant::now());
     }
 
+    /// Starts a shard by resuming its session, queueing it to identify instead
+    /// if that fails.
+    #[instrument(skip(self, state))]
+    async fn resume(&mut self, state: ShardSessionState) {
+        let [shard_id, shard_total] = state.shard_info;
+
+        let result = Shard::new_resumed(
+            Arc::clone(&self.ws_url),
+            &self.cache_and_http.http.token,
+            state,
+            self.intents,
+            self.compression,
+            self.encoding,
+        )
+        .await;
+
+        match result {
+            Ok(shard) => self.run_shard(shard).await,
+            Err(why) => {
+                warn!("[Shard Queuer] Err resuming session of shard {}: {:?}", shard_id, why);
+                info!("[Shard Queuer] Queueing start of shard {}", shard_id);
+
+                self.queue.push_back((shard_id, shard_total));
+            },
+        }
+    }
+
     #[instrument(skip(self))]
     async fn start(&mut self, shard_id: u64, shard_total: u64) -> Result<()> {
         let shard_info = [shard_id, shard_total];
 
-        let mut shard = Shard::new(
+        let shard = Shard::new_with_encoding(
             Arc::clone(&self.ws_url),
             &self.cache_and_http.http.token,
             shard_info,
             self.intents,
+            self.compression,
+            self.encoding,
         )
         .await?;
 
+        self.run_shard(shard).await;
+
+        Ok(())
+    }
+
+    async fn run_shard(&mut self, mut shard: Shard) {
+        let shard_id = shard.shard_info()[0];
+
         shard.set_http(Arc::clone(&self.cache_and_http.http));
 
         let mut runner = ShardRunner::new(ShardRunnerOptions {
             data: Arc::clone(&self.data),
             event_handler: self.event_handler.as_ref().map(Arc::clone),
             raw_event_handler: self.raw_event_handler.as_ref().map(Arc::clone),
+            middleware: self.middleware.clone(),
+            gateway_recorder: self.gateway_recorder.as_ref().map(Arc::clone),
             #[cfg(feature = "framework")]
             framework: Arc::clone(&self.framework),
             manager_tx: self.manager_tx.clone(),
@@ -194,7 +401,9 @@ impl ShardQueuer {
             voice_manager: self.voice_manager.clone(),
             shard,
             cache_and_http: Arc::clone(&self.cache_and_http),
+            dispatch_mode: self.dispatch_mode,
         });
+        runner.closed_sessions = Some(Arc::clone(&self.closed_sessions));
 
         let runner_info = ShardRunnerInfo {
             latency: None,
@@ -208,8 +417,6 @@ impl ShardQueuer {
         });
 
         self.runners.lock().await.insert(ShardId(shard_id), runner_info);
-
-        Ok(())
     }
 
     #[instrument(skip(self))]
@@ -256,3 +463,66 @@ impl ShardQueuer {
         }
     }
 }
+
+/// Whether the queue holds a shard of every bucket, meaning that a full batch
+/// can be started.
+fn buckets_filled(queue: &VecDeque<(u64, u64)>, max_concurrency: u64) -> bool {
+    (0..max_concurrency).all(|bucket| queue.iter().any(|(id, _)| id % max_concurrency == bucket))
+}
+
+/// Takes the first queued shard of every bucket out of the queue, up to
+/// `limit` shards.
+///
+/// Shards belong to the bucket `shard_id % max_concurrency`. Discord allows one
+/// shard of every bucket to identify at the same time.
+fn pop_batch(
+    queue: &mut VecDeque<(u64, u64)>,
+    max_concurrency: u64,
+    limit: u64,
+) -> Vec<(u64, u64)> {
+    let mut batch: Vec<(u64, u64)> = Vec::new();
+    let mut index = 0;
+
+    while index < queue.len() && (batch.len() as u64) < limit.min(max_concurrency) {
+        let bucket = queue[index].0 % max_concurrency;
+
+        if batch.iter().any(|(id, _)| id % max_concurrency == bucket) {
+            index += 1;
+        } else if let Some(shard) = queue.remove(index) {
+            batch.push(shard);
+        }
+    }
+
+    batch
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use super::{buckets_filled, pop_batch};
+
+    #[test]
+    fn test_batches_one_shard_per_bucket() {
+        let mut queue = (0..10).map(|id| (id, 10)).collect::<VecDeque<_>>();
+
+        assert!(buckets_filled(&queue, 4));
+        assert_eq!(pop_batch(&mut queue, 4, u64::MAX), vec![(0, 10), (1, 10), (2, 10), (3, 10)]);
+        assert_eq!(pop_batch(&mut queue, 4, u64::MAX), vec![(4, 10), (5, 10), (6, 10), (7, 10)]);
+        assert!(!buckets_filled(&queue, 4));
+        assert_eq!(pop_batch(&mut queue, 4, u64::MAX), vec![(8, 10), (9, 10)]);
+        assert!(queue.is_empty());
+        assert!(pop_batch(&mut queue, 4, u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_batches_within_limit() {
+        let mut queue = VecDeque::from(vec![(0, 4), (2, 4), (1, 4), (3, 4)]);
+
+        assert_eq!(pop_batch(&mut queue, 2, 0), vec![]);
+        assert_eq!(pop_batch(&mut queue, 2, 1), vec![(0, 4)]);
+        assert_eq!(pop_batch(&mut queue, 2, 2), vec![(2, 4), (1, 4)]);
+        assert_eq!(queue, VecDeque::from(vec![(3, 4)]));
+        assert_eq!(pop_batch(&mut queue, 1, u64::MAX), vec![(3, 4)]);
+    }
+}
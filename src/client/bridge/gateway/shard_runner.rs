@@ -5,6 +5,7 @@ use async_tungstenite::tungstenite;
 use async_tungstenite::tungstenite::error::Error as TungsteniteError;
 use async_tungstenite::tungstenite::protocol::frame::CloseFrame;
 use futures::channel::mpsc::{self, UnboundedReceiver as Receiver, UnboundedSender as Sender};
+use futures::future::BoxFuture;
 use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
 use tokio::sync::RwLock;
@@ -12,11 +13,12 @@ use tracing::{debug, error, info, instrument, trace, warn};
 use typemap_rev::TypeMap;
 
 use super::event::{ClientEvent, ShardStageUpdateEvent};
-use super::{ShardClientMessage, ShardId, ShardManagerMessage, ShardRunnerMessage};
+use super::{ClosedSessions, ShardClientMessage, ShardId, ShardManagerMessage, ShardRunnerMessage};
 #[cfg(feature = "voice")]
 use crate::client::bridge::voice::VoiceGatewayManager;
-use crate::client::dispatch::{dispatch, DispatchEvent};
-use crate::client::{EventHandler, RawEventHandler};
+use crate::client::dispatch::{dispatch, DispatchEvent, DispatchMode, Dispatcher};
+use crate::client::recording::GatewayRecorder;
+use crate::client::{Context, EventHandler, EventMiddleware, Next, RawEventHandler};
 #[cfg(feature = "collector")]
 use crate::collector::{
     ComponentInteractionFilter,
@@ -31,7 +33,6 @@ use crate::collector::{
 use crate::framework::Framework;
 use crate::gateway::{GatewayError, InterMessage, ReconnectType, Shard, ShardAction};
 use crate::internal::prelude::*;
-use crate::internal::ws_impl::{ReceiverExt, SenderExt};
 #[cfg(feature = "collector")]
 use crate::model::application::interaction::Interaction;
 use crate::model::event::{Event, GatewayEvent};
@@ -42,6 +43,8 @@ pub struct ShardRunner {
     data: Arc<RwLock<TypeMap>>,
     event_handler: Option<Arc<dyn EventHandler>>,
     raw_event_handler: Option<Arc<dyn RawEventHandler>>,
+    middleware: Vec<Arc<dyn EventMiddleware>>,
+    gateway_recorder: Option<Arc<dyn GatewayRecorder>>,
     #[cfg(feature = "framework")]
     framework: Arc<dyn Framework + Send + Sync>,
     manager_tx: Sender<ShardManagerMessage>,
@@ -50,9 +53,12 @@ pub struct ShardRunner {
     // channel to send messages to the shard runner from the shard manager
     runner_tx: Sender<InterMessage>,
     pub(crate) shard: Shard,
+    // where to store the session state on shutdown, set by the shard queuer
+    pub(crate) closed_sessions: Option<ClosedSessions>,
     #[cfg(feature = "voice")]
     voice_manager: Option<Arc<dyn VoiceGatewayManager + Send + Sync + 'static>>,
     cache_and_http: Arc<CacheAndHttp>,
+    dispatcher: Dispatcher,
     #[cfg(feature = "collector")]
     event_filters: Vec<EventFilter>,
     #[cfg(feature = "collector")]
@@ -76,13 +82,17 @@ impl ShardRunner {
             data: opt.data,
             event_handler: opt.event_handler,
             raw_event_handler: opt.raw_event_handler,
+            middleware: opt.middleware,
+            gateway_recorder: opt.gateway_recorder,
             #[cfg(feature = "framework")]
             framework: opt.framework,
             manager_tx: opt.manager_tx,
             shard: opt.shard,
+            closed_sessions: None,
             #[cfg(feature = "voice")]
             voice_manager: opt.voice_manager,
             cache_and_http: opt.cache_and_http,
+            dispatcher: Dispatcher::new(opt.dispatch_mode),
             #[cfg(feature = "collector")]
             event_filters: Vec::new(),
             #[cfg(feature = "collector")]
@@ -311,6 +321,11 @@ impl ShardRunner {
             }
         }
 
+        // Keep the session around, in case the manager wants to resume it.
+        if let (Some(sessions), Some(state)) = (&self.closed_sessions, self.shard.session_state()) {
+            sessions.lock().await.insert(id, state);
+        }
+
         // Inform the manager that shutdown for this shard has finished.
         if let Err(why) = self.manager_tx.unbounded_send(ShardManagerMessage::ShutdownFinished(id))
         {
@@ -323,9 +338,21 @@ impl ShardRunner {
         false
     }
 
-    #[inline]
     #[instrument(skip(self, event))]
     async fn dispatch(&self, event: DispatchEvent) {
+        match event {
+            DispatchEvent::Model(event) if !self.middleware.is_empty() => {
+                let dispatch = |event| self.dispatch_to_handlers(DispatchEvent::Model(event));
+
+                Next::new(&self.middleware, &dispatch).run(self.context(), event).await;
+            },
+            event => self.dispatch_to_handlers(event).await,
+        }
+    }
+
+    /// Dispatches the event to the cache, the event handlers and the framework,
+    /// after it passed through the middleware.
+    fn dispatch_to_handlers(&self, event: DispatchEvent) -> BoxFuture<'_, ()> {
         dispatch(
             event,
             #[cfg(feature = "framework")]
@@ -336,8 +363,19 @@ impl ShardRunner {
             &self.runner_tx,
             self.shard.shard_info()[0],
             Arc::clone(&self.cache_and_http),
+            &self.dispatcher,
+        )
+    }
+
+    fn context(&self) -> Context {
+        Context::new(
+            Arc::clone(&self.data),
+            self.runner_tx.clone(),
+            self.shard.shard_info()[0],
+            Arc::clone(&self.cache_and_http.http),
+            #[cfg(feature = "cache")]
+            Arc::clone(&self.cache_and_http.cache),
         )
-        .await;
     }
 
     // Handles a received value over the shard runner rx channel.
@@ -467,7 +505,7 @@ impl ShardRunner {
             },
             InterMessage::Json(value) => {
                 // Value must be forwarded over the websocket
-                self.shard.client.send_json(&value).await.is_ok()
+                self.shard.send_payload(&value).await.is_ok()
             },
         }
     }
@@ -537,8 +575,20 @@ impl ShardRunner {
     /// present event was successful.
     #[instrument(skip(self))]
     async fn recv_event(&mut self) -> Result<(Option<Event>, Option<ShardAction>, bool)> {
-        let gw_event = match self.shard.client.recv_json().await {
-            Ok(Some(value)) => GatewayEvent::deserialize(value).map(Some).map_err(From::from),
+        let gw_event = match self.shard.recv_json().await {
+            Ok(Some(value)) => {
+                if let Some(recorder) = &self.gateway_recorder {
+                    if let Err(why) = recorder.record(self.shard.shard_info(), &value) {
+                        warn!(
+                            "[ShardRunner {:?}] Failed to record payload: {:?}",
+                            self.shard.shard_info(),
+                            why,
+                        );
+                    }
+                }
+
+                GatewayEvent::deserialize(value).map(Some).map_err(From::from)
+            },
             Ok(None) => Ok(None),
             Err(Error::Tungstenite(TungsteniteError::Io(_))) => {
                 debug!("Attempting to auto-reconnect");
@@ -664,10 +714,15 @@ impl ShardRunner {
 }
 
 /// Options to be passed to [`ShardRunner::new`].
+///
+/// Besides struct literals, they can be created via [`Self::new`], which uses
+/// the defaults for all optional fields.
 pub struct ShardRunnerOptions {
     pub data: Arc<RwLock<TypeMap>>,
     pub event_handler: Option<Arc<dyn EventHandler>>,
     pub raw_event_handler: Option<Arc<dyn RawEventHandler>>,
+    pub middleware: Vec<Arc<dyn EventMiddleware>>,
+    pub gateway_recorder: Option<Arc<dyn GatewayRecorder>>,
     #[cfg(feature = "framework")]
     pub framework: Arc<dyn Framework + Send + Sync>,
     pub manager_tx: Sender<ShardManagerMessage>,
@@ -675,4 +730,34 @@ pub struct ShardRunnerOptions {
     #[cfg(feature = "voice")]
     pub voice_manager: Option<Arc<dyn VoiceGatewayManager + Send + Sync>>,
     pub cache_and_http: Arc<CacheAndHttp>,
+    pub dispatch_mode: DispatchMode,
+}
+
+impl ShardRunnerOptions {
+    /// Creates options for running the shard, which has no event handlers and
+    /// uses the defaults for everything else.
+    #[must_use]
+    pub fn new(
+        data: Arc<RwLock<TypeMap>>,
+        #[cfg(feature = "framework")] framework: Arc<dyn Framework + Send + Sync>,
+        manager_tx: Sender<ShardManagerMessage>,
+        shard: Shard,
+        cache_and_http: Arc<CacheAndHttp>,
+    ) -> Self {
+        Self {
+            data,
+            event_handler: None,
+            raw_event_handler: None,
+            middleware: Vec::new(),
+            gateway_recorder: None,
+            #[cfg(feature = "framework")]
+            framework,
+            manager_tx,
+            shard,
+            #[cfg(feature = "voice")]
+            voice_manager: None,
+            cache_and_http,
+            dispatch_mode: DispatchMode::default(),
+        }
+    }
 }
@@ -89,6 +89,19 @@ impl Context {
         }
     }
 
+    /// Creates a context for unit tests, which is not connected to a shard.
+    #[cfg(all(test, feature = "gateway"))]
+    pub(crate) fn test() -> Context {
+        Context::new(
+            Arc::default(),
+            futures::channel::mpsc::unbounded().0,
+            0,
+            Arc::new(Http::new("token")),
+            #[cfg(feature = "cache")]
+            Arc::new(Cache::new()),
+        )
+    }
+
     /// Sets the current user as being [`Online`]. This maintains the current
     /// activity.
     ///
@@ -1,11 +1,15 @@
+use std::collections::hash_map::DefaultHasher;
 #[cfg(feature = "cache")]
 use std::fmt;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 
 use futures::channel::mpsc::UnboundedSender as Sender;
 use futures::future::{BoxFuture, FutureExt};
-use tokio::sync::RwLock;
-use tracing::instrument;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{instrument, warn, Instrument};
 use typemap_rev::TypeMap;
 
 #[cfg(feature = "gateway")]
@@ -23,8 +27,6 @@ use crate::internal::tokio::spawn_named;
 use crate::model::channel::{Channel, Message};
 use crate::model::event::Event;
 use crate::model::guild::Member;
-#[cfg(feature = "cache")]
-use crate::model::id::GuildId;
 use crate::CacheAndHttp;
 
 #[inline]
@@ -63,6 +65,160 @@ fn context(
     Context::new(Arc::clone(data), runner_tx.clone(), shard_id, Arc::clone(http))
 }
 
+/// How events are dispatched to the [`EventHandler`].
+///
+/// Only the [`EventHandler`] is affected: the [`RawEventHandler`] always
+/// handles the events of a shard one after another, and framework commands
+/// always run concurrently.
+///
+/// **Note**: In the [`Sequential`] and [`Keyed`] modes, the shard waits for
+/// room in a full lane before doing anything else. Meanwhile it neither sends
+/// heartbeats nor handles messages from the [`ShardManager`], including
+/// shutting down. If the event handler is slow enough for the lane to stay
+/// full until a heartbeat is missed, Discord disconnects the shard, which then
+/// has to reconnect and resume. Keep the handler quick, spawning long-running
+/// work onto tasks of its own, or pick a larger `capacity`.
+///
+/// [`Sequential`]: Self::Sequential
+/// [`Keyed`]: Self::Keyed
+/// [`ShardManager`]: super::bridge::gateway::ShardManager
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DispatchMode {
+    /// Every call of the event handler runs on its own task, so calls may
+    /// complete in any order, and a [`EventHandler::message_update`] call may
+    /// run before the [`EventHandler::message`] call of the same message.
+    Concurrent,
+    /// The events of a shard are handled one after another, in the order they
+    /// were received.
+    ///
+    /// Up to `capacity` events wait to be handled, after which the shard stops
+    /// receiving events until there is room again, see the note above.
+    Sequential { capacity: usize },
+    /// The events of a guild, or of a channel outside of guilds, are handled
+    /// one after another in the order they were received, while those of
+    /// different guilds are handled concurrently.
+    ///
+    /// Guilds are spread over `lanes` tasks per shard, each handling its events
+    /// in order. Up to `capacity` events wait to be handled per lane, after
+    /// which the shard stops receiving events until there is room again, see
+    /// the note above.
+    ///
+    /// **Note**: Without the `model` feature, all events are handled in one
+    /// lane.
+    Keyed { lanes: usize, capacity: usize },
+}
+
+impl Default for DispatchMode {
+    fn default() -> Self {
+        Self::Concurrent
+    }
+}
+
+/// The lanes handling the events of a shard, as configured by its
+/// [`DispatchMode`].
+pub(crate) struct Dispatcher {
+    lanes: Vec<mpsc::Sender<BoxFuture<'static, ()>>>,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(mode: DispatchMode) -> Self {
+        let (lanes, capacity) = match mode {
+            DispatchMode::Concurrent => (0, 0),
+            DispatchMode::Sequential {
+                capacity,
+            } => (1, capacity),
+            DispatchMode::Keyed {
+                lanes,
+                capacity,
+            } => (lanes.max(1), capacity),
+        };
+
+        let lanes = (0..lanes)
+            .map(|_| {
+                let (tx, mut rx) = mpsc::channel::<BoxFuture<'static, ()>>(capacity.max(1));
+
+                spawn_named("dispatch::lane", async move {
+                    while let Some(future) = rx.recv().await {
+                        if AssertUnwindSafe(future).catch_unwind().await.is_err() {
+                            warn!("Event handler panicked");
+                        }
+                    }
+                });
+
+                tx
+            })
+            .collect();
+
+        Self {
+            lanes,
+        }
+    }
+
+    /// Gets the lane the event is handled in.
+    fn lane(&self, event: &DispatchEvent) -> Lane<'_> {
+        if self.lanes.len() <= 1 {
+            return Lane(self.lanes.first());
+        }
+
+        let mut hasher = DefaultHasher::new();
+        event_key(event).hash(&mut hasher);
+
+        Lane(self.lanes.get(hasher.finish() as usize % self.lanes.len()))
+    }
+}
+
+/// The Id of the guild of the event, or of its channel outside of guilds.
+#[cfg(feature = "model")]
+fn event_key(event: &DispatchEvent) -> Option<u64> {
+    use crate::model::event::RelatedId;
+
+    match event {
+        DispatchEvent::Model(event) => match (event.guild_id(), event.channel_id()) {
+            (RelatedId::Some(guild_id), _) => Some(guild_id.0),
+            (_, RelatedId::Some(channel_id)) => Some(channel_id.0),
+            _ => None,
+        },
+        DispatchEvent::Client(_) => None,
+    }
+}
+
+#[cfg(not(feature = "model"))]
+fn event_key(_event: &DispatchEvent) -> Option<u64> {
+    None
+}
+
+/// Spawns the future, running it in the current tracing span.
+fn spawn_in_span<F>(name: &str, future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    spawn_named(name, future.in_current_span());
+}
+
+/// A lane of a [`Dispatcher`], or `None` if calls of the event handler run
+/// concurrently.
+#[derive(Clone, Copy)]
+struct Lane<'a>(Option<&'a mpsc::Sender<BoxFuture<'static, ()>>>);
+
+impl Lane<'_> {
+    /// Runs the call of the event handler, waiting for room in the lane if it
+    /// is full.
+    async fn spawn<F>(self, name: &str, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match self.0 {
+            Some(lane) => {
+                if lane.send(future.in_current_span().boxed()).await.is_err() {
+                    warn!("Dispatch lane closed, dropping {}", name);
+                }
+            },
+            None => spawn_in_span(name, future),
+        }
+    }
+}
+
 // Once we can use `Box` as part of a pattern, we will reconsider boxing.
 #[allow(clippy::large_enum_variant)]
 #[non_exhaustive]
@@ -168,8 +324,27 @@ pub(crate) fn dispatch<'rec>(
     runner_tx: &'rec Sender<InterMessage>,
     shard_id: u64,
     cache_and_http: Arc<CacheAndHttp>,
+    dispatcher: &'rec Dispatcher,
 ) -> BoxFuture<'rec, ()> {
     async move {
+        let lane = dispatcher.lane(&event);
+
+        #[cfg(feature = "framework")]
+        if let DispatchEvent::Model(Event::InteractionCreate(ref event)) = event {
+            #[cfg(not(feature = "cache"))]
+            let context = context(data, runner_tx, shard_id, &cache_and_http.http);
+            #[cfg(feature = "cache")]
+            let context =
+                context(data, runner_tx, shard_id, &cache_and_http.http, &cache_and_http.cache);
+
+            let framework = Arc::clone(framework);
+            let interaction = event.interaction.clone();
+
+            spawn_in_span("dispatch::framework::interaction", async move {
+                framework.interaction(context, interaction).await;
+            });
+        }
+
         match (event_handler, raw_event_handler) {
             (None, None) => {
                 event.update(&cache_and_http);
@@ -189,7 +364,7 @@ pub(crate) fn dispatch<'rec>(
 
                     let framework = Arc::clone(framework);
 
-                    spawn_named("dispatch::framework::message", async move {
+                    spawn_in_span("dispatch::framework::message", async move {
                         framework.dispatch(context, event.message).await;
                     });
                 }
@@ -212,22 +387,22 @@ pub(crate) fn dispatch<'rec>(
                     #[cfg(not(feature = "framework"))]
                     {
                         // Avoid cloning if there will be no framework dispatch.
-                        dispatch_message(context, event.message, h).await;
+                        dispatch_message(context, event.message, h, lane).await;
                     }
 
                     #[cfg(feature = "framework")]
                     {
-                        dispatch_message(context.clone(), event.message.clone(), h).await;
+                        dispatch_message(context.clone(), event.message.clone(), h, lane).await;
 
                         let framework = Arc::clone(framework);
 
-                        spawn_named("dispatch::framework::message", async move {
+                        spawn_in_span("dispatch::framework::message", async move {
                             framework.dispatch(context, event.message).await;
                         });
                     }
                 },
                 other => {
-                    handle_event(other, data, h, runner_tx, shard_id, cache_and_http).await;
+                    handle_event(other, data, h, runner_tx, shard_id, cache_and_http, lane).await;
                 },
             },
             (None, Some(ref rh)) => {
@@ -262,7 +437,7 @@ pub(crate) fn dispatch<'rec>(
 
                             let framework = Arc::clone(framework);
 
-                            spawn_named("dispatch::framework::message", async move {
+                            spawn_in_span("dispatch::framework::message", async move {
                                 framework.dispatch(context, message).await;
                             });
                         } else {
@@ -290,23 +465,32 @@ pub(crate) fn dispatch<'rec>(
                         #[cfg(not(feature = "framework"))]
                         {
                             // Avoid cloning if there will be no framework dispatch.
-                            dispatch_message(context, event.message, handler).await;
+                            dispatch_message(context, event.message, handler, lane).await;
                         }
 
                         #[cfg(feature = "framework")]
                         {
-                            dispatch_message(context.clone(), event.message.clone(), handler).await;
+                            dispatch_message(context.clone(), event.message.clone(), handler, lane)
+                                .await;
 
                             let framework = Arc::clone(framework);
                             let message = event.message;
-                            spawn_named("dispatch::framework::message", async move {
+                            spawn_in_span("dispatch::framework::message", async move {
                                 framework.dispatch(context, message).await;
                             });
                         }
                     },
                     other => {
-                        handle_event(other, data, handler, runner_tx, shard_id, cache_and_http)
-                            .await;
+                        handle_event(
+                            other,
+                            data,
+                            handler,
+                            runner_tx,
+                            shard_id,
+                            cache_and_http,
+                            lane,
+                        )
+                        .await;
                     },
                 }
             },
@@ -319,6 +503,7 @@ async fn dispatch_message(
     context: Context,
     mut message: Message,
     event_handler: &Arc<dyn EventHandler>,
+    lane: Lane<'_>,
 ) {
     #[cfg(feature = "model")]
     {
@@ -327,14 +512,15 @@ async fn dispatch_message(
 
     let event_handler = Arc::clone(event_handler);
 
-    spawn_named("dispatch::event_handler::message", async move {
+    lane.spawn("dispatch::event_handler::message", async move {
         event_handler.message(context, message).await;
-    });
+    })
+    .await;
 }
 // Once we can use `Box` as part of a pattern, we will reconsider boxing.
 #[allow(clippy::too_many_arguments)]
 #[cfg_attr(feature = "cache", allow(clippy::used_underscore_binding))]
-#[instrument(skip(event, data, event_handler, cache_and_http))]
+#[instrument(skip(event, data, event_handler, cache_and_http, lane))]
 async fn handle_event(
     event: DispatchEvent,
     data: &Arc<RwLock<TypeMap>>,
@@ -342,6 +528,7 @@ async fn handle_event(
     runner_tx: &Sender<InterMessage>,
     shard_id: u64,
     cache_and_http: Arc<CacheAndHttp>,
+    lane: Lane<'_>,
 ) {
     #[cfg(not(feature = "cache"))]
     let context = context(data, runner_tx, shard_id, &cache_and_http.http);
@@ -356,9 +543,10 @@ async fn handle_event(
         DispatchEvent::Client(event) => {
             return match event {
                 ClientEvent::ShardStageUpdate(event) => {
-                    spawn_named("dispatch::event_handler::shard_stage_update", async move {
+                    lane.spawn("dispatch::event_handler::shard_stage_update", async move {
                         event_handler.shard_stage_update(context, event).await;
-                    });
+                    })
+                    .await;
                 },
             }
         },
@@ -367,47 +555,54 @@ async fn handle_event(
     // Handle Event, this is done to prevent indenting twice (once to destructure DispatchEvent, then to destructure Event)
     match model_event {
         Event::ApplicationCommandPermissionsUpdate(event) => {
-            spawn_named(
+            lane.spawn(
                 "dispatch::event_handler::application_command_permissions_update",
                 async move {
                     event_handler
                         .application_command_permissions_update(context, event.permission)
                         .await;
                 },
-            );
+            )
+            .await;
         },
         Event::AutoModerationRuleCreate(event) => {
-            spawn_named("dispatch::event_handler::auto_moderation_rule_create", async move {
+            lane.spawn("dispatch::event_handler::auto_moderation_rule_create", async move {
                 event_handler.auto_moderation_rule_create(context, event.rule).await;
-            });
+            })
+            .await;
         },
         Event::AutoModerationRuleUpdate(event) => {
-            spawn_named("dispatch::event_handler::auto_moderation_rule_update", async move {
+            lane.spawn("dispatch::event_handler::auto_moderation_rule_update", async move {
                 event_handler.auto_moderation_rule_update(context, event.rule).await;
-            });
+            })
+            .await;
         },
         Event::AutoModerationRuleDelete(event) => {
-            spawn_named("dispatch::event_handler::auto_moderation_rule_delete", async move {
+            lane.spawn("dispatch::event_handler::auto_moderation_rule_delete", async move {
                 event_handler.auto_moderation_rule_delete(context, event.rule).await;
-            });
+            })
+            .await;
         },
         Event::AutoModerationActionExecution(event) => {
-            spawn_named("dispatch::event_handler::auto_moderation_action_execution", async move {
+            lane.spawn("dispatch::event_handler::auto_moderation_action_execution", async move {
                 event_handler.auto_moderation_action_execution(context, event.execution).await;
-            });
+            })
+            .await;
         },
         Event::ChannelCreate(mut event) => {
             update(&cache_and_http, &mut event);
             match event.channel {
                 Channel::Guild(channel) => {
-                    spawn_named("dispatch::event_handler::channel_create", async move {
+                    lane.spawn("dispatch::event_handler::channel_create", async move {
                         event_handler.channel_create(context, &channel).await;
-                    });
+                    })
+                    .await;
                 },
                 Channel::Category(channel) => {
-                    spawn_named("dispatch::event_handler::category_create", async move {
+                    lane.spawn("dispatch::event_handler::category_create", async move {
                         event_handler.category_create(context, &channel).await;
-                    });
+                    })
+                    .await;
                 },
                 // Private channel create events are no longer sent to bots in the v8 gateway.
                 _ => {},
@@ -419,24 +614,27 @@ async fn handle_event(
             match event.channel {
                 Channel::Private(_) => {},
                 Channel::Guild(channel) => {
-                    spawn_named("dispatch::event_handler::channel_delete", async move {
+                    lane.spawn("dispatch::event_handler::channel_delete", async move {
                         event_handler.channel_delete(context, &channel).await;
-                    });
+                    })
+                    .await;
                 },
                 Channel::Category(channel) => {
-                    spawn_named("dispatch::event_handler::category_delete", async move {
+                    lane.spawn("dispatch::event_handler::category_delete", async move {
                         event_handler.category_delete(context, &channel).await;
-                    });
+                    })
+                    .await;
                 },
             }
         },
         Event::ChannelPinsUpdate(event) => {
-            spawn_named("dispatch::event_handler::channel_pins_update", async move {
+            lane.spawn("dispatch::event_handler::channel_pins_update", async move {
                 event_handler.channel_pins_update(context, event).await;
-            });
+            })
+            .await;
         },
         Event::ChannelUpdate(mut event) => {
-            spawn_named("dispatch::event_handler::channel_update", async move {
+            lane.spawn("dispatch::event_handler::channel_update", async move {
                 feature_cache! {{
                     let old_channel = cache_and_http.cache.as_ref().channel(event.channel.id());
                     update(&cache_and_http, &mut event);
@@ -447,17 +645,20 @@ async fn handle_event(
 
                     event_handler.channel_update(context, event.channel).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::GuildBanAdd(event) => {
-            spawn_named("dispatch::event_handler::guild_ban_addition", async move {
+            lane.spawn("dispatch::event_handler::guild_ban_addition", async move {
                 event_handler.guild_ban_addition(context, event.guild_id, event.user).await;
-            });
+            })
+            .await;
         },
         Event::GuildBanRemove(event) => {
-            spawn_named("dispatch::event_handler::guild_ban_removal", async move {
+            lane.spawn("dispatch::event_handler::guild_ban_removal", async move {
                 event_handler.guild_ban_removal(context, event.guild_id, event.user).await;
-            });
+            })
+            .await;
         },
         Event::GuildCreate(mut event) => {
             #[cfg(feature = "cache")]
@@ -470,68 +671,69 @@ async fn handle_event(
                 let context = context.clone();
 
                 if cache_and_http.cache.unavailable_guilds.is_empty() {
-                    let guild_amount = cache_and_http
-                        .cache
-                        .guilds
-                        .iter()
-                        .map(|i| *i.key())
-                        .collect::<Vec<GuildId>>();
+                    let guild_amount = cache_and_http.cache.backend.guild_ids();
                     let event_handler = Arc::clone(&event_handler);
 
-                    spawn_named("dispatch::event_handler::cache_ready", async move {
+                    lane.spawn("dispatch::event_handler::cache_ready", async move {
                         event_handler.cache_ready(context, guild_amount).await;
-                    });
+                    })
+                    .await;
                 }
             }
 
-            spawn_named("dispatch::event_handler::guild_create", async move {
+            lane.spawn("dispatch::event_handler::guild_create", async move {
                 feature_cache! {{
                     event_handler.guild_create(context, event.guild, _is_new).await;
                 } else {
                     event_handler.guild_create(context, event.guild).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::GuildDelete(mut event) => {
             let _full = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_delete", async move {
+            lane.spawn("dispatch::event_handler::guild_delete", async move {
                 feature_cache! {{
                     event_handler.guild_delete(context, event.guild, _full).await;
                 } else {
                     event_handler.guild_delete(context, event.guild).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::GuildEmojisUpdate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_emojis_update", async move {
+            lane.spawn("dispatch::event_handler::guild_emojis_update", async move {
                 event_handler.guild_emojis_update(context, event.guild_id, event.emojis).await;
-            });
+            })
+            .await;
         },
         Event::GuildIntegrationsUpdate(event) => {
-            spawn_named("dispatch::event_handler::guild_integrations_update", async move {
+            lane.spawn("dispatch::event_handler::guild_integrations_update", async move {
                 event_handler.guild_integrations_update(context, event.guild_id).await;
-            });
+            })
+            .await;
         },
         Event::GuildMemberAdd(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_member_addition", async move {
+            lane.spawn("dispatch::event_handler::guild_member_addition", async move {
                 event_handler.guild_member_addition(context, event.member).await;
-            });
+            })
+            .await;
         },
         Event::GuildMemberRemove(mut event) => {
             let _member = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_member_removal", async move {
+            lane.spawn("dispatch::event_handler::guild_member_removal", async move {
                 feature_cache! {{
                     event_handler.guild_member_removal(context, event.guild_id, event.user, _member).await;
                 } else {
                     event_handler.guild_member_removal(context, event.guild_id, event.user).await;
                 }}
-            });
+            }).await;
         },
         Event::GuildMemberUpdate(mut event) => {
             let _before = update(&cache_and_http, &mut event);
@@ -541,7 +743,7 @@ async fn handle_event(
                 None
             }};
 
-            spawn_named("dispatch::event_handler::guild_member_update", async move {
+            lane.spawn("dispatch::event_handler::guild_member_update", async move {
                 feature_cache! {{
                     if let Some(after) = _after {
                         event_handler.guild_member_update(context, _before, after).await;
@@ -549,60 +751,66 @@ async fn handle_event(
                 } else {
                     event_handler.guild_member_update(context, event).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::GuildMembersChunk(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_members_chunk", async move {
+            lane.spawn("dispatch::event_handler::guild_members_chunk", async move {
                 event_handler.guild_members_chunk(context, event).await;
-            });
+            })
+            .await;
         },
         Event::GuildRoleCreate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_role_create", async move {
+            lane.spawn("dispatch::event_handler::guild_role_create", async move {
                 event_handler.guild_role_create(context, event.role).await;
-            });
+            })
+            .await;
         },
         Event::GuildRoleDelete(mut event) => {
             let _role = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_role_delete", async move {
+            lane.spawn("dispatch::event_handler::guild_role_delete", async move {
                 feature_cache! {{
                     event_handler.guild_role_delete(context, event.guild_id, event.role_id, _role).await;
                 } else {
                     event_handler.guild_role_delete(context, event.guild_id, event.role_id).await;
                 }}
-            });
+            }).await;
         },
         Event::GuildRoleUpdate(mut event) => {
             let _before = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_role_update", async move {
+            lane.spawn("dispatch::event_handler::guild_role_update", async move {
                 feature_cache! {{
                     event_handler.guild_role_update(context, _before, event.role).await;
                 } else {
                     event_handler.guild_role_update(context, event.role).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::GuildStickersUpdate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            tokio::spawn(async move {
+            lane.spawn("dispatch::event_handler::guild_stickers_update", async move {
                 event_handler.guild_stickers_update(context, event.guild_id, event.stickers).await;
-            });
+            })
+            .await;
         },
         Event::GuildUnavailable(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::guild_unavailable", async move {
+            lane.spawn("dispatch::event_handler::guild_unavailable", async move {
                 event_handler.guild_unavailable(context, event.guild_id).await;
-            });
+            })
+            .await;
         },
         Event::GuildUpdate(mut event) => {
-            spawn_named("dispatch::event_handler::guild_update", async move {
+            lane.spawn("dispatch::event_handler::guild_update", async move {
                 feature_cache! {{
                     let before = cache_and_http.cache
                         .guild(event.guild.id);
@@ -615,227 +823,366 @@ async fn handle_event(
 
                     event_handler.guild_update(context, event.guild).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::InviteCreate(event) => {
-            spawn_named("dispatch::event_handler::invite_create", async move {
+            lane.spawn("dispatch::event_handler::invite_create", async move {
                 event_handler.invite_create(context, event).await;
-            });
+            })
+            .await;
         },
         Event::InviteDelete(event) => {
-            spawn_named("dispatch::event_handler::invite_delete", async move {
+            lane.spawn("dispatch::event_handler::invite_delete", async move {
                 event_handler.invite_delete(context, event).await;
-            });
+            })
+            .await;
         },
         // Already handled by the framework check macro
         Event::MessageCreate(_) => {},
         Event::MessageDeleteBulk(event) => {
-            spawn_named("dispatch::event_handler::message_delete_bulk", async move {
+            lane.spawn("dispatch::event_handler::message_delete_bulk", async move {
                 event_handler
                     .message_delete_bulk(context, event.channel_id, event.ids, event.guild_id)
                     .await;
-            });
+            })
+            .await;
         },
         Event::MessageDelete(event) => {
-            spawn_named("dispatch::event_handler::message_delete", async move {
+            lane.spawn("dispatch::event_handler::message_delete", async move {
                 event_handler
                     .message_delete(context, event.channel_id, event.message_id, event.guild_id)
                     .await;
-            });
+            })
+            .await;
         },
         Event::MessageUpdate(mut event) => {
             let _before = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::message_update", async move {
+            lane.spawn("dispatch::event_handler::message_update", async move {
                 feature_cache! {{
                     let _after = cache_and_http.cache.message(event.channel_id, event.id);
                     event_handler.message_update(context, _before, _after, event).await;
                 } else {
                     event_handler.message_update(context, event).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::PresencesReplace(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::presence_replace", async move {
+            lane.spawn("dispatch::event_handler::presence_replace", async move {
                 event_handler.presence_replace(context, event.presences).await;
-            });
+            })
+            .await;
         },
         Event::PresenceUpdate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::presence_update", async move {
+            lane.spawn("dispatch::event_handler::presence_update", async move {
                 event_handler.presence_update(context, event.presence).await;
-            });
+            })
+            .await;
         },
         Event::ReactionAdd(event) => {
-            spawn_named("dispatch::event_handler::reaction_add", async move {
+            lane.spawn("dispatch::event_handler::reaction_add", async move {
                 event_handler.reaction_add(context, event.reaction).await;
-            });
+            })
+            .await;
         },
         Event::ReactionRemove(event) => {
-            spawn_named("dispatch::event_handler::reaction_remove", async move {
+            lane.spawn("dispatch::event_handler::reaction_remove", async move {
                 event_handler.reaction_remove(context, event.reaction).await;
-            });
+            })
+            .await;
         },
         Event::ReactionRemoveAll(event) => {
-            spawn_named("dispatch::event_handler::remove_all", async move {
+            lane.spawn("dispatch::event_handler::remove_all", async move {
                 event_handler
                     .reaction_remove_all(context, event.channel_id, event.message_id)
                     .await;
-            });
+            })
+            .await;
         },
         Event::Ready(mut event) => {
             update(&cache_and_http, &mut event);
-            spawn_named("dispatch::event_handler::ready", async move {
+            lane.spawn("dispatch::event_handler::ready", async move {
                 event_handler.ready(context, event.ready).await;
-            });
+            })
+            .await;
         },
         Event::Resumed(event) => {
-            spawn_named("dispatch::event_handler::resume", async move {
+            lane.spawn("dispatch::event_handler::resume", async move {
                 event_handler.resume(context, event).await;
-            });
+            })
+            .await;
         },
         Event::TypingStart(event) => {
-            spawn_named("dispatch::event_handler::typing_start", async move {
+            lane.spawn("dispatch::event_handler::typing_start", async move {
                 event_handler.typing_start(context, event).await;
-            });
+            })
+            .await;
         },
         Event::Unknown(event) => {
-            spawn_named("dispatch::event_handler::unknown", async move {
+            lane.spawn("dispatch::event_handler::unknown", async move {
                 event_handler.unknown(context, event.kind, event.value).await;
-            });
+            })
+            .await;
         },
         Event::UserUpdate(mut event) => {
             let _before = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::user_update", async move {
+            lane.spawn("dispatch::event_handler::user_update", async move {
                 feature_cache! {{
                     event_handler.user_update(context, _before.expect("missing old user"), event.current_user).await;
                 } else {
                     event_handler.user_update(context, event.current_user).await;
                 }}
-            });
+            }).await;
         },
         Event::VoiceServerUpdate(event) => {
-            spawn_named("dispatch::event_handler::voice_server_update", async move {
+            lane.spawn("dispatch::event_handler::voice_server_update", async move {
                 event_handler.voice_server_update(context, event).await;
-            });
+            })
+            .await;
         },
         Event::VoiceStateUpdate(mut event) => {
             let _before = update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::voice_state_update", async move {
+            lane.spawn("dispatch::event_handler::voice_state_update", async move {
                 feature_cache! {{
                     event_handler.voice_state_update(context, _before, event.voice_state).await;
                 } else {
                     event_handler.voice_state_update(context, event.voice_state).await;
                 }}
-            });
+            })
+            .await;
         },
         Event::WebhookUpdate(event) => {
-            spawn_named("dispatch::event_handler::webhook_update", async move {
+            lane.spawn("dispatch::event_handler::webhook_update", async move {
                 event_handler.webhook_update(context, event.guild_id, event.channel_id).await;
-            });
+            })
+            .await;
         },
         Event::InteractionCreate(event) => {
-            spawn_named("dispatch::event_handler::interaction_create", async move {
+            lane.spawn("dispatch::event_handler::interaction_create", async move {
                 event_handler.interaction_create(context, event.interaction).await;
-            });
+            })
+            .await;
         },
         Event::IntegrationCreate(event) => {
-            spawn_named("dispatch::event_handler::integration_create", async move {
+            lane.spawn("dispatch::event_handler::integration_create", async move {
                 event_handler.integration_create(context, event.integration).await;
-            });
+            })
+            .await;
         },
         Event::IntegrationUpdate(event) => {
-            spawn_named("dispatch::event_handler::integration_update", async move {
+            lane.spawn("dispatch::event_handler::integration_update", async move {
                 event_handler.integration_update(context, event.integration).await;
-            });
+            })
+            .await;
         },
         Event::IntegrationDelete(event) => {
-            spawn_named("dispatch::event_handler::integration_delete", async move {
+            lane.spawn("dispatch::event_handler::integration_delete", async move {
                 event_handler
                     .integration_delete(context, event.id, event.guild_id, event.application_id)
                     .await;
-            });
+            })
+            .await;
         },
         Event::StageInstanceCreate(event) => {
-            spawn_named("dispatch::event_handler::stage_instance_create", async move {
+            lane.spawn("dispatch::event_handler::stage_instance_create", async move {
                 event_handler.stage_instance_create(context, event.stage_instance).await;
-            });
+            })
+            .await;
         },
         Event::StageInstanceUpdate(event) => {
-            spawn_named("dispatch::event_handler::stage_instance_update", async move {
+            lane.spawn("dispatch::event_handler::stage_instance_update", async move {
                 event_handler.stage_instance_update(context, event.stage_instance).await;
-            });
+            })
+            .await;
         },
         Event::StageInstanceDelete(event) => {
-            spawn_named("dispatch::event_handler::stage_instance_delete", async move {
+            lane.spawn("dispatch::event_handler::stage_instance_delete", async move {
                 event_handler.stage_instance_delete(context, event.stage_instance).await;
-            });
+            })
+            .await;
         },
         Event::ThreadCreate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::thread_create", async move {
+            lane.spawn("dispatch::event_handler::thread_create", async move {
                 event_handler.thread_create(context, event.thread).await;
-            });
+            })
+            .await;
         },
         Event::ThreadUpdate(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::thread_update", async move {
+            lane.spawn("dispatch::event_handler::thread_update", async move {
                 event_handler.thread_update(context, event.thread).await;
-            });
+            })
+            .await;
         },
         Event::ThreadDelete(mut event) => {
             update(&cache_and_http, &mut event);
 
-            spawn_named("dispatch::event_handler::thread_delete", async move {
+            lane.spawn("dispatch::event_handler::thread_delete", async move {
                 event_handler.thread_delete(context, event.thread).await;
-            });
+            })
+            .await;
         },
         Event::ThreadListSync(event) => {
-            spawn_named("dispatch::event_handler::thread_list_sync", async move {
+            lane.spawn("dispatch::event_handler::thread_list_sync", async move {
                 event_handler.thread_list_sync(context, event).await;
-            });
+            })
+            .await;
         },
         Event::ThreadMemberUpdate(event) => {
-            spawn_named("dispatch::event_handler::thread_member_update", async move {
+            lane.spawn("dispatch::event_handler::thread_member_update", async move {
                 event_handler.thread_member_update(context, event.member).await;
-            });
+            })
+            .await;
         },
         Event::ThreadMembersUpdate(event) => {
-            spawn_named("dispatch::event_handler::thread_members_update", async move {
+            lane.spawn("dispatch::event_handler::thread_members_update", async move {
                 event_handler.thread_members_update(context, event).await;
-            });
+            })
+            .await;
         },
         Event::GuildScheduledEventCreate(event) => {
-            spawn_named("dispatch::event_handler::guild_scheduled_event_create", async move {
+            lane.spawn("dispatch::event_handler::guild_scheduled_event_create", async move {
                 event_handler.guild_scheduled_event_create(context, event.event).await;
-            });
+            })
+            .await;
         },
         Event::GuildScheduledEventUpdate(event) => {
-            spawn_named("dispatch::event_handler::guild_scheduled_event_update", async move {
+            lane.spawn("dispatch::event_handler::guild_scheduled_event_update", async move {
                 event_handler.guild_scheduled_event_update(context, event.event).await;
-            });
+            })
+            .await;
         },
         Event::GuildScheduledEventDelete(event) => {
-            spawn_named("dispatch::event_handler::guild_scheduled_event_delete", async move {
+            lane.spawn("dispatch::event_handler::guild_scheduled_event_delete", async move {
                 event_handler.guild_scheduled_event_delete(context, event.event).await;
-            });
+            })
+            .await;
         },
         Event::GuildScheduledEventUserAdd(event) => {
-            spawn_named("dispatch::event_handler::guild_scheduled_event_user_add", async move {
+            lane.spawn("dispatch::event_handler::guild_scheduled_event_user_add", async move {
                 event_handler.guild_scheduled_event_user_add(context, event).await;
-            });
+            })
+            .await;
         },
         Event::GuildScheduledEventUserRemove(event) => {
-            spawn_named("dispatch::event_handler::guild_scheduled_event_user_remove", async move {
+            lane.spawn("dispatch::event_handler::guild_scheduled_event_user_remove", async move {
                 event_handler.guild_scheduled_event_user_remove(context, event).await;
-            });
+            })
+            .await;
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::bridge::gateway::event::ShardStageUpdateEvent;
+    use crate::client::bridge::gateway::ShardId;
+    use crate::gateway::ConnectionStage;
+
+    fn event() -> DispatchEvent {
+        DispatchEvent::Client(ClientEvent::ShardStageUpdate(ShardStageUpdateEvent {
+            new: ConnectionStage::Connected,
+            old: ConnectionStage::Connecting,
+            shard_id: ShardId(0),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_sequential_order() {
+        let dispatcher = Dispatcher::new(DispatchMode::Sequential {
+            capacity: 1,
+        });
+        let handled = Arc::new(Mutex::new(Vec::new()));
+
+        for i in 0..5_u64 {
+            let handled = Arc::clone(&handled);
+
+            // Earlier calls take longer, so they would complete last if they
+            // ran concurrently.
+            dispatcher
+                .lane(&event())
+                .spawn("test", async move {
+                    tokio::time::sleep(Duration::from_millis(10 * (5 - i))).await;
+                    handled.lock().unwrap().push(i);
+                })
+                .await;
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        dispatcher.lane(&event()).spawn("test", async move { tx.send(()).unwrap() }).await;
+        rx.await.unwrap();
+
+        assert_eq!(*handled.lock().unwrap(), [0, 1, 2, 3, 4]);
+    }
+
+    #[cfg(feature = "model")]
+    fn guild_event(guild_id: u64) -> DispatchEvent {
+        use crate::model::event::GuildUnavailableEvent;
+        use crate::model::id::GuildId;
+
+        DispatchEvent::Model(Event::GuildUnavailable(GuildUnavailableEvent {
+            guild_id: GuildId(guild_id),
+        }))
+    }
+
+    #[cfg(feature = "model")]
+    #[tokio::test]
+    async fn test_keyed_lanes() {
+        let dispatcher = Dispatcher::new(DispatchMode::Keyed {
+            lanes: 4,
+            capacity: 1,
+        });
+        let lane = |guild_id| dispatcher.lane(&guild_event(guild_id)).0.unwrap();
+
+        assert!(lane(1).same_channel(lane(1)));
+        let other = (2..100).find(|&guild_id| !lane(1).same_channel(lane(guild_id))).unwrap();
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let (unblock_tx, unblock_rx) = tokio::sync::oneshot::channel();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+
+        // The lane of guild 1 is blocked until the event of the other guild was
+        // handled, which would never happen if they shared a lane.
+        let first = Arc::clone(&handled);
+        dispatcher
+            .lane(&guild_event(1))
+            .spawn("test", async move {
+                unblock_rx.await.unwrap();
+                first.lock().unwrap().push(1);
+            })
+            .await;
+
+        let second = Arc::clone(&handled);
+        dispatcher
+            .lane(&guild_event(other))
+            .spawn("test", async move {
+                second.lock().unwrap().push(other);
+                unblock_tx.send(()).unwrap();
+            })
+            .await;
+
+        // Events of the same guild are still handled in order.
+        dispatcher
+            .lane(&guild_event(1))
+            .spawn("test", async move { done_tx.send(()).unwrap() })
+            .await;
+        done_rx.await.unwrap();
+
+        assert_eq!(*handled.lock().unwrap(), [other, 1]);
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::context::Context;
+use super::event_handler::EventHandler;
+use crate::client::bridge::gateway::event::*;
+use crate::http::ratelimiting::RatelimitInfo;
+use crate::json::Value;
+use crate::model::application::command::CommandPermission;
+use crate::model::application::interaction::Interaction;
+use crate::model::guild::automod::{ActionExecution, Rule};
+use crate::model::prelude::*;
+
+/// Generates [`FullEvent`], its dispatch to an [`EventHandler`], and the
+/// [`EventHandler`] forwarding every method to a [`FullEventHandler`].
+///
+/// Events whose [`EventHandler`] method takes its argument by reference are
+/// listed separately, as the variant owns a clone of it.
+macro_rules! full_events {
+    (
+        owned {
+            $(
+                $(#[doc = $doc:literal])*
+                $(#[cfg($cfg:meta)])?
+                $variant:ident => $method:ident { $($field:ident: $ty:ty),* $(,)? },
+            )*
+        }
+        borrowed {
+            $(
+                $(#[doc = $borrowed_doc:literal])*
+                $borrowed_variant:ident => $borrowed_method:ident {
+                    $borrowed_field:ident: $borrowed_ty:ty $(,)?
+                },
+            )*
+        }
+    ) => {
+        /// An event along with the same data, enriched by the cache, which the
+        /// [`EventHandler`] method of the same name is called with.
+        ///
+        /// This allows handling every event in one place, such as for forwarding
+        /// events to a channel or an actor, or for feeding them into tests. See
+        /// [`FullEventHandler`] for receiving events in this form.
+        ///
+        /// Two kinds of calls are left out:
+        ///
+        /// - Rate limits hit by the HTTP client are not gateway events and come
+        ///   without a [`Context`], so they are passed to
+        ///   [`FullEventHandler::ratelimit`] instead.
+        /// - Raw events are handled by the [`RawEventHandler`] before the cache
+        ///   is updated, rather than by the [`EventHandler`]. Set one alongside
+        ///   via [`ClientBuilder::raw_event_handler`] to receive them.
+        ///
+        /// [`RawEventHandler`]: super::RawEventHandler
+        /// [`ClientBuilder::raw_event_handler`]: super::ClientBuilder::raw_event_handler
+        #[allow(clippy::large_enum_variant)]
+        #[derive(Clone, Debug)]
+        #[non_exhaustive]
+        pub enum FullEvent {
+            $(
+                $(#[doc = $doc])*
+                $(#[cfg($cfg)])?
+                $variant { $($field: $ty),* },
+            )*
+            $(
+                $(#[doc = $borrowed_doc])*
+                $borrowed_variant { $borrowed_field: $borrowed_ty },
+            )*
+        }
+
+        impl FullEvent {
+            /// Calls the method of the event handler matching the event, as if the
+            /// event had been received from the gateway.
+            pub async fn dispatch(self, ctx: Context, handler: &dyn EventHandler) {
+                match self {
+                    $(
+                        $(#[cfg($cfg)])?
+                        Self::$variant { $($field),* } => handler.$method(ctx, $($field),*).await,
+                    )*
+                    $(
+                        Self::$borrowed_variant { $borrowed_field } => {
+                            handler.$borrowed_method(ctx, &$borrowed_field).await;
+                        },
+                    )*
+                }
+            }
+        }
+
+        #[async_trait]
+        impl<H: FullEventHandler> EventHandler for FullEventAdapter<H> {
+            $(
+                $(#[cfg($cfg)])?
+                async fn $method(&self, ctx: Context, $($field: $ty),*) {
+                    self.0.dispatch(ctx, FullEvent::$variant { $($field),* }).await;
+                }
+            )*
+            $(
+                async fn $borrowed_method(&self, ctx: Context, $borrowed_field: &$borrowed_ty) {
+                    let event = FullEvent::$borrowed_variant {
+                        $borrowed_field: $borrowed_field.clone(),
+                    };
+
+                    self.0.dispatch(ctx, event).await;
+                }
+            )*
+
+            async fn ratelimit(&self, data: RatelimitInfo) {
+                self.0.ratelimit(data).await;
+            }
+        }
+    };
+}
+
+full_events! {
+    owned {
+        /// An application command's permissions were updated.
+        ApplicationCommandPermissionsUpdate => application_command_permissions_update {
+            permission: CommandPermission,
+        },
+        /// An auto moderation rule was created.
+        AutoModerationRuleCreate => auto_moderation_rule_create { rule: Rule },
+        /// An auto moderation rule was updated.
+        AutoModerationRuleUpdate => auto_moderation_rule_update { rule: Rule },
+        /// An auto moderation rule was deleted.
+        AutoModerationRuleDelete => auto_moderation_rule_delete { rule: Rule },
+        /// An auto moderation rule was triggered and an action was executed.
+        AutoModerationActionExecution => auto_moderation_action_execution {
+            execution: ActionExecution,
+        },
+        /// The cache has received and inserted all data from guilds.
+        #[cfg(feature = "cache")]
+        CacheReady => cache_ready { guilds: Vec<GuildId> },
+        /// A channel's pins were updated.
+        ChannelPinsUpdate => channel_pins_update { pin: ChannelPinsUpdateEvent },
+        /// A channel was updated, along with its old data from the cache.
+        #[cfg(feature = "cache")]
+        ChannelUpdate => channel_update { old: Option<Channel>, new: Channel },
+        /// A channel was updated.
+        #[cfg(not(feature = "cache"))]
+        ChannelUpdate => channel_update { new_data: Channel },
+        /// A user was banned from a guild.
+        GuildBanAddition => guild_ban_addition { guild_id: GuildId, banned_user: User },
+        /// A user was unbanned from a guild.
+        GuildBanRemoval => guild_ban_removal { guild_id: GuildId, unbanned_user: User },
+        /// A guild became available, or the current user joined it, and whether
+        /// the guild is new to the cache.
+        #[cfg(feature = "cache")]
+        GuildCreate => guild_create { guild: Guild, is_new: bool },
+        /// A guild became available, or the current user joined it.
+        #[cfg(not(feature = "cache"))]
+        GuildCreate => guild_create { guild: Guild },
+        /// A guild became unavailable, or the current user left it, along with
+        /// its full data from the cache.
+        #[cfg(feature = "cache")]
+        GuildDelete => guild_delete { incomplete: UnavailableGuild, full: Option<Guild> },
+        /// A guild became unavailable, or the current user left it.
+        #[cfg(not(feature = "cache"))]
+        GuildDelete => guild_delete { incomplete: UnavailableGuild },
+        /// A guild's emojis were updated.
+        GuildEmojisUpdate => guild_emojis_update {
+            guild_id: GuildId,
+            current_state: HashMap<EmojiId, Emoji>,
+        },
+        /// A guild's integration was added, updated or removed.
+        GuildIntegrationsUpdate => guild_integrations_update { guild_id: GuildId },
+        /// A user joined a guild.
+        GuildMemberAddition => guild_member_addition { new_member: Member },
+        /// A user left a guild, along with their member data from the cache.
+        #[cfg(feature = "cache")]
+        GuildMemberRemoval => guild_member_removal {
+            guild_id: GuildId,
+            user: User,
+            member_data_if_available: Option<Member>,
+        },
+        /// A user left a guild.
+        #[cfg(not(feature = "cache"))]
+        GuildMemberRemoval => guild_member_removal { guild_id: GuildId, kicked: User },
+        /// A member was updated, along with their old data from the cache.
+        #[cfg(feature = "cache")]
+        GuildMemberUpdate => guild_member_update { old_if_available: Option<Member>, new: Member },
+        /// A member was updated.
+        #[cfg(not(feature = "cache"))]
+        GuildMemberUpdate => guild_member_update { new: GuildMemberUpdateEvent },
+        /// Requested members of a guild were received.
+        GuildMembersChunk => guild_members_chunk { chunk: GuildMembersChunkEvent },
+        /// A role was created.
+        GuildRoleCreate => guild_role_create { new: Role },
+        /// A role was deleted, along with its data from the cache.
+        #[cfg(feature = "cache")]
+        GuildRoleDelete => guild_role_delete {
+            guild_id: GuildId,
+            removed_role_id: RoleId,
+            removed_role_data_if_available: Option<Role>,
+        },
+        /// A role was deleted.
+        #[cfg(not(feature = "cache"))]
+        GuildRoleDelete => guild_role_delete { guild_id: GuildId, removed_role_id: RoleId },
+        /// A role was updated, along with its old data from the cache.
+        #[cfg(feature = "cache")]
+        GuildRoleUpdate => guild_role_update { old_data_if_available: Option<Role>, new: Role },
+        /// A role was updated.
+        #[cfg(not(feature = "cache"))]
+        GuildRoleUpdate => guild_role_update { new_data: Role },
+        /// A guild's stickers were updated.
+        GuildStickersUpdate => guild_stickers_update {
+            guild_id: GuildId,
+            current_state: HashMap<StickerId, Sticker>,
+        },
+        /// A guild became unavailable.
+        GuildUnavailable => guild_unavailable { guild_id: GuildId },
+        /// A guild was updated, along with its old data from the cache.
+        #[cfg(feature = "cache")]
+        GuildUpdate => guild_update {
+            old_data_if_available: Option<Guild>,
+            new_but_incomplete: PartialGuild,
+        },
+        /// A guild was updated.
+        #[cfg(not(feature = "cache"))]
+        GuildUpdate => guild_update { new_but_incomplete_data: PartialGuild },
+        /// An invite was created.
+        InviteCreate => invite_create { data: InviteCreateEvent },
+        /// An invite was deleted.
+        InviteDelete => invite_delete { data: InviteDeleteEvent },
+        /// A message was created.
+        Message => message { new_message: Message },
+        /// A message was deleted.
+        MessageDelete => message_delete {
+            channel_id: ChannelId,
+            deleted_message_id: MessageId,
+            guild_id: Option<GuildId>,
+        },
+        /// Multiple messages were deleted at once.
+        MessageDeleteBulk => message_delete_bulk {
+            channel_id: ChannelId,
+            multiple_deleted_messages_ids: Vec<MessageId>,
+            guild_id: Option<GuildId>,
+        },
+        /// A message was updated, along with its old and new data from the
+        /// cache.
+        #[cfg(feature = "cache")]
+        MessageUpdate => message_update {
+            old_if_available: Option<Message>,
+            new: Option<Message>,
+            event: MessageUpdateEvent,
+        },
+        /// A message was updated.
+        #[cfg(not(feature = "cache"))]
+        MessageUpdate => message_update { new_data: MessageUpdateEvent },
+        /// A reaction was added to a message.
+        ReactionAdd => reaction_add { add_reaction: Reaction },
+        /// A reaction was removed from a message.
+        ReactionRemove => reaction_remove { removed_reaction: Reaction },
+        /// All reactions were removed from a message.
+        ReactionRemoveAll => reaction_remove_all {
+            channel_id: ChannelId,
+            removed_from_message_id: MessageId,
+        },
+        /// Presences were replaced.
+        PresenceReplace => presence_replace { presences: Vec<Presence> },
+        /// A user's presence was updated.
+        PresenceUpdate => presence_update { new_data: Presence },
+        /// The shard started a new session.
+        Ready => ready { data_about_bot: Ready },
+        /// The shard resumed its session.
+        Resume => resume { event: ResumedEvent },
+        /// The shard's connection stage changed.
+        ShardStageUpdate => shard_stage_update { event: ShardStageUpdateEvent },
+        /// A user started typing.
+        TypingStart => typing_start { event: TypingStartEvent },
+        /// An event unknown to serenity was received.
+        Unknown => unknown { name: String, raw: Value },
+        /// The current user was updated, along with its old data from the
+        /// cache.
+        #[cfg(feature = "cache")]
+        UserUpdate => user_update { old_data: CurrentUser, new: CurrentUser },
+        /// The current user was updated.
+        #[cfg(not(feature = "cache"))]
+        UserUpdate => user_update { new_data: CurrentUser },
+        /// A guild's voice server was updated.
+        VoiceServerUpdate => voice_server_update { event: VoiceServerUpdateEvent },
+        /// A user's voice state was updated, along with its old data from the
+        /// cache.
+        #[cfg(feature = "cache")]
+        VoiceStateUpdate => voice_state_update { old: Option<VoiceState>, new: VoiceState },
+        /// A user's voice state was updated.
+        #[cfg(not(feature = "cache"))]
+        VoiceStateUpdate => voice_state_update { new: VoiceState },
+        /// A channel's webhooks were updated.
+        WebhookUpdate => webhook_update { guild_id: GuildId, belongs_to_channel_id: ChannelId },
+        /// An interaction was created.
+        InteractionCreate => interaction_create { interaction: Interaction },
+        /// A guild integration was created.
+        IntegrationCreate => integration_create { integration: Integration },
+        /// A guild integration was updated.
+        IntegrationUpdate => integration_update { integration: Integration },
+        /// A guild integration was deleted.
+        IntegrationDelete => integration_delete {
+            integration_id: IntegrationId,
+            guild_id: GuildId,
+            application_id: Option<ApplicationId>,
+        },
+        /// A stage instance was created.
+        StageInstanceCreate => stage_instance_create { stage_instance: StageInstance },
+        /// A stage instance was updated.
+        StageInstanceUpdate => stage_instance_update { stage_instance: StageInstance },
+        /// A stage instance was deleted.
+        StageInstanceDelete => stage_instance_delete { stage_instance: StageInstance },
+        /// A thread was created, or the current user was added to it.
+        ThreadCreate => thread_create { thread: GuildChannel },
+        /// A thread was updated.
+        ThreadUpdate => thread_update { thread: GuildChannel },
+        /// A thread was deleted.
+        ThreadDelete => thread_delete { thread: PartialGuildChannel },
+        /// The current user gained access to channels' threads.
+        ThreadListSync => thread_list_sync { thread_list_sync: ThreadListSyncEvent },
+        /// The current user's thread member was updated.
+        ThreadMemberUpdate => thread_member_update { thread_member: ThreadMember },
+        /// Members were added to or removed from a thread.
+        ThreadMembersUpdate => thread_members_update {
+            thread_members_update: ThreadMembersUpdateEvent,
+        },
+        /// A scheduled event was created.
+        GuildScheduledEventCreate => guild_scheduled_event_create { event: ScheduledEvent },
+        /// A scheduled event was updated.
+        GuildScheduledEventUpdate => guild_scheduled_event_update { event: ScheduledEvent },
+        /// A scheduled event was deleted.
+        GuildScheduledEventDelete => guild_scheduled_event_delete { event: ScheduledEvent },
+        /// A member subscribed to a scheduled event.
+        GuildScheduledEventUserAdd => guild_scheduled_event_user_add {
+            subscribed: GuildScheduledEventUserAddEvent,
+        },
+        /// A member unsubscribed from a scheduled event.
+        GuildScheduledEventUserRemove => guild_scheduled_event_user_remove {
+            unsubscribed: GuildScheduledEventUserRemoveEvent,
+        },
+    }
+    borrowed {
+        /// A channel was created.
+        ChannelCreate => channel_create { channel: GuildChannel },
+        /// A channel category was created.
+        CategoryCreate => category_create { category: ChannelCategory },
+        /// A channel category was deleted.
+        CategoryDelete => category_delete { category: ChannelCategory },
+        /// A channel was deleted.
+        ChannelDelete => channel_delete { channel: GuildChannel },
+    }
+}
+
+/// A trait for handling every event in one method, as a [`FullEvent`].
+///
+/// This is an alternative to the [`EventHandler`], set with
+/// [`ClientBuilder::full_event_handler`], and receives events with the same
+/// data.
+///
+/// # Examples
+///
+/// Forward messages to a channel:
+///
+/// ```rust,no_run
+/// use serenity::async_trait;
+/// use serenity::client::{Context, FullEvent, FullEventHandler};
+/// use serenity::model::channel::Message;
+/// use tokio::sync::mpsc::UnboundedSender;
+///
+/// struct Forwarder(UnboundedSender<Message>);
+///
+/// #[async_trait]
+/// impl FullEventHandler for Forwarder {
+///     async fn dispatch(&self, _ctx: Context, event: FullEvent) {
+///         if let FullEvent::Message {
+///             new_message,
+///         } = event
+///         {
+///             let _ = self.0.send(new_message);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`ClientBuilder::full_event_handler`]: super::ClientBuilder::full_event_handler
+#[async_trait]
+pub trait FullEventHandler: Send + Sync {
+    /// Dispatched when any event occurs.
+    async fn dispatch(&self, ctx: Context, event: FullEvent);
+
+    /// Dispatched when an HTTP rate limit is hit, as with
+    /// [`EventHandler::ratelimit`].
+    async fn ratelimit(&self, _data: RatelimitInfo) {}
+}
+
+/// Forwards the calls of every [`EventHandler`] method to a
+/// [`FullEventHandler`].
+pub(crate) struct FullEventAdapter<H>(pub(crate) H);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<FullEvent>>, Mutex<Vec<RatelimitInfo>>);
+
+    #[async_trait]
+    impl FullEventHandler for Recorder {
+        async fn dispatch(&self, _ctx: Context, event: FullEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+
+        async fn ratelimit(&self, data: RatelimitInfo) {
+            self.1.lock().unwrap().push(data);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let adapter = FullEventAdapter(Recorder::default());
+        let event = FullEvent::Unknown {
+            name: "A".to_string(),
+            raw: crate::json::NULL,
+        };
+
+        event.dispatch(Context::test(), &adapter).await;
+        adapter.guild_integrations_update(Context::test(), GuildId(5)).await;
+
+        let events = adapter.0 .0.lock().unwrap();
+        assert!(matches!(&events[0], FullEvent::Unknown { name, .. } if name == "A"));
+        assert!(matches!(events[1], FullEvent::GuildIntegrationsUpdate {
+            guild_id: GuildId(5)
+        }));
+    }
+    #[tokio::test]
+    async fn test_ratelimit() {
+        let adapter = FullEventAdapter(Recorder::default());
+
+        adapter
+            .ratelimit(RatelimitInfo {
+                timeout: std::time::Duration::from_secs(1),
+                limit: 5,
+                method: crate::http::LightMethod::Get,
+                path: "/gateway".to_string(),
+                global: false,
+                scope: None,
+            })
+            .await;
+
+        assert_eq!(adapter.0 .1.lock().unwrap()[0].path, "/gateway");
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+
+use super::context::Context;
+use crate::model::event::Event;
+
+/// Wraps the dispatch of every event received from the gateway, before the
+/// cache is updated and the event reaches the [`EventHandler`], the
+/// [`RawEventHandler`] and the framework.
+///
+/// Middleware is registered with [`ClientBuilder::middleware`], and runs in
+/// the order it was registered. Each middleware passes the event on by calling
+/// [`Next::run`], and may modify the event beforehand, or drop it by not
+/// calling it at all.
+///
+/// Calls of the [`EventHandler`] and framework commands run on their own
+/// tasks, as configured by the [`DispatchMode`], so [`Next::run`] returns once
+/// they are started. They do run in the [tracing span] [`Next::run`] was
+/// called in.
+///
+/// # Examples
+///
+/// Drop events of a guild while its features are disabled, and count the
+/// events of each type:
+///
+/// ```rust,no_run
+/// use std::collections::BTreeMap;
+/// use std::sync::Mutex;
+///
+/// use serenity::async_trait;
+/// use serenity::client::{Context, EventMiddleware, Next};
+/// use serenity::model::event::{Event, EventType, RelatedId};
+/// use serenity::model::id::GuildId;
+///
+/// struct Middleware {
+///     disabled_guild: GuildId,
+///     counts: Mutex<BTreeMap<EventType, u64>>,
+/// }
+///
+/// #[async_trait]
+/// impl EventMiddleware for Middleware {
+///     async fn handle(&self, ctx: Context, event: Event, next: Next<'_>) {
+///         if let RelatedId::Some(guild_id) = event.guild_id() {
+///             if guild_id == self.disabled_guild {
+///                 return;
+///             }
+///         }
+///
+///         *self.counts.lock().unwrap().entry(event.event_type()).or_default() += 1;
+///
+///         next.run(ctx, event).await;
+///     }
+/// }
+/// ```
+///
+/// [`EventHandler`]: super::EventHandler
+/// [`RawEventHandler`]: super::RawEventHandler
+/// [`ClientBuilder::middleware`]: super::ClientBuilder::middleware
+/// [`DispatchMode`]: super::DispatchMode
+/// [tracing span]: tracing::Span
+#[async_trait]
+pub trait EventMiddleware: Send + Sync {
+    /// Handles the event, passing it on by calling [`Next::run`].
+    async fn handle(&self, ctx: Context, event: Event, next: Next<'_>);
+}
+
+/// The rest of the middleware chain, ending in the dispatch of the event.
+pub struct Next<'a> {
+    middleware: &'a [Arc<dyn EventMiddleware>],
+    dispatch: &'a (dyn Fn(Event) -> BoxFuture<'a, ()> + Send + Sync),
+}
+
+impl<'a> Next<'a> {
+    /// Creates a chain of the middleware, ending in `dispatch`, which is
+    /// called with the event if every middleware passes it on.
+    ///
+    /// The client creates the chain when dispatching events. This is useful
+    /// to test middleware on its own, passing a `dispatch` function recording
+    /// the events it is called with.
+    pub fn new(
+        middleware: &'a [Arc<dyn EventMiddleware>],
+        dispatch: &'a (dyn Fn(Event) -> BoxFuture<'a, ()> + Send + Sync),
+    ) -> Self {
+        Self {
+            middleware,
+            dispatch,
+        }
+    }
+
+    /// Passes the event on to the next middleware, or dispatches it if this is
+    /// the last one.
+    pub async fn run(self, ctx: Context, event: Event) {
+        match self.middleware.split_first() {
+            Some((middleware, rest)) => {
+                middleware.handle(ctx, event, Next::new(rest, self.dispatch)).await;
+            },
+            None => (self.dispatch)(event).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures::future::FutureExt;
+
+    use super::*;
+    use crate::model::event::UnknownEvent;
+
+    fn unknown(kind: &str) -> Event {
+        Event::Unknown(UnknownEvent {
+            kind: kind.to_string(),
+            value: crate::json::NULL,
+        })
+    }
+
+    fn kind(event: &Event) -> String {
+        match event {
+            Event::Unknown(event) => event.kind.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Renames `A` events to `B`.
+    struct Rename;
+
+    #[async_trait]
+    impl EventMiddleware for Rename {
+        async fn handle(&self, ctx: Context, event: Event, next: Next<'_>) {
+            let event = if kind(&event) == "A" { unknown("B") } else { event };
+
+            next.run(ctx, event).await;
+        }
+    }
+
+    /// Drops `C` events.
+    struct Filter;
+
+    #[async_trait]
+    impl EventMiddleware for Filter {
+        async fn handle(&self, ctx: Context, event: Event, next: Next<'_>) {
+            if kind(&event) != "C" {
+                next.run(ctx, event).await;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain() {
+        let middleware: Vec<Arc<dyn EventMiddleware>> = vec![Arc::new(Rename), Arc::new(Filter)];
+        let dispatched = Mutex::new(Vec::new());
+        let dispatch = |event: Event| {
+            dispatched.lock().unwrap().push(kind(&event));
+            async {}.boxed()
+        };
+
+        for kind in ["A", "C", "D"] {
+            Next::new(&middleware, &dispatch).run(Context::test(), unknown(kind)).await;
+        }
+
+        assert_eq!(*dispatched.lock().unwrap(), ["B", "D"]);
+    }
+}
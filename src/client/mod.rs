@@ -24,6 +24,12 @@ mod dispatch;
 mod error;
 #[cfg(feature = "gateway")]
 mod event_handler;
+#[cfg(feature = "gateway")]
+mod full_event;
+#[cfg(feature = "gateway")]
+mod middleware;
+#[cfg(feature = "gateway")]
+pub mod recording;
 
 use std::future::Future;
 use std::pin::Pin;
@@ -45,11 +51,21 @@ use self::bridge::gateway::{
 #[cfg(feature = "voice")]
 use self::bridge::voice::VoiceGatewayManager;
 pub use self::context::Context;
+#[cfg(feature = "gateway")]
+pub use self::dispatch::DispatchMode;
 pub use self::error::Error as ClientError;
 #[cfg(feature = "gateway")]
 pub use self::event_handler::{EventHandler, RawEventHandler};
 #[cfg(feature = "gateway")]
-use super::gateway::GatewayError;
+use self::full_event::FullEventAdapter;
+#[cfg(feature = "gateway")]
+pub use self::full_event::{FullEvent, FullEventHandler};
+#[cfg(feature = "gateway")]
+pub use self::middleware::{EventMiddleware, Next};
+#[cfg(feature = "gateway")]
+use self::recording::GatewayRecorder;
+#[cfg(feature = "gateway")]
+use super::gateway::{GatewayEncoding, GatewayError, ShardSessionState, TransportCompression};
 #[cfg(feature = "cache")]
 pub use crate::cache::Cache;
 #[cfg(feature = "cache")]
@@ -58,6 +74,8 @@ use crate::cache::Settings as CacheSettings;
 use crate::framework::Framework;
 use crate::http::Http;
 use crate::internal::prelude::*;
+#[cfg(feature = "cache")]
+use crate::internal::tokio::spawn_named;
 #[cfg(feature = "gateway")]
 use crate::model::gateway::GatewayIntents;
 use crate::model::id::ApplicationId;
@@ -73,6 +91,10 @@ pub struct ClientBuilder {
     http: Option<Http>,
     fut: Option<BoxFuture<'static, Result<Client>>>,
     intents: GatewayIntents,
+    dispatch_mode: DispatchMode,
+    compression: TransportCompression,
+    encoding: GatewayEncoding,
+    session_states: Vec<ShardSessionState>,
     #[cfg(feature = "cache")]
     cache_settings: Option<CacheSettings>,
     #[cfg(feature = "framework")]
@@ -81,6 +103,8 @@ pub struct ClientBuilder {
     voice_manager: Option<Arc<dyn VoiceGatewayManager + Send + Sync + 'static>>,
     event_handler: Option<Arc<dyn EventHandler>>,
     raw_event_handler: Option<Arc<dyn RawEventHandler>>,
+    middleware: Vec<Arc<dyn EventMiddleware>>,
+    gateway_recorder: Option<Arc<dyn GatewayRecorder>>,
 }
 
 #[cfg(feature = "gateway")]
@@ -91,6 +115,10 @@ impl ClientBuilder {
             http: Some(http),
             fut: None,
             intents,
+            dispatch_mode: DispatchMode::default(),
+            compression: TransportCompression::default(),
+            encoding: GatewayEncoding::default(),
+            session_states: Vec::new(),
             #[cfg(feature = "cache")]
             cache_settings: Some(CacheSettings::new()),
             #[cfg(feature = "framework")]
@@ -99,6 +127,8 @@ impl ClientBuilder {
             voice_manager: None,
             event_handler: None,
             raw_event_handler: None,
+            middleware: Vec::new(),
+            gateway_recorder: None,
         }
     }
 
@@ -310,6 +340,71 @@ impl ClientBuilder {
         self.intents
     }
 
+    /// Sets how events are dispatched to the event handler.
+    ///
+    /// Defaults to [`DispatchMode::Concurrent`]. Use
+    /// [`DispatchMode::Sequential`] or [`DispatchMode::Keyed`] if the event
+    /// handler relies on events being handled in the order they happened.
+    pub fn dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        self.dispatch_mode = dispatch_mode;
+
+        self
+    }
+
+    /// Gets the dispatch mode. See [`Self::dispatch_mode`] for more info.
+    pub fn get_dispatch_mode(&self) -> DispatchMode {
+        self.dispatch_mode
+    }
+
+    /// Sets the compression shards use to receive payloads from the gateway.
+    ///
+    /// Defaults to [`TransportCompression::Payload`]. Larger bots may prefer
+    /// [`TransportCompression::ZlibStream`], which saves bandwidth by
+    /// compressing the whole connection.
+    pub fn transport_compression(mut self, compression: TransportCompression) -> Self {
+        self.compression = compression;
+
+        self
+    }
+
+    /// Gets the transport compression. See [`Self::transport_compression`] for
+    /// more info.
+    pub fn get_transport_compression(&self) -> TransportCompression {
+        self.compression
+    }
+
+    /// Sets the encoding shards use to exchange payloads with the gateway.
+    ///
+    /// Defaults to [`GatewayEncoding::Json`]. Other encodings are available
+    /// through their respective features, such as `etf`.
+    pub fn gateway_encoding(mut self, encoding: GatewayEncoding) -> Self {
+        self.encoding = encoding;
+
+        self
+    }
+
+    /// Gets the gateway encoding. See [`Self::gateway_encoding`] for more info.
+    pub fn get_gateway_encoding(&self) -> GatewayEncoding {
+        self.encoding
+    }
+
+    /// Sets the states of sessions to resume when starting the shards, such as
+    /// those returned by [`ShardManager::shutdown_all_resumable`] before a
+    /// restart.
+    ///
+    /// Shards whose session can't be resumed, for example because it expired or
+    /// the number of shards changed, identify as usual.
+    pub fn session_states(mut self, session_states: Vec<ShardSessionState>) -> Self {
+        self.session_states = session_states;
+
+        self
+    }
+
+    /// Gets the session states. See [`Self::session_states`] for more info.
+    pub fn get_session_states(&self) -> &[ShardSessionState] {
+        &self.session_states
+    }
+
     /// Sets an event handler with multiple methods for each possible event.
     pub fn event_handler<H: EventHandler + 'static>(mut self, event_handler: H) -> Self {
         self.event_handler = Some(Arc::new(event_handler));
@@ -327,6 +422,14 @@ impl ClientBuilder {
         self
     }
 
+    /// Sets an event handler with a single method where all events will be
+    /// dispatched as a [`FullEvent`], in place of the [`Self::event_handler`].
+    pub fn full_event_handler<H: FullEventHandler + 'static>(mut self, event_handler: H) -> Self {
+        self.event_handler = Some(Arc::new(FullEventAdapter(event_handler)));
+
+        self
+    }
+
     /// Gets the event handler, if already initialized. See [`Self::event_handler`] for more info.
     pub fn get_event_handler(&self) -> Option<Arc<dyn EventHandler>> {
         self.event_handler.clone()
@@ -345,6 +448,44 @@ impl ClientBuilder {
     pub fn get_raw_event_handler(&self) -> Option<Arc<dyn RawEventHandler>> {
         self.raw_event_handler.clone()
     }
+
+    /// Adds middleware wrapping the dispatch of every event, after that which
+    /// was added before. See [`EventMiddleware`] for more info.
+    pub fn middleware<M: EventMiddleware + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Arc::new(middleware));
+
+        self
+    }
+
+    /// Adds middleware wrapping the dispatch of every event. Passed by Arc.
+    pub fn middleware_arc(mut self, middleware: Arc<dyn EventMiddleware>) -> Self {
+        self.middleware.push(middleware);
+
+        self
+    }
+
+    /// Gets the middleware, in the order it runs in. See [`Self::middleware`]
+    /// for more info.
+    pub fn get_middleware(&self) -> &[Arc<dyn EventMiddleware>] {
+        &self.middleware
+    }
+
+    /// Sets a recorder called with every payload received from the gateway,
+    /// such as a [`JsonLinesRecorder`] for replaying them later. See the
+    /// [`recording`] module for more info.
+    ///
+    /// [`JsonLinesRecorder`]: recording::JsonLinesRecorder
+    pub fn gateway_recorder<R: GatewayRecorder + 'static>(mut self, recorder: R) -> Self {
+        self.gateway_recorder = Some(Arc::new(recorder));
+
+        self
+    }
+
+    /// Gets the gateway recorder, if already initialized. See
+    /// [`Self::gateway_recorder`] for more info.
+    pub fn get_gateway_recorder(&self) -> Option<Arc<dyn GatewayRecorder>> {
+        self.gateway_recorder.clone()
+    }
 }
 
 #[cfg(feature = "gateway")]
@@ -362,7 +503,13 @@ impl Future for ClientBuilder {
                 If you don't want to use the command framework, disable default features and specify all features you want to use.");
             let event_handler = self.event_handler.take();
             let raw_event_handler = self.raw_event_handler.take();
+            let middleware = std::mem::take(&mut self.middleware);
+            let gateway_recorder = self.gateway_recorder.take();
             let intents = self.intents;
+            let dispatch_mode = self.dispatch_mode;
+            let compression = self.compression;
+            let encoding = self.encoding;
+            let session_states = std::mem::take(&mut self.session_states);
 
             let mut http = self.http.take().unwrap();
             if let Some(event_handler) = event_handler.clone() {
@@ -383,6 +530,22 @@ impl Future for ClientBuilder {
             });
 
             self.fut = Some(Box::pin(async move {
+                #[cfg(feature = "cache")]
+                if let Some(interval) = cache_and_http.cache.settings().sweep_interval {
+                    let cache = Arc::downgrade(&cache_and_http.cache);
+
+                    spawn_named("cache::sweep", async move {
+                        loop {
+                            tokio::time::sleep(interval).await;
+
+                            match cache.upgrade() {
+                                Some(cache) => cache.sweep(),
+                                None => break,
+                            }
+                        }
+                    });
+                }
+
                 let ws_url = Arc::new(Mutex::new(match http.get_gateway().await {
                     Ok(response) => response.url,
                     Err(err) => {
@@ -396,6 +559,8 @@ impl Future for ClientBuilder {
                         data: &data,
                         event_handler: &event_handler,
                         raw_event_handler: &raw_event_handler,
+                        middleware: &middleware,
+                        gateway_recorder: &gateway_recorder,
                         #[cfg(feature = "framework")]
                         framework: &framework,
                         shard_index: 0,
@@ -406,6 +571,10 @@ impl Future for ClientBuilder {
                         ws_url: &ws_url,
                         cache_and_http: &cache_and_http,
                         intents,
+                        dispatch_mode,
+                        compression,
+                        encoding,
+                        session_states,
                     })
                     .await
                 };
@@ -0,0 +1,527 @@
+//! Recording the payloads received from the gateway, and replaying them
+//! offline to reproduce bugs.
+//!
+//! # Examples
+//!
+//! Record the payloads received by a bot:
+//!
+//! ```rust,no_run
+//! use serenity::client::recording::JsonLinesRecorder;
+//! use serenity::prelude::*;
+//! # struct Handler;
+//! # impl EventHandler for Handler {}
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let recorder = JsonLinesRecorder::create("gateway.jsonl")?;
+//! let mut client = Client::builder("token", GatewayIntents::default())
+//!     .event_handler(Handler)
+//!     .gateway_recorder(recorder)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Then replay them against the same event handler:
+//!
+//! ```rust,no_run
+//! use std::fs::File;
+//! use std::io::BufReader;
+//! use std::sync::Arc;
+//!
+//! use serenity::client::recording::Replayer;
+//! use serenity::http::Http;
+//! # use serenity::prelude::*;
+//! # struct Handler;
+//! # impl EventHandler for Handler {}
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let replayer = Replayer::new(Arc::new(Http::new("token"))).event_handler(Handler);
+//! replayer.replay(BufReader::new(File::open("gateway.jsonl")?)).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, LineWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::{fmt, thread};
+
+#[cfg(feature = "framework")]
+use async_trait::async_trait;
+use futures::channel::mpsc::{self, UnboundedSender as Sender};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{Mutex, RwLock};
+use tracing::warn;
+use typemap_rev::TypeMap;
+
+use super::dispatch::{dispatch, DispatchEvent, DispatchMode, Dispatcher};
+#[cfg(feature = "framework")]
+use super::Context;
+use super::{EventHandler, RawEventHandler};
+#[cfg(feature = "cache")]
+use crate::cache::Cache;
+#[cfg(feature = "framework")]
+use crate::framework::Framework;
+use crate::gateway::{InterMessage, ShardSessionState, ShardState};
+use crate::http::Http;
+use crate::internal::prelude::*;
+#[cfg(feature = "framework")]
+use crate::model::channel::Message;
+use crate::model::event::GatewayEvent;
+use crate::model::Timestamp;
+use crate::{json, CacheAndHttp};
+
+/// Called with every payload received from the gateway by a [`ShardRunner`],
+/// before it is deserialized.
+///
+/// This is called on the shard's task, and should return quickly, without
+/// blocking on IO.
+///
+/// [`ShardRunner`]: super::bridge::gateway::ShardRunner
+pub trait GatewayRecorder: Send + Sync {
+    /// Records the payload received by the shard, given its
+    /// [`Shard::shard_info`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload could not be recorded. The shard logs
+    /// it, and keeps running.
+    ///
+    /// [`Shard::shard_info`]: crate::gateway::Shard::shard_info
+    fn record(&self, shard_info: [u64; 2], payload: &Value) -> Result<()>;
+}
+
+/// A payload received from the gateway, as recorded by a
+/// [`JsonLinesRecorder`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct RecordedFrame {
+    /// When the payload was received.
+    pub timestamp: Timestamp,
+    /// The Id of the shard which received the payload.
+    pub shard_id: u64,
+    /// The total number of shards the bot was running.
+    pub shard_total: u64,
+    /// The payload, as received from the gateway.
+    pub payload: Value,
+}
+
+#[derive(Serialize)]
+struct RecordedFrameRef<'a> {
+    timestamp: Timestamp,
+    shard_id: u64,
+    shard_total: u64,
+    payload: &'a Value,
+}
+
+/// Records payloads as [`RecordedFrame`]s, one JSON object per line.
+///
+/// Lines are written on a dedicated thread, so that the shard is not blocked
+/// while they are. If writing a line fails, the error is logged and the
+/// recording stops, and any further calls to [`GatewayRecorder::record`]
+/// return an error.
+pub struct JsonLinesRecorder {
+    tx: UnboundedSender<String>,
+}
+
+impl JsonLinesRecorder {
+    /// Creates a recorder writing to the given writer.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        let (tx, rx) = unbounded_channel();
+        thread::spawn(move || write_lines(writer, rx));
+
+        Self {
+            tx,
+        }
+    }
+
+    /// Creates a recorder writing to the file at the given path, replacing it
+    /// if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`std::io::Error`] if the file could not be created.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(LineWriter::new(File::create(path)?)))
+    }
+}
+
+impl GatewayRecorder for JsonLinesRecorder {
+    /// Queues the payload to be written by the recorder's thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if the payload could not be serialized, or
+    /// [`Error::Io`] if the recording stopped.
+    fn record(&self, shard_info: [u64; 2], payload: &Value) -> Result<()> {
+        let frame = RecordedFrameRef {
+            timestamp: Timestamp::now(),
+            shard_id: shard_info[0],
+            shard_total: shard_info[1],
+            payload,
+        };
+
+        let line = json::to_string(&frame)?;
+
+        self.tx
+            .send(line)
+            .map_err(|_| Error::Io(io::Error::new(io::ErrorKind::BrokenPipe, "recording stopped")))
+    }
+}
+
+/// Writes the lines received from a [`JsonLinesRecorder`] until it is dropped,
+/// or writing fails.
+fn write_lines(mut writer: impl Write, mut rx: UnboundedReceiver<String>) {
+    while let Some(line) = rx.blocking_recv() {
+        if let Err(why) = writeln!(writer, "{}", line) {
+            warn!("[JsonLinesRecorder] Failed to record payload, stopping: {:?}", why);
+
+            return;
+        }
+    }
+
+    if let Err(why) = writer.flush() {
+        warn!("[JsonLinesRecorder] Failed to flush recording: {:?}", why);
+    }
+}
+
+impl fmt::Debug for JsonLinesRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLinesRecorder").finish_non_exhaustive()
+    }
+}
+
+/// Replays recorded payloads through the cache, the event handlers and the
+/// framework, as if they were received by a shard, without connecting to the
+/// gateway.
+///
+/// The handlers are given a [`Context`] with the [`Http`] client passed to
+/// [`Self::new`], so any requests they make are sent to Discord, unless the
+/// client uses a mocked transport. Messages to the shard, such as presence
+/// updates, are discarded.
+///
+/// Payloads are handled as by [`Shard::handle_event`] of a shard per recorded
+/// shard Id, as they would be by a [`ShardRunner`], so that the shard's state,
+/// such as its session and sequence number, follows the recording. See
+/// [`Self::session_state`]. The actions the shards request in response, such
+/// as identifying or heartbeating, are not performed, since there is no
+/// connection to the gateway.
+///
+/// The [`EventMiddleware`] and collectors are not replayed.
+///
+/// [`Context`]: super::Context
+/// [`Shard::handle_event`]: crate::gateway::Shard::handle_event
+/// [`ShardRunner`]: super::bridge::gateway::ShardRunner
+/// [`EventMiddleware`]: super::EventMiddleware
+pub struct Replayer {
+    data: Arc<RwLock<TypeMap>>,
+    event_handler: Option<Arc<dyn EventHandler>>,
+    raw_event_handler: Option<Arc<dyn RawEventHandler>>,
+    #[cfg(feature = "framework")]
+    framework: Arc<dyn Framework + Send + Sync>,
+    cache_and_http: Arc<CacheAndHttp>,
+    runner_tx: Sender<InterMessage>,
+    dispatcher: Dispatcher,
+    shards: Mutex<HashMap<u64, ShardState>>,
+}
+
+impl Replayer {
+    /// Creates a replayer with an empty cache, and no handlers.
+    pub fn new(http: Arc<Http>) -> Self {
+        Self {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            event_handler: None,
+            raw_event_handler: None,
+            #[cfg(feature = "framework")]
+            framework: Arc::new(NoFramework),
+            cache_and_http: Arc::new(CacheAndHttp {
+                #[cfg(feature = "cache")]
+                cache: Arc::new(Cache::new()),
+                http,
+            }),
+            runner_tx: mpsc::unbounded().0,
+            dispatcher: Dispatcher::new(DispatchMode::default()),
+            shards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the event handler the payloads are dispatched to.
+    #[must_use]
+    pub fn event_handler<H: EventHandler + 'static>(mut self, event_handler: H) -> Self {
+        self.event_handler = Some(Arc::new(event_handler));
+
+        self
+    }
+
+    /// Sets the raw event handler the payloads are dispatched to.
+    #[must_use]
+    pub fn raw_event_handler<H: RawEventHandler + 'static>(mut self, raw_event_handler: H) -> Self {
+        self.raw_event_handler = Some(Arc::new(raw_event_handler));
+
+        self
+    }
+
+    /// Sets the framework the payloads are dispatched to.
+    #[cfg(feature = "framework")]
+    #[must_use]
+    pub fn framework<F: Framework + Send + Sync + 'static>(mut self, framework: F) -> Self {
+        self.framework = Arc::new(framework);
+
+        self
+    }
+
+    /// Sets the cache updated by the payloads, such as one with the same
+    /// settings as the recorded bot's.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn cache(mut self, cache: Arc<Cache>) -> Self {
+        self.cache_and_http = Arc::new(CacheAndHttp {
+            cache,
+            http: Arc::clone(&self.cache_and_http.http),
+        });
+
+        self
+    }
+
+    /// Sets the data available through [`Context::data`].
+    ///
+    /// [`Context::data`]: super::Context::data
+    #[must_use]
+    pub fn data(mut self, data: Arc<RwLock<TypeMap>>) -> Self {
+        self.data = data;
+
+        self
+    }
+
+    /// Sets how the payloads are dispatched to the event handler. See
+    /// [`DispatchMode`] for more info.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime with a mode other than
+    /// [`DispatchMode::Concurrent`].
+    #[must_use]
+    pub fn dispatch_mode(mut self, dispatch_mode: DispatchMode) -> Self {
+        self.dispatcher = Dispatcher::new(dispatch_mode);
+
+        self
+    }
+
+    /// Replays the frames read from a recording, one [`RecordedFrame`] per
+    /// line, in the order they were recorded.
+    ///
+    /// Returns once every frame was dispatched, which may be before the event
+    /// handler finished handling them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading a line failed, or [`Error::Json`] if
+    /// a line is not a valid frame, stopping the replay.
+    pub async fn replay(&self, reader: impl BufRead) -> Result<()> {
+        for line in reader.lines() {
+            let mut line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let frame = json::from_str(&mut line)?;
+            self.replay_frame(frame).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replays a single frame, passing it through the state of the shard which
+    /// received it, and dispatching it if it holds an event.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`] if the payload could not be deserialized into
+    /// a [`GatewayEvent`], or any error returned by [`Shard::handle_event`].
+    ///
+    /// [`Shard::handle_event`]: crate::gateway::Shard::handle_event
+    pub async fn replay_frame(&self, frame: RecordedFrame) -> Result<()> {
+        let event = Ok(GatewayEvent::deserialize(frame.payload)?);
+
+        {
+            let mut shards = self.shards.lock().await;
+
+            let shard = match shards.entry(frame.shard_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mut shard = ShardState::new([frame.shard_id, frame.shard_total]);
+                    shard.set_http(Arc::clone(&self.cache_and_http.http));
+
+                    entry.insert(shard)
+                },
+            };
+
+            // There is no connection, so the actions the shard requests are
+            // not performed.
+            shard.handle_event(&event)?;
+        }
+
+        if let Ok(GatewayEvent::Dispatch(_, event)) = event {
+            dispatch(
+                DispatchEvent::Model(event),
+                #[cfg(feature = "framework")]
+                &self.framework,
+                &self.data,
+                &self.event_handler,
+                &self.raw_event_handler,
+                &self.runner_tx,
+                frame.shard_id,
+                Arc::clone(&self.cache_and_http),
+                &self.dispatcher,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves the session state of the shard with the given Id, as of the
+    /// last replayed frame it received.
+    ///
+    /// Returns `None` if no frame of the shard was replayed, or the shard has
+    /// no session.
+    pub async fn session_state(&self, shard_id: u64) -> Option<ShardSessionState> {
+        self.shards.lock().await.get(&shard_id).and_then(ShardState::session_state)
+    }
+}
+
+impl fmt::Debug for Replayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replayer").finish_non_exhaustive()
+    }
+}
+
+/// The framework of a [`Replayer`] none was set for.
+#[cfg(feature = "framework")]
+struct NoFramework;
+
+#[cfg(feature = "framework")]
+#[async_trait]
+impl Framework for NoFramework {
+    async fn dispatch(&self, _: Context, _: Message) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+    use crate::client::Context;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<StdMutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Handler(UnboundedSender<(u64, String)>);
+
+    #[async_trait]
+    impl EventHandler for Handler {
+        async fn unknown(&self, ctx: Context, name: String, _raw: Value) {
+            self.0.send((ctx.shard_id, name)).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let buffer = Buffer::default();
+        let recorder = JsonLinesRecorder::new(buffer.clone());
+        recorder
+            .record([0, 2], &json::json!({"op": 10, "d": {"heartbeat_interval": 45000}}))
+            .unwrap();
+        recorder
+            .record([1, 2], &json::json!({"op": 0, "s": 1, "t": "SOMETHING", "d": {}}))
+            .unwrap();
+
+        // Dropping the recorder stops its thread once every line was written.
+        drop(recorder);
+        let recording = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let recording = buffer.0.lock().unwrap().clone();
+
+                if std::str::from_utf8(&recording).unwrap().lines().count() == 2 {
+                    break recording;
+                }
+
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let (tx, mut rx) = unbounded_channel();
+        let replayer = Replayer::new(Arc::new(Http::new("token"))).event_handler(Handler(tx));
+        replayer.replay(&recording[..]).await.unwrap();
+
+        assert_eq!(rx.recv().await, Some((1, "SOMETHING".to_string())));
+        assert!(replayer.session_state(0).await.is_none());
+        assert!(replayer.session_state(1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_replay_through_shard() {
+        let replayer = Replayer::new(Arc::new(Http::new("token")));
+
+        let ready = json::json!({
+            "op": 0,
+            "s": 1,
+            "t": "READY",
+            "d": {
+                "v": 10,
+                "user": {
+                    "id": "1",
+                    "username": "serenity",
+                    "discriminator": "0000",
+                    "avatar": null,
+                    "bot": true,
+                    "mfa_enabled": false,
+                },
+                "guilds": [],
+                "session_id": "session",
+                "resume_gateway_url": "wss://resume.discord.gg",
+                "application": {
+                    "id": "1",
+                    "flags": 0,
+                },
+            },
+        });
+        let resumed = json::json!({"op": 0, "s": 2, "t": "RESUMED", "d": {"_trace": []}});
+
+        for payload in [ready, resumed] {
+            let frame = RecordedFrame {
+                timestamp: Timestamp::now(),
+                shard_id: 2,
+                shard_total: 4,
+                payload,
+            };
+            replayer.replay_frame(frame).await.unwrap();
+        }
+
+        let state = replayer.session_state(2).await.unwrap();
+        assert_eq!(state.shard_info, [2, 4]);
+        assert_eq!(state.session_id, "session");
+        assert_eq!(state.seq, 2);
+        assert_eq!(state.resume_gateway_url.as_deref(), Some("wss://resume.discord.gg"));
+    }
+}
@@ -13,10 +13,14 @@ use tracing::instrument;
 use crate::client::ClientError;
 #[cfg(feature = "collector")]
 use crate::collector::CollectorError;
+#[cfg(feature = "etf")]
+use crate::etf::EtfError;
 #[cfg(feature = "gateway")]
 use crate::gateway::GatewayError;
 #[cfg(feature = "http")]
 use crate::http::HttpError;
+#[cfg(feature = "interactions_endpoint")]
+use crate::interactions_endpoint::InteractionsEndpointError;
 use crate::internal::prelude::*;
 use crate::model::ModelError;
 
@@ -48,6 +52,9 @@ pub enum Error {
     #[cfg(feature = "simd-json")]
     /// An error from the `simd_json` crate.
     SimdJson(simd_json::Error),
+    /// An error encoding or decoding an ETF payload.
+    #[cfg(feature = "etf")]
+    Etf(EtfError),
     /// An error from the [`model`] module.
     ///
     /// [`model`]: crate::model
@@ -96,6 +103,11 @@ pub enum Error {
     /// [`http`]: crate::http
     #[cfg(feature = "http")]
     Http(Box<HttpError>),
+    /// An error from the [`interactions_endpoint`] module.
+    ///
+    /// [`interactions_endpoint`]: crate::interactions_endpoint
+    #[cfg(feature = "interactions_endpoint")]
+    InteractionsEndpoint(InteractionsEndpointError),
     /// An error from the `tungstenite` crate.
     #[cfg(feature = "gateway")]
     Tungstenite(TungsteniteError),
@@ -108,6 +120,13 @@ impl From<simd_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "etf")]
+impl From<EtfError> for Error {
+    fn from(e: EtfError) -> Self {
+        Error::Etf(e)
+    }
+}
+
 impl From<FormatError> for Error {
     fn from(e: FormatError) -> Error {
         Error::Format(e)
@@ -153,6 +172,13 @@ impl From<HttpError> for Error {
     }
 }
 
+#[cfg(feature = "interactions_endpoint")]
+impl From<InteractionsEndpointError> for Error {
+    fn from(e: InteractionsEndpointError) -> Error {
+        Error::InteractionsEndpoint(e)
+    }
+}
+
 #[cfg(feature = "http")]
 impl From<InvalidHeaderValue> for Error {
     fn from(e: InvalidHeaderValue) -> Error {
@@ -180,6 +206,8 @@ impl fmt::Display for Error {
             Self::Url(msg) => f.write_str(msg),
             #[cfg(feature = "simd-json")]
             Error::SimdJson(inner) => fmt::Display::fmt(&inner, f),
+            #[cfg(feature = "etf")]
+            Self::Etf(inner) => fmt::Display::fmt(&inner, f),
             #[cfg(feature = "client")]
             Self::Client(inner) => fmt::Display::fmt(&inner, f),
             #[cfg(feature = "collector")]
@@ -188,6 +216,8 @@ impl fmt::Display for Error {
             Self::Gateway(inner) => fmt::Display::fmt(&inner, f),
             #[cfg(feature = "http")]
             Self::Http(inner) => fmt::Display::fmt(&inner, f),
+            #[cfg(feature = "interactions_endpoint")]
+            Self::InteractionsEndpoint(inner) => fmt::Display::fmt(&inner, f),
             #[cfg(feature = "gateway")]
             Self::Tungstenite(inner) => fmt::Display::fmt(&inner, f),
         }
@@ -202,6 +232,8 @@ impl StdError for Error {
             Self::Io(inner) => Some(inner),
             Self::Json(inner) => Some(inner),
             Self::Model(inner) => Some(inner),
+            #[cfg(feature = "etf")]
+            Self::Etf(inner) => Some(inner),
             #[cfg(feature = "client")]
             Self::Client(inner) => Some(inner),
             #[cfg(feature = "collector")]
@@ -210,6 +242,8 @@ impl StdError for Error {
             Self::Gateway(inner) => Some(inner),
             #[cfg(feature = "http")]
             Self::Http(inner) => Some(inner),
+            #[cfg(feature = "interactions_endpoint")]
+            Self::InteractionsEndpoint(inner) => Some(inner),
             #[cfg(feature = "gateway")]
             Self::Tungstenite(inner) => Some(inner),
             _ => None,
@@ -0,0 +1,812 @@
+//! This module implements the [External Term Format] used by the gateway when
+//! connecting with `encoding=etf`.
+//!
+//! Only the subset of terms Discord sends and accepts is supported. Terms are
+//! mapped onto serde's data model as follows:
+//!
+//! - the atoms `nil`, `true` and `false` are `null`, `true` and `false`; other
+//!   atoms are strings;
+//! - binaries are strings, or bytes if they aren't valid UTF-8;
+//! - integers, including big integers of up to 64 bits, and floats are numbers;
+//! - lists and tuples are sequences;
+//! - maps are maps.
+//!
+//! [External Term Format]: https://www.erlang.org/doc/apps/erts/erl_ext_dist.html
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{
+    self,
+    Deserialize,
+    DeserializeSeed,
+    IgnoredAny,
+    IntoDeserializer,
+    MapAccess,
+    SeqAccess,
+    Visitor,
+};
+use serde::ser::{self, Serialize};
+
+pub(crate) const VERSION: u8 = 131;
+
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const FLOAT_EXT: u8 = 99;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const MAP_EXT: u8 = 116;
+const SMALL_ATOM_EXT: u8 = 115;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// An error encoding or decoding a term.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum EtfError {
+    /// The input ended in the middle of a term.
+    Eof,
+    /// The input does not start with the version number `131`.
+    InvalidVersion(u8),
+    /// A term had a tag which is not supported.
+    UnsupportedTag(u8),
+    /// An integer does not fit into 64 bits.
+    IntegerOverflow,
+    /// A list did not end with an empty list.
+    ImproperList,
+    /// Bytes remained after the term.
+    TrailingBytes,
+    /// A custom error from a [`Serialize`] or [`Deserialize`] implementation.
+    ///
+    /// [`Deserialize`]: serde::Deserialize
+    Custom(String),
+}
+
+impl fmt::Display for EtfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eof => f.write_str("Unexpected end of ETF input"),
+            Self::InvalidVersion(v) => write!(f, "Invalid ETF version {}", v),
+            Self::UnsupportedTag(tag) => write!(f, "Unsupported ETF tag {}", tag),
+            Self::IntegerOverflow => f.write_str("ETF integer does not fit into 64 bits"),
+            Self::ImproperList => f.write_str("ETF list is not a proper list"),
+            Self::TrailingBytes => f.write_str("Trailing bytes after ETF term"),
+            Self::Custom(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for EtfError {}
+
+impl de::Error for EtfError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl ser::Error for EtfError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, EtfError>;
+
+/// Deserializes an instance of `T` from a term, starting with the version
+/// number.
+///
+/// # Errors
+///
+/// Returns an [`EtfError`] if the input is not a valid term, or if it does not
+/// match the layout of `T`.
+pub fn from_slice<'a, T: de::Deserialize<'a>>(input: &'a [u8]) -> Result<T> {
+    match input.split_first() {
+        Some((&VERSION, rest)) => {
+            let mut deserializer = Deserializer {
+                input: rest,
+            };
+            let value = T::deserialize(&mut deserializer)?;
+
+            if deserializer.input.is_empty() {
+                Ok(value)
+            } else {
+                Err(EtfError::TrailingBytes)
+            }
+        },
+        Some((&version, _)) => Err(EtfError::InvalidVersion(version)),
+        None => Err(EtfError::Eof),
+    }
+}
+
+/// Serializes a value into a term, starting with the version number.
+///
+/// # Errors
+///
+/// Returns an [`EtfError`] if the [`Serialize`] implementation of `T` fails.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut serializer = Serializer {
+        output: vec![VERSION],
+    };
+    value.serialize(&mut serializer)?;
+
+    Ok(serializer.output)
+}
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8]> {
+        if self.input.len() < len {
+            return Err(EtfError::Eof);
+        }
+
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize> {
+        Ok(u16::from_be_bytes(self.take_array()?).into())
+    }
+
+    fn u32(&mut self) -> Result<usize> {
+        Ok(u32::from_be_bytes(self.take_array()?) as usize)
+    }
+
+    fn peek_atom(&self) -> Option<&'de [u8]> {
+        let (&tag, rest) = self.input.split_first()?;
+
+        let (len, rest) = match tag {
+            SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT => (usize::from(*rest.first()?), rest.get(1..)?),
+            ATOM_EXT | ATOM_UTF8_EXT => {
+                (usize::from(u16::from_be_bytes([*rest.first()?, *rest.get(1)?])), rest.get(2..)?)
+            },
+            _ => return None,
+        };
+
+        rest.get(..len)
+    }
+
+    fn big<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value> {
+        let negative = self.u8()? != 0;
+        let digits = self.take(len)?;
+
+        let mut magnitude = 0_u64;
+        for (i, &digit) in digits.iter().enumerate() {
+            if digit == 0 {
+                continue;
+            }
+
+            if i >= 8 {
+                return Err(EtfError::IntegerOverflow);
+            }
+
+            magnitude |= u64::from(digit) << (8 * i);
+        }
+
+        if negative {
+            let value = i64::try_from(magnitude).map(|m| -m).or_else(|_| {
+                if magnitude == i64::MIN.unsigned_abs() {
+                    Ok(i64::MIN)
+                } else {
+                    Err(EtfError::IntegerOverflow)
+                }
+            })?;
+
+            visitor.visit_i64(value)
+        } else {
+            visitor.visit_u64(magnitude)
+        }
+    }
+
+    fn list<V: Visitor<'de>>(&mut self, len: usize, tail: bool, visitor: V) -> Result<V::Value> {
+        let mut access = Access {
+            de: self,
+            remaining: len,
+        };
+        let value = visitor.visit_seq(&mut access)?;
+
+        while access.remaining > 0 {
+            access.next_element::<IgnoredAny>()?;
+        }
+
+        if tail && self.u8()? != NIL_EXT {
+            return Err(EtfError::ImproperList);
+        }
+
+        Ok(value)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = EtfError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.u8()? {
+            SMALL_INTEGER_EXT => visitor.visit_u8(self.u8()?),
+            INTEGER_EXT => visitor.visit_i32(i32::from_be_bytes(self.take_array()?)),
+            NEW_FLOAT_EXT => visitor.visit_f64(f64::from_be_bytes(self.take_array()?)),
+            FLOAT_EXT => {
+                let bytes = self.take(31)?;
+                let float = std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|s| s.trim_end_matches('\0').parse().ok())
+                    .ok_or_else(|| EtfError::Custom("Invalid ETF float".to_string()))?;
+
+                visitor.visit_f64(float)
+            },
+            tag @ (ATOM_EXT | SMALL_ATOM_EXT | ATOM_UTF8_EXT | SMALL_ATOM_UTF8_EXT) => {
+                let len = if tag == ATOM_EXT || tag == ATOM_UTF8_EXT {
+                    self.u16()?
+                } else {
+                    usize::from(self.u8()?)
+                };
+
+                match self.take(len)? {
+                    b"nil" => visitor.visit_unit(),
+                    b"true" => visitor.visit_bool(true),
+                    b"false" => visitor.visit_bool(false),
+                    atom => visit_str_or_bytes(atom, visitor),
+                }
+            },
+            SMALL_TUPLE_EXT => {
+                let len = usize::from(self.u8()?);
+                self.list(len, false, visitor)
+            },
+            LARGE_TUPLE_EXT => {
+                let len = self.u32()?;
+                self.list(len, false, visitor)
+            },
+            NIL_EXT => self.list(0, false, visitor),
+            STRING_EXT => {
+                let len = self.u16()?;
+                visitor.visit_seq(SeqDeserializer::new(self.take(len)?.iter().copied()))
+            },
+            LIST_EXT => {
+                let len = self.u32()?;
+                self.list(len, true, visitor)
+            },
+            BINARY_EXT => {
+                let len = self.u32()?;
+                visit_str_or_bytes(self.take(len)?, visitor)
+            },
+            SMALL_BIG_EXT => {
+                let len = usize::from(self.u8()?);
+                self.big(len, visitor)
+            },
+            LARGE_BIG_EXT => {
+                let len = self.u32()?;
+                self.big(len, visitor)
+            },
+            MAP_EXT => {
+                let len = self.u32()?;
+                let mut access = Access {
+                    de: self,
+                    remaining: len,
+                };
+                let value = visitor.visit_map(&mut access)?;
+
+                while access.remaining > 0 {
+                    access.next_entry::<IgnoredAny, IgnoredAny>()?;
+                }
+
+                Ok(value)
+            },
+            tag => Err(EtfError::UnsupportedTag(tag)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.peek_atom() == Some(b"nil") {
+            self.deserialize_any(IgnoredAny)?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        // Only unit variants are supported, which are encoded as strings.
+        let variant = <&str>::deserialize(&mut *self)?;
+        let deserializer: StrDeserializer<'_, EtfError> = variant.into_deserializer();
+
+        visitor.visit_enum(deserializer)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+fn visit_str_or_bytes<'de, V: Visitor<'de>>(bytes: &'de [u8], visitor: V) -> Result<V::Value> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => visitor.visit_borrowed_str(s),
+        Err(_) => visitor.visit_borrowed_bytes(bytes),
+    }
+}
+
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de> SeqAccess<'de> for Access<'_, 'de> {
+    type Error = EtfError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> MapAccess<'de> for Access<'_, 'de> {
+    type Error = EtfError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct Serializer {
+    output: Vec<u8>,
+}
+
+impl Serializer {
+    fn atom(&mut self, atom: &str) {
+        self.output.push(SMALL_ATOM_UTF8_EXT);
+        self.output.push(atom.len() as u8);
+        self.output.extend_from_slice(atom.as_bytes());
+    }
+
+    fn binary(&mut self, bytes: &[u8]) -> Result<()> {
+        let len = u32::try_from(bytes.len()).map_err(|_| ser::Error::custom("Binary too long"))?;
+
+        self.output.push(BINARY_EXT);
+        self.output.extend_from_slice(&len.to_be_bytes());
+        self.output.extend_from_slice(bytes);
+
+        Ok(())
+    }
+
+    fn integer(&mut self, negative: bool, magnitude: u64) {
+        if !negative && u8::try_from(magnitude).is_ok() {
+            self.output.push(SMALL_INTEGER_EXT);
+            self.output.push(magnitude as u8);
+        } else if i32::try_from(magnitude).is_ok() {
+            let value = if negative { -(magnitude as i32) } else { magnitude as i32 };
+
+            self.output.push(INTEGER_EXT);
+            self.output.extend_from_slice(&value.to_be_bytes());
+        } else {
+            let digits = magnitude.to_le_bytes();
+            let len = 8 - magnitude.leading_zeros() as usize / 8;
+
+            self.output.push(SMALL_BIG_EXT);
+            self.output.push(len as u8);
+            self.output.push(u8::from(negative));
+            self.output.extend_from_slice(&digits[..len]);
+        }
+    }
+
+    /// Writes the header of a list or map, with a placeholder for its length.
+    fn begin(&mut self, tag: u8) -> Compound<'_> {
+        let start = self.output.len();
+
+        self.output.push(tag);
+        self.output.extend_from_slice(&[0; 4]);
+
+        Compound {
+            ser: self,
+            start,
+            len: 0,
+        }
+    }
+
+    /// Writes a map with a single entry, used for the non-unit variants of
+    /// enums.
+    fn variant(&mut self, variant: &str) -> Result<()> {
+        self.output.push(MAP_EXT);
+        self.output.extend_from_slice(&1_u32.to_be_bytes());
+        self.binary(variant.as_bytes())
+    }
+}
+
+struct Compound<'a> {
+    ser: &'a mut Serializer,
+    start: usize,
+    len: u32,
+}
+
+impl Compound<'_> {
+    fn end_list(self) {
+        if self.len == 0 {
+            // Empty lists have no header and no tail.
+            self.ser.output.truncate(self.start);
+            self.ser.output.push(NIL_EXT);
+        } else {
+            self.ser.output[self.start + 1..self.start + 5]
+                .copy_from_slice(&self.len.to_be_bytes());
+            self.ser.output.push(NIL_EXT);
+        }
+    }
+
+    fn end_map(self) {
+        self.ser.output[self.start + 1..self.start + 5].copy_from_slice(&self.len.to_be_bytes());
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = EtfError;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.atom(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.integer(v < 0, v.unsigned_abs());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.integer(false, v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.output.push(NEW_FLOAT_EXT);
+        self.output.extend_from_slice(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.binary(v.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.binary(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.binary(v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.atom("nil");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(self.begin(LIST_EXT))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>> {
+        self.variant(variant)?;
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>> {
+        Ok(self.begin(MAP_EXT))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>> {
+        self.variant(variant)?;
+        self.serialize_map(Some(len))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.len += 1;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_list();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_list();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_list();
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_list();
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.len += 1;
+        key.serialize(&mut *self.ser)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_map();
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.len += 1;
+        self.ser.binary(key.as_bytes())?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_map();
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EtfError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.end_map();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_slice, to_vec};
+    use crate::json::{json, Value};
+
+    // A HELLO as the gateway sends it, with atom keys and a big integer.
+    const HELLO_TERM: [u8; 117] = [
+        0x83, 0x74, 0x00, 0x00, 0x00, 0x04, 0x77, 0x02, 0x6f, 0x70, 0x61, 0x0a, 0x77, 0x01, 0x73,
+        0x77, 0x03, 0x6e, 0x69, 0x6c, 0x77, 0x01, 0x74, 0x77, 0x03, 0x6e, 0x69, 0x6c, 0x77, 0x01,
+        0x64, 0x74, 0x00, 0x00, 0x00, 0x03, 0x77, 0x12, 0x68, 0x65, 0x61, 0x72, 0x74, 0x62, 0x65,
+        0x61, 0x74, 0x5f, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x76, 0x61, 0x6c, 0x62, 0x00, 0x00, 0xa1,
+        0x22, 0x77, 0x06, 0x5f, 0x74, 0x72, 0x61, 0x63, 0x65, 0x6c, 0x00, 0x00, 0x00, 0x01, 0x6d,
+        0x00, 0x00, 0x00, 0x10, 0x67, 0x61, 0x74, 0x65, 0x77, 0x61, 0x79, 0x2d, 0x70, 0x72, 0x64,
+        0x2d, 0x6d, 0x61, 0x69, 0x6e, 0x6a, 0x77, 0x08, 0x67, 0x75, 0x69, 0x6c, 0x64, 0x5f, 0x69,
+        0x64, 0x6e, 0x08, 0x00, 0x00, 0x20, 0x80, 0xc0, 0x08, 0x23, 0x21, 0x01,
+    ];
+
+    #[test]
+    fn test_decode_gateway_term() {
+        let expected = json!({
+            "op": 10,
+            "s": null,
+            "t": null,
+            "d": {
+                "heartbeat_interval": 41250,
+                "_trace": ["gateway-prd-main"],
+                "guild_id": 81384788765712384_u64,
+            },
+        });
+
+        assert_eq!(from_slice::<Value>(&HELLO_TERM).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_empty_list() {
+        let term = to_vec(&json!({ "mentions": [] })).unwrap();
+
+        assert_eq!(term[term.len() - 1], super::NIL_EXT);
+        assert_eq!(from_slice::<Value>(&term).unwrap(), json!({ "mentions": [] }));
+    }
+}
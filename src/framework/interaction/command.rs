@@ -0,0 +1,217 @@
+use std::fmt;
+
+use futures::future::BoxFuture;
+
+use crate::builder::CreateApplicationCommand;
+use crate::client::Context;
+use crate::framework::standard::{CommandResult, OnlyIn, Reason};
+use crate::model::application::interaction::application_command::ApplicationCommandInteraction;
+use crate::model::application::interaction::autocomplete::AutocompleteInteraction;
+use crate::model::permissions::Permissions;
+
+pub type InteractionCommandFn = for<'fut> fn(
+    &'fut Context,
+    &'fut ApplicationCommandInteraction,
+) -> BoxFuture<'fut, CommandResult>;
+pub type AutocompleteFn =
+    for<'fut> fn(&'fut Context, &'fut AutocompleteInteraction) -> BoxFuture<'fut, CommandResult>;
+pub type InteractionCheckFunction = for<'fut> fn(
+    &'fut Context,
+    &'fut ApplicationCommandInteraction,
+    &'fut InteractionCommand,
+) -> BoxFuture<'fut, Result<(), Reason>>;
+
+/// A check that is executed before an [`InteractionCommand`] to determine
+/// whether the user is permitted to use it.
+///
+/// This is the interaction counterpart of a standard framework [`Check`].
+///
+/// [`Check`]: crate::framework::standard::Check
+pub struct InteractionCheck {
+    /// Name passed to [`DispatchError::CheckFailed`] when the check fails.
+    ///
+    /// [`DispatchError::CheckFailed`]: crate::framework::standard::DispatchError::CheckFailed
+    pub name: &'static str,
+    /// Function that will be executed.
+    pub function: InteractionCheckFunction,
+}
+
+impl fmt::Debug for InteractionCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InteractionCheck")
+            .field("name", &self.name)
+            .field("function", &"<fn>")
+            .finish()
+    }
+}
+
+/// An application command handled by an [`InteractionFramework`].
+///
+/// The command is registered with Discord from its definition, see
+/// [`InteractionFramework::register_globally`], and invocations of it are
+/// routed to its function by name.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use serenity::framework::interaction::InteractionCommand;
+/// use serenity::framework::standard::macros::hook;
+/// use serenity::framework::standard::CommandResult;
+/// use serenity::model::application::command::CommandOptionType;
+/// use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+/// use serenity::model::application::interaction::InteractionResponseType;
+/// use serenity::prelude::*;
+///
+/// #[hook]
+/// async fn echo(ctx: &Context, interaction: &ApplicationCommandInteraction) -> CommandResult {
+///     let text = interaction.data.options[0].value.as_ref().and_then(|v| v.as_str()).unwrap_or("");
+///
+///     interaction
+///         .create_interaction_response(&ctx.http, |r| {
+///             r.kind(InteractionResponseType::ChannelMessageWithSource)
+///                 .interaction_response_data(|d| d.content(text))
+///         })
+///         .await?;
+///
+///     Ok(())
+/// }
+///
+/// let command = InteractionCommand::new("echo", echo).create(|c| {
+///     c.description("Repeats a text").create_option(|o| {
+///         o.name("text").description("The text").kind(CommandOptionType::String).required(true)
+///     })
+/// });
+/// ```
+///
+/// [`InteractionFramework`]: super::InteractionFramework
+/// [`InteractionFramework::register_globally`]: super::InteractionFramework::register_globally
+pub struct InteractionCommand {
+    pub(crate) name: String,
+    pub(crate) create: CreateApplicationCommand,
+    pub(crate) fun: InteractionCommandFn,
+    pub(crate) autocomplete: Option<AutocompleteFn>,
+    pub(crate) checks: Vec<InteractionCheck>,
+    pub(crate) bucket: Option<String>,
+    pub(crate) only_in: OnlyIn,
+    pub(crate) owners_only: bool,
+    pub(crate) required_permissions: Permissions,
+}
+
+impl InteractionCommand {
+    /// Creates a command with the given name, which calls `fun` when invoked.
+    ///
+    /// Chat input commands also need a description, which can be set via
+    /// [`Self::create`].
+    #[must_use]
+    pub fn new(name: impl ToString, fun: InteractionCommandFn) -> Self {
+        let name = name.to_string();
+        let mut create = CreateApplicationCommand::default();
+        create.name(&name);
+
+        Self {
+            name,
+            create,
+            fun,
+            autocomplete: None,
+            checks: Vec::new(),
+            bucket: None,
+            only_in: OnlyIn::None,
+            owners_only: false,
+            required_permissions: Permissions::empty(),
+        }
+    }
+
+    /// Sets up the command sent to Discord when registering it, such as its
+    /// description, kind and options.
+    #[must_use]
+    pub fn create<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut CreateApplicationCommand) -> &mut CreateApplicationCommand,
+    {
+        f(&mut self.create);
+
+        self
+    }
+
+    /// Sets the function called for autocomplete interactions of the command.
+    #[must_use]
+    pub fn autocomplete(mut self, fun: AutocompleteFn) -> Self {
+        self.autocomplete = Some(fun);
+
+        self
+    }
+
+    /// Adds a check that must pass before the command is executed. Checks are
+    /// executed in the order they were added and short-circuit on the first
+    /// failure.
+    #[must_use]
+    pub fn check(mut self, name: &'static str, function: InteractionCheckFunction) -> Self {
+        self.checks.push(InteractionCheck {
+            name,
+            function,
+        });
+
+        self
+    }
+
+    /// Sets the ratelimit bucket of the command, as defined via
+    /// [`InteractionFramework::bucket`].
+    ///
+    /// [`InteractionFramework::bucket`]: super::InteractionFramework::bucket
+    #[must_use]
+    pub fn bucket(mut self, name: impl ToString) -> Self {
+        self.bucket = Some(name.to_string());
+
+        self
+    }
+
+    /// Sets whether the command can only be used in direct messages or guilds.
+    #[must_use]
+    pub fn only_in(mut self, only_in: OnlyIn) -> Self {
+        self.only_in = only_in;
+
+        self
+    }
+
+    /// Sets whether the command can only be used by the framework's owners.
+    #[must_use]
+    pub fn owners_only(mut self, owners_only: bool) -> Self {
+        self.owners_only = owners_only;
+
+        self
+    }
+
+    /// Sets the permissions a member needs in the channel to use the command.
+    ///
+    /// This is checked by the framework. To hide the command from members
+    /// lacking them as well, use
+    /// [`CreateApplicationCommand::default_member_permissions`].
+    #[must_use]
+    pub fn required_permissions(mut self, permissions: Permissions) -> Self {
+        self.required_permissions = permissions;
+
+        self
+    }
+
+    /// The name of the command.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Debug for InteractionCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InteractionCommand")
+            .field("name", &self.name)
+            .field("create", &self.create)
+            .field("fun", &"<fn>")
+            .field("autocomplete", &self.autocomplete.map(|_| "<fn>"))
+            .field("checks", &self.checks)
+            .field("bucket", &self.bucket)
+            .field("only_in", &self.only_in)
+            .field("owners_only", &self.owners_only)
+            .field("required_permissions", &self.required_permissions)
+            .finish()
+    }
+}
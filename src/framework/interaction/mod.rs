@@ -0,0 +1,703 @@
+//! A framework for application commands and other interactions.
+//!
+//! Commands are defined as [`InteractionCommand`]s, which are registered with
+//! Discord via [`InteractionFramework::register_globally`] or
+//! [`InteractionFramework::register_in_guild`]. Received interactions are
+//! routed to the commands by name, and to component and modal handlers by
+//! custom id.
+//!
+//! Commands can be guarded by the same concepts as the
+//! [standard framework]'s: checks, ratelimit buckets defined via a
+//! [`BucketBuilder`], and a hook receiving a [`DispatchError`] when a command
+//! isn't executed.
+//!
+//! # Examples
+//!
+//! Registering a `ping` command once the bot is ready:
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use serenity::framework::interaction::{InteractionCommand, InteractionFramework};
+//! use serenity::framework::standard::macros::hook;
+//! use serenity::framework::standard::CommandResult;
+//! use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+//! use serenity::model::application::interaction::InteractionResponseType;
+//! use serenity::model::gateway::Ready;
+//! use serenity::prelude::*;
+//!
+//! #[hook]
+//! async fn ping(ctx: &Context, interaction: &ApplicationCommandInteraction) -> CommandResult {
+//!     interaction
+//!         .create_interaction_response(&ctx.http, |r| {
+//!             r.kind(InteractionResponseType::ChannelMessageWithSource)
+//!                 .interaction_response_data(|d| d.content("Pong!"))
+//!         })
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//!
+//! struct Handler {
+//!     framework: Arc<InteractionFramework>,
+//! }
+//!
+//! #[serenity::async_trait]
+//! impl EventHandler for Handler {
+//!     async fn ready(&self, ctx: Context, _: Ready) {
+//!         if let Err(why) = self.framework.register_globally(&ctx.http).await {
+//!             println!("Error registering commands: {:?}", why);
+//!         }
+//!     }
+//! }
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let token = std::env::var("DISCORD_TOKEN")?;
+//!
+//! let framework = Arc::new(
+//!     InteractionFramework::new()
+//!         .command(InteractionCommand::new("ping", ping).create(|c| c.description("Pong!"))),
+//! );
+//!
+//! let mut client = Client::builder(&token, GatewayIntents::empty())
+//!     .event_handler(Handler {
+//!         framework: Arc::clone(&framework),
+//!     })
+//!     .framework_arc(framework)
+//!     .await?;
+//! #     Ok(())
+//! # }
+//! ```
+//!
+//! To use it alongside a [`StandardFramework`], pass it to
+//! [`StandardFramework::interactions`] instead.
+//!
+//! [standard framework]: crate::framework::standard
+//! [`StandardFramework`]: crate::framework::StandardFramework
+//! [`StandardFramework::interactions`]: crate::framework::StandardFramework::interactions
+
+mod command;
+
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{debug, instrument, warn};
+
+pub use self::command::*;
+use super::standard::buckets::{Bucket, BucketTarget, RateLimitAction, RevertBucket};
+use super::standard::{BucketBuilder, CommandResult, DispatchError, OnlyIn};
+use super::Framework;
+use crate::builder::CreateApplicationCommand;
+use crate::client::Context;
+use crate::http::Http;
+use crate::internal::prelude::*;
+use crate::model::application::command::Command;
+use crate::model::application::interaction::application_command::ApplicationCommandInteraction;
+use crate::model::application::interaction::autocomplete::AutocompleteInteraction;
+use crate::model::application::interaction::message_component::MessageComponentInteraction;
+use crate::model::application::interaction::modal::ModalSubmitInteraction;
+use crate::model::application::interaction::Interaction;
+use crate::model::channel::Message;
+use crate::model::id::{GuildId, UserId};
+
+pub type ComponentFn = for<'fut> fn(
+    &'fut Context,
+    &'fut MessageComponentInteraction,
+) -> BoxFuture<'fut, CommandResult>;
+pub type ModalFn =
+    for<'fut> fn(&'fut Context, &'fut ModalSubmitInteraction) -> BoxFuture<'fut, CommandResult>;
+
+type DispatchHook = for<'fut> fn(
+    &'fut Context,
+    &'fut ApplicationCommandInteraction,
+    DispatchError,
+    &'fut str,
+) -> BoxFuture<'fut, ()>;
+type AfterHook = for<'fut> fn(
+    &'fut Context,
+    &'fut ApplicationCommandInteraction,
+    &'fut str,
+    CommandResult,
+) -> BoxFuture<'fut, ()>;
+
+/// A framework dispatching interactions to [`InteractionCommand`]s and to
+/// component and modal handlers.
+///
+/// Refer to the [module-level documentation] for more information.
+///
+/// [module-level documentation]: self
+#[derive(Default)]
+pub struct InteractionFramework {
+    commands: HashMap<String, InteractionCommand>,
+    components: HashMap<String, ComponentFn>,
+    modals: HashMap<String, ModalFn>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    owners: HashSet<UserId>,
+    dispatch: Option<DispatchHook>,
+    after: Option<AfterHook>,
+}
+
+impl InteractionFramework {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a command, replacing any previous command of the same name.
+    #[must_use]
+    pub fn command(mut self, command: InteractionCommand) -> Self {
+        self.commands.insert(command.name.clone(), command);
+
+        self
+    }
+
+    /// Adds a handler for message component interactions.
+    ///
+    /// Interactions are routed by the part of their custom id preceding the
+    /// first `:`, which allows to carry state in the rest of it. For example,
+    /// a button with the custom id `vote:42` is handled by the handler for
+    /// `vote`.
+    #[must_use]
+    pub fn component(mut self, custom_id: impl ToString, fun: ComponentFn) -> Self {
+        self.components.insert(custom_id.to_string(), fun);
+
+        self
+    }
+
+    /// Adds a handler for modal submit interactions.
+    ///
+    /// These are routed by custom id the same way as in [`Self::component`].
+    #[must_use]
+    pub fn modal(mut self, custom_id: impl ToString, fun: ModalFn) -> Self {
+        self.modals.insert(custom_id.to_string(), fun);
+
+        self
+    }
+
+    /// Defines a ratelimit bucket, which commands can be assigned to via
+    /// [`InteractionCommand::bucket`].
+    ///
+    /// Refer to [`StandardFramework::bucket`] for more information. The
+    /// bucket's [`check`] and [`delay_action`] receive a [`Message`], so they
+    /// are not used for interactions.
+    ///
+    /// [`StandardFramework::bucket`]: super::StandardFramework::bucket
+    /// [`check`]: BucketBuilder::check
+    /// [`delay_action`]: BucketBuilder::delay_action
+    #[inline]
+    pub async fn bucket<F>(self, name: &str, f: F) -> Self
+    where
+        F: FnOnce(&mut BucketBuilder) -> &mut BucketBuilder,
+    {
+        let mut builder = BucketBuilder::default();
+
+        f(&mut builder);
+
+        self.buckets.lock().await.insert(name.to_string(), builder.construct());
+
+        self
+    }
+
+    /// Sets the users allowed to use commands marked as
+    /// [`InteractionCommand::owners_only`].
+    #[must_use]
+    pub fn owners(mut self, owners: HashSet<UserId>) -> Self {
+        self.owners = owners;
+
+        self
+    }
+
+    /// Specify the function that's called in case a command wasn't executed
+    /// for one reason or another.
+    ///
+    /// Refer to [`StandardFramework::on_dispatch_error`] for more information.
+    ///
+    /// [`StandardFramework::on_dispatch_error`]: super::StandardFramework::on_dispatch_error
+    #[must_use]
+    pub fn on_dispatch_error(mut self, f: DispatchHook) -> Self {
+        self.dispatch = Some(f);
+
+        self
+    }
+
+    /// Specify the function to be called after every command's execution,
+    /// receiving its result.
+    #[must_use]
+    pub fn after(mut self, f: AfterHook) -> Self {
+        self.after = Some(f);
+
+        self
+    }
+
+    /// Builds the commands to send to Discord from their definitions.
+    #[must_use]
+    pub fn create_commands(&self) -> Vec<CreateApplicationCommand> {
+        self.commands.values().map(|command| command.create.clone()).collect()
+    }
+
+    /// Overwrites the bot's global commands with the framework's commands.
+    ///
+    /// **Note**: The application Id must be set on the [`Http`] client, which
+    /// happens once a shard received a [`Ready`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Http`] if a command is malformed.
+    ///
+    /// [`Ready`]: crate::model::gateway::Ready
+    pub async fn register_globally(&self, http: impl AsRef<Http>) -> Result<Vec<Command>> {
+        let commands = self.create_commands();
+
+        Command::set_global_application_commands(http, |c| c.set_application_commands(commands))
+            .await
+    }
+
+    /// Overwrites the bot's commands in a guild with the framework's commands.
+    ///
+    /// Unlike global commands, guild commands are available immediately, which
+    /// is useful while developing.
+    ///
+    /// **Note**: The application Id must be set on the [`Http`] client, which
+    /// happens once a shard received a [`Ready`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Http`] if a command is malformed or the bot is not
+    /// in the guild.
+    ///
+    /// [`Ready`]: crate::model::gateway::Ready
+    pub async fn register_in_guild(
+        &self,
+        http: impl AsRef<Http>,
+        guild_id: GuildId,
+    ) -> Result<Vec<Command>> {
+        let commands = self.create_commands();
+
+        guild_id.set_application_commands(http, |c| c.set_application_commands(commands)).await
+    }
+
+    async fn should_fail(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        command: &InteractionCommand,
+    ) -> Option<DispatchError> {
+        if command.owners_only && !self.owners.contains(&interaction.user.id) {
+            return Some(DispatchError::OnlyForOwners);
+        }
+
+        match (command.only_in, interaction.guild_id) {
+            (OnlyIn::Dm, Some(_)) => return Some(DispatchError::OnlyForDM),
+            (OnlyIn::Guild, None) => return Some(DispatchError::OnlyForGuilds),
+            _ => {},
+        }
+
+        // Interactions carry the member's permissions in the channel.
+        let permissions = interaction.member.as_ref().and_then(|member| member.permissions);
+
+        if let Some(permissions) = permissions {
+            let lacking = command.required_permissions - permissions;
+
+            if !lacking.is_empty() {
+                return Some(DispatchError::LackingPermissions(lacking));
+            }
+        }
+
+        let target = BucketTarget {
+            user: interaction.user.id,
+            guild: interaction.guild_id,
+            channel: interaction.channel_id,
+        };
+
+        // Like the standard framework, delay and try again until the bucket
+        // passes or cancels the invocation.
+        loop {
+            let mut duration = None;
+
+            {
+                let mut buckets = self.buckets.lock().await;
+
+                if let Some(bucket) = command.bucket.as_ref().and_then(|b| buckets.get_mut(b)) {
+                    if let Some(rate_limit_info) = bucket.take_for(ctx, &target) {
+                        duration = match rate_limit_info.action {
+                            RateLimitAction::Cancelled | RateLimitAction::FailedDelay => {
+                                return Some(DispatchError::Ratelimited(rate_limit_info))
+                            },
+                            RateLimitAction::Delayed => Some(rate_limit_info.rate_limit),
+                        };
+                    }
+                }
+            }
+
+            match duration {
+                Some(duration) => sleep(duration).await,
+                None => break,
+            }
+        }
+
+        for check in &command.checks {
+            if let Err(reason) = (check.function)(ctx, interaction, command).await {
+                return Some(DispatchError::CheckFailed(check.name, reason));
+            }
+        }
+
+        None
+    }
+
+    async fn dispatch_command(&self, ctx: Context, interaction: ApplicationCommandInteraction) {
+        let command = if let Some(command) = self.commands.get(&interaction.data.name) {
+            command
+        } else {
+            debug!("Received unknown application command {}", interaction.data.name);
+
+            return;
+        };
+
+        if let Some(error) = self.should_fail(&ctx, &interaction, command).await {
+            if let Some(dispatch) = &self.dispatch {
+                dispatch(&ctx, &interaction, error, &command.name).await;
+            }
+
+            return;
+        }
+
+        let res = (command.fun)(&ctx, &interaction).await;
+
+        // Check if the command wants to revert the bucket by giving back a ticket.
+        if matches!(res, Err(ref e) if e.is::<RevertBucket>()) {
+            let mut buckets = self.buckets.lock().await;
+
+            if let Some(bucket) = command.bucket.as_ref().and_then(|b| buckets.get_mut(b)) {
+                bucket.give_for(&ctx, &BucketTarget {
+                    user: interaction.user.id,
+                    guild: interaction.guild_id,
+                    channel: interaction.channel_id,
+                });
+            }
+        }
+
+        if let Some(after) = &self.after {
+            after(&ctx, &interaction, &command.name, res).await;
+        }
+    }
+
+    async fn dispatch_autocomplete(&self, ctx: Context, interaction: AutocompleteInteraction) {
+        let fun = self.commands.get(&interaction.data.name).and_then(|c| c.autocomplete);
+
+        if let Some(fun) = fun {
+            if let Err(why) = fun(&ctx, &interaction).await {
+                warn!("Error in autocomplete of {}: {:?}", interaction.data.name, why);
+            }
+        }
+    }
+
+    async fn dispatch_component(&self, ctx: Context, interaction: MessageComponentInteraction) {
+        if let Some(fun) = route(&self.components, &interaction.data.custom_id) {
+            if let Err(why) = fun(&ctx, &interaction).await {
+                warn!("Error in component handler of {}: {:?}", interaction.data.custom_id, why);
+            }
+        }
+    }
+
+    async fn dispatch_modal(&self, ctx: Context, interaction: ModalSubmitInteraction) {
+        if let Some(fun) = route(&self.modals, &interaction.data.custom_id) {
+            if let Err(why) = fun(&ctx, &interaction).await {
+                warn!("Error in modal handler of {}: {:?}", interaction.data.custom_id, why);
+            }
+        }
+    }
+}
+
+/// Finds the handler for a custom id, by the part preceding the first `:`.
+fn route<T: Copy>(handlers: &HashMap<String, T>, custom_id: &str) -> Option<T> {
+    let key = custom_id.split(':').next().unwrap_or(custom_id);
+
+    handlers.get(key).copied()
+}
+
+#[async_trait]
+impl Framework for InteractionFramework {
+    async fn dispatch(&self, _: Context, _: Message) {}
+
+    #[instrument(skip(self, ctx, interaction))]
+    async fn interaction(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::ApplicationCommand(interaction) => {
+                self.dispatch_command(ctx, interaction).await;
+            },
+            Interaction::Autocomplete(interaction) => {
+                self.dispatch_autocomplete(ctx, interaction).await;
+            },
+            Interaction::MessageComponent(interaction) => {
+                self.dispatch_component(ctx, interaction).await;
+            },
+            Interaction::ModalSubmit(interaction) => {
+                self.dispatch_modal(ctx, interaction).await;
+            },
+            Interaction::Ping(_) => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use futures::future::BoxFuture;
+    use typemap_rev::TypeMapKey;
+
+    use super::*;
+    use crate::framework::standard::Reason;
+    use crate::json::{self, json, Value};
+    use crate::model::permissions::Permissions;
+
+    /// The calls made by the framework, in order.
+    struct Log;
+
+    impl TypeMapKey for Log {
+        type Value = Vec<String>;
+    }
+
+    async fn push(ctx: &Context, entry: impl Into<String>) {
+        ctx.data.write().await.entry::<Log>().or_insert_with(Vec::new).push(entry.into());
+    }
+
+    async fn log(ctx: &Context) -> Vec<String> {
+        ctx.data.write().await.remove::<Log>().unwrap_or_default()
+    }
+
+    fn run<'fut>(
+        ctx: &'fut Context,
+        interaction: &'fut ApplicationCommandInteraction,
+    ) -> BoxFuture<'fut, CommandResult> {
+        Box::pin(async move {
+            push(ctx, format!("run {}", interaction.data.name)).await;
+
+            Ok(())
+        })
+    }
+
+    fn revert<'fut>(
+        ctx: &'fut Context,
+        _: &'fut ApplicationCommandInteraction,
+    ) -> BoxFuture<'fut, CommandResult> {
+        Box::pin(async move {
+            push(ctx, "revert").await;
+
+            Err(RevertBucket.into())
+        })
+    }
+
+    fn complete<'fut>(
+        ctx: &'fut Context,
+        interaction: &'fut AutocompleteInteraction,
+    ) -> BoxFuture<'fut, CommandResult> {
+        Box::pin(async move {
+            push(ctx, format!("complete {}", interaction.data.name)).await;
+
+            Ok(())
+        })
+    }
+
+    fn on_dispatch_error<'fut>(
+        ctx: &'fut Context,
+        _: &'fut ApplicationCommandInteraction,
+        error: DispatchError,
+        name: &'fut str,
+    ) -> BoxFuture<'fut, ()> {
+        Box::pin(async move {
+            let error = match error {
+                DispatchError::CheckFailed(check, _) => format!("check {}", check),
+                DispatchError::Ratelimited(_) => "ratelimited".to_string(),
+                DispatchError::OnlyForOwners => "owners".to_string(),
+                DispatchError::OnlyForDM => "dm".to_string(),
+                DispatchError::OnlyForGuilds => "guilds".to_string(),
+                DispatchError::LackingPermissions(lacking) => format!("lacking {}", lacking),
+                _ => unreachable!(),
+            };
+
+            push(ctx, format!("{} failed: {}", name, error)).await;
+        })
+    }
+
+    fn pass<'fut>(
+        ctx: &'fut Context,
+        _: &'fut ApplicationCommandInteraction,
+        _: &'fut InteractionCommand,
+    ) -> BoxFuture<'fut, StdResult<(), Reason>> {
+        Box::pin(async move {
+            push(ctx, "pass").await;
+
+            Ok(())
+        })
+    }
+
+    fn fail<'fut>(
+        ctx: &'fut Context,
+        _: &'fut ApplicationCommandInteraction,
+        _: &'fut InteractionCommand,
+    ) -> BoxFuture<'fut, StdResult<(), Reason>> {
+        Box::pin(async move {
+            push(ctx, "fail").await;
+
+            Err(Reason::Unknown)
+        })
+    }
+
+    fn interaction(kind: u8, name: &str, guild: Option<Permissions>) -> Interaction {
+        let mut value = json!({
+            "id": "1",
+            "application_id": "2",
+            "type": kind,
+            "data": { "id": "3", "name": name, "type": 1 },
+            "channel_id": "4",
+            "user": { "id": "5", "username": "ferris", "discriminator": "0001", "avatar": null },
+            "token": "token",
+            "version": 1,
+            "locale": "en-US",
+        });
+
+        if let Some(permissions) = guild {
+            let user = value["user"].clone();
+            let member = json!({
+                "user": user,
+                "roles": [],
+                "joined_at": "2022-01-01T00:00:00Z",
+                "deaf": false,
+                "mute": false,
+                "permissions": permissions.bits().to_string(),
+            });
+
+            let map = value.as_object_mut().unwrap();
+            map.insert("guild_id".to_string(), Value::from("6"));
+            map.insert("member".to_string(), member);
+        }
+
+        json::from_value(value).unwrap()
+    }
+
+    fn command(name: &str, guild: Option<Permissions>) -> Interaction {
+        interaction(2, name, guild)
+    }
+
+    #[test]
+    fn test_route_custom_id() {
+        let mut handlers = HashMap::new();
+        handlers.insert("vote".to_string(), 1);
+
+        assert_eq!(route(&handlers, "vote"), Some(1));
+        assert_eq!(route(&handlers, "vote:42"), Some(1));
+        assert_eq!(route(&handlers, "poll:42"), None);
+    }
+
+    #[tokio::test]
+    async fn test_route_by_name() {
+        let framework = InteractionFramework::new()
+            .command(InteractionCommand::new("ping", run).autocomplete(complete))
+            .command(InteractionCommand::new("pong", run));
+        let ctx = Context::test();
+
+        framework.interaction(ctx.clone(), command("pong", None)).await;
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+        framework.interaction(ctx.clone(), command("unknown", None)).await;
+        framework.interaction(ctx.clone(), interaction(4, "ping", None)).await;
+        // Commands without an autocomplete function ignore autocompletion.
+        framework.interaction(ctx.clone(), interaction(4, "pong", None)).await;
+
+        assert_eq!(log(&ctx).await, ["run pong", "run ping", "complete ping"]);
+    }
+
+    #[tokio::test]
+    async fn test_checks_short_circuit() {
+        let framework = InteractionFramework::new()
+            .command(
+                InteractionCommand::new("ping", run)
+                    .check("pass", pass)
+                    .check("fail", fail)
+                    .check("unreached", pass),
+            )
+            .on_dispatch_error(on_dispatch_error);
+        let ctx = Context::test();
+
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+
+        assert_eq!(log(&ctx).await, ["pass", "fail", "ping failed: check fail"]);
+    }
+
+    #[tokio::test]
+    async fn test_bucket() {
+        let framework = InteractionFramework::new()
+            .bucket("slow", |b| b.delay(60))
+            .await
+            .command(InteractionCommand::new("ping", run).bucket("slow"))
+            .command(InteractionCommand::new("revert", revert).bucket("slow"))
+            .on_dispatch_error(on_dispatch_error);
+        let ctx = Context::test();
+
+        // Reverting gives back the ticket taken from the bucket.
+        framework.interaction(ctx.clone(), command("revert", None)).await;
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+
+        assert_eq!(log(&ctx).await, ["revert", "run ping", "ping failed: ratelimited"]);
+    }
+
+    #[tokio::test]
+    async fn test_only_in() {
+        let framework = InteractionFramework::new()
+            .command(InteractionCommand::new("dm", run).only_in(OnlyIn::Dm))
+            .command(InteractionCommand::new("guild", run).only_in(OnlyIn::Guild))
+            .on_dispatch_error(on_dispatch_error);
+        let ctx = Context::test();
+        let member = Some(Permissions::empty());
+
+        framework.interaction(ctx.clone(), command("dm", None)).await;
+        framework.interaction(ctx.clone(), command("dm", member)).await;
+        framework.interaction(ctx.clone(), command("guild", None)).await;
+        framework.interaction(ctx.clone(), command("guild", member)).await;
+
+        assert_eq!(log(&ctx).await, [
+            "run dm",
+            "dm failed: dm",
+            "guild failed: guilds",
+            "run guild"
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_owners_only() {
+        let command_def = || InteractionCommand::new("ping", run).owners_only(true);
+        let ctx = Context::test();
+
+        let framework =
+            InteractionFramework::new().command(command_def()).on_dispatch_error(on_dispatch_error);
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+
+        let owners = std::iter::once(UserId(5)).collect();
+        let framework = InteractionFramework::new().command(command_def()).owners(owners);
+        framework.interaction(ctx.clone(), command("ping", None)).await;
+
+        assert_eq!(log(&ctx).await, ["ping failed: owners", "run ping"]);
+    }
+
+    #[tokio::test]
+    async fn test_required_permissions() {
+        let required = Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS;
+        let framework = InteractionFramework::new()
+            .command(InteractionCommand::new("ban", run).required_permissions(required))
+            .on_dispatch_error(on_dispatch_error);
+        let ctx = Context::test();
+
+        framework.interaction(ctx.clone(), command("ban", Some(Permissions::KICK_MEMBERS))).await;
+        framework.interaction(ctx.clone(), command("ban", Some(required))).await;
+
+        assert_eq!(log(&ctx).await, [
+            format!("ban failed: lacking {}", Permissions::BAN_MEMBERS),
+            "run ban".to_string(),
+        ]);
+    }
+}
@@ -80,14 +80,19 @@
 //!
 //! [`ClientBuilder::framework`]: crate::client::ClientBuilder::framework
 
+#[cfg(feature = "interaction_framework")]
+pub mod interaction;
 #[cfg(feature = "standard_framework")]
 pub mod standard;
 
 use async_trait::async_trait;
 
+#[cfg(feature = "interaction_framework")]
+pub use self::interaction::InteractionFramework;
 #[cfg(feature = "standard_framework")]
 pub use self::standard::StandardFramework;
 use crate::client::Context;
+use crate::model::application::interaction::Interaction;
 use crate::model::channel::Message;
 
 /// A trait for defining your own framework for serenity to use.
@@ -101,6 +106,14 @@ use crate::model::channel::Message;
 #[async_trait]
 pub trait Framework: Send + Sync {
     async fn dispatch(&self, _: Context, _: Message);
+
+    /// Called for every received interaction, in addition to
+    /// [`EventHandler::interaction_create`].
+    ///
+    /// Does nothing by default.
+    ///
+    /// [`EventHandler::interaction_create`]: crate::client::EventHandler::interaction_create
+    async fn interaction(&self, _: Context, _: Interaction) {}
 }
 
 #[async_trait]
@@ -112,6 +125,11 @@ where
     async fn dispatch(&self, ctx: Context, msg: Message) {
         (**self).dispatch(ctx, msg).await;
     }
+
+    #[inline]
+    async fn interaction(&self, ctx: Context, interaction: Interaction) {
+        (**self).interaction(ctx, interaction).await;
+    }
 }
 
 #[async_trait]
@@ -123,4 +141,9 @@ where
     async fn dispatch(&self, ctx: Context, msg: Message) {
         (**self).dispatch(ctx, msg).await;
     }
+
+    #[inline]
+    async fn interaction(&self, ctx: Context, interaction: Interaction) {
+        (**self).interaction(ctx, interaction).await;
+    }
 }
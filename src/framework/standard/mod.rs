@@ -26,10 +26,14 @@ use tracing::instrument;
 use uwl::Stream;
 
 use self::buckets::{RateLimitInfo, RevertBucket};
+#[cfg(feature = "interaction_framework")]
+use super::interaction::InteractionFramework;
 use super::Framework;
 #[cfg(feature = "cache")]
 use crate::cache::Cache;
 use crate::client::Context;
+#[cfg(feature = "interaction_framework")]
+use crate::model::application::interaction::Interaction;
 #[cfg(feature = "cache")]
 use crate::model::channel::Channel;
 use crate::model::channel::Message;
@@ -105,6 +109,8 @@ pub struct StandardFramework {
     prefix_only: Option<PrefixOnlyHook>,
     config: Configuration,
     help: Option<&'static HelpCommand>,
+    #[cfg(feature = "interaction_framework")]
+    interactions: Option<Arc<InteractionFramework>>,
     /// Whether the framework has been "initialized".
     ///
     /// The framework is initialized once one of the following occurs:
@@ -614,6 +620,19 @@ impl StandardFramework {
 
         self
     }
+
+    /// Sets an [`InteractionFramework`] to dispatch interactions to, in
+    /// addition to the commands parsed from messages.
+    ///
+    /// The framework is passed by [`Arc`], so a clone can be kept to register
+    /// its commands.
+    #[cfg(feature = "interaction_framework")]
+    #[must_use]
+    pub fn interactions(mut self, framework: Arc<InteractionFramework>) -> Self {
+        self.interactions = Some(framework);
+
+        self
+    }
 }
 
 #[async_trait]
@@ -779,6 +798,13 @@ impl Framework for StandardFramework {
             },
         }
     }
+
+    #[cfg(feature = "interaction_framework")]
+    async fn interaction(&self, ctx: Context, interaction: Interaction) {
+        if let Some(interactions) = &self.interactions {
+            interactions.interaction(ctx, interaction).await;
+        }
+    }
 }
 
 pub trait CommonOptions {
@@ -7,6 +7,8 @@ use futures::future::BoxFuture;
 use crate::client::Context;
 use crate::internal::tokio::spawn_named;
 use crate::model::channel::Message;
+#[cfg(feature = "interaction_framework")]
+use crate::model::id::{ChannelId, GuildId, UserId};
 
 type Check = for<'fut> fn(&'fut Context, &'fut Message) -> BoxFuture<'fut, bool>;
 
@@ -81,6 +83,55 @@ impl Bucket {
         }
     }
 
+    /// Takes a ticket for an invocation that isn't a message, such as an
+    /// interaction.
+    ///
+    /// The bucket's check and delay action expect a message, so they are not
+    /// called.
+    #[cfg(feature = "interaction_framework")]
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    pub fn take_for(&mut self, ctx: &Context, target: &BucketTarget) -> Option<RateLimitInfo> {
+        match self {
+            Self::Global(counter) => counter.take_ticket(0),
+            Self::User(counter) => counter.take_ticket(target.user.0),
+            Self::Guild(counter) => target.guild.and_then(|id| counter.take_ticket(id.0)),
+            Self::Channel(counter) => counter.take_ticket(target.channel.0),
+            #[cfg(feature = "cache")]
+            Self::Category(counter) => ctx
+                .cache
+                .guild_channel_field(target.channel, |channel| channel.parent_id)
+                .flatten()
+                .and_then(|id| counter.take_ticket(id.0)),
+        }
+    }
+
+    /// Returns a ticket taken via [`Self::take_for`].
+    #[cfg(feature = "interaction_framework")]
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    pub fn give_for(&mut self, ctx: &Context, target: &BucketTarget) {
+        match self {
+            Self::Global(counter) => counter.give_ticket(0),
+            Self::User(counter) => counter.give_ticket(target.user.0),
+            Self::Guild(counter) => {
+                if let Some(guild_id) = target.guild {
+                    counter.give_ticket(guild_id.0);
+                }
+            },
+            Self::Channel(counter) => counter.give_ticket(target.channel.0),
+            #[cfg(feature = "cache")]
+            Self::Category(counter) => {
+                let category_id = ctx
+                    .cache
+                    .guild_channel_field(target.channel, |channel| channel.parent_id)
+                    .flatten();
+
+                if let Some(category_id) = category_id {
+                    counter.give_ticket(category_id.0);
+                }
+            },
+        }
+    }
+
     #[inline]
     pub async fn give(&mut self, ctx: &Context, msg: &Message) {
         match self {
@@ -104,6 +155,15 @@ impl Bucket {
     }
 }
 
+/// The invoker of a command that isn't a message, used to pick the ticket
+/// holder in a bucket.
+#[cfg(feature = "interaction_framework")]
+pub(crate) struct BucketTarget {
+    pub user: UserId,
+    pub guild: Option<GuildId>,
+    pub channel: ChannelId,
+}
+
 /// Keeps track of who owns how many tickets and when they accessed the last
 /// time.
 pub(crate) struct TicketCounter {
@@ -183,6 +243,25 @@ impl TicketCounter {
             }
         }
 
+        let rate_limit_info = self.take_ticket(id)?;
+
+        if let (RateLimitAction::Delayed, Some(delay_action)) =
+            (&rate_limit_info.action, self.delay_action)
+        {
+            let ctx = ctx.clone();
+            let msg = msg.clone();
+
+            spawn_named("buckets::delay_action", async move {
+                delay_action(&ctx, &msg).await;
+            });
+        }
+
+        Some(rate_limit_info)
+    }
+
+    /// Takes a ticket for the ticket holder, without calling the check or the
+    /// delay action.
+    fn take_ticket(&mut self, id: u64) -> Option<RateLimitInfo> {
         let now = Instant::now();
         let Self {
             tickets_for,
@@ -206,15 +285,6 @@ impl TicketCounter {
                     let action = if self.await_ratelimits > ticket_owner.awaiting {
                         ticket_owner.awaiting += 1;
 
-                        if let Some(delay_action) = self.delay_action {
-                            let ctx = ctx.clone();
-                            let msg = msg.clone();
-
-                            spawn_named("buckets::delay_action", async move {
-                                delay_action(&ctx, &msg).await;
-                            });
-                        }
-
                         RateLimitAction::Delayed
                     // Is this bucket utilising delay limits?
                     } else if self.await_ratelimits > 0 {
@@ -253,15 +323,6 @@ impl TicketCounter {
             let action = if self.await_ratelimits > ticket_owner.awaiting {
                 ticket_owner.awaiting += 1;
 
-                if let Some(delay_action) = self.delay_action {
-                    let ctx = ctx.clone();
-                    let msg = msg.clone();
-
-                    spawn_named("buckets::delay_action", async move {
-                        delay_action(&ctx, &msg).await;
-                    });
-                }
-
                 RateLimitAction::Delayed
             // Is this bucket utilising delay limits?
             } else if self.await_ratelimits > 0 {
@@ -299,6 +360,11 @@ impl TicketCounter {
             }
         }
 
+        self.give_ticket(id);
+    }
+
+    /// Returns a ticket to the ticket holder, without calling the check.
+    fn give_ticket(&mut self, id: u64) {
         if let Some(ticket_owner) = self.tickets_for.get_mut(&id) {
             // Remove a ticket if one is available.
             if ticket_owner.tickets > 0 {
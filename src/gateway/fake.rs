@@ -0,0 +1,480 @@
+//! A local gateway server speaking Discord's gateway protocol, to test
+//! [`Shard`]s, the [`ShardRunner`] and the [`ShardManager`] without connecting
+//! to Discord.
+//!
+//! The test scripts each connection, sending payloads such as
+//! [`FakeConnection::hello`] and [`FakeConnection::ready`], and checking those
+//! the shard sends back with methods such as
+//! [`FakeConnection::expect_identify`].
+//!
+//! Payloads are sent in the encoding the shard expects, but never compressed,
+//! so shards must not use [`TransportCompression::ZlibStream`].
+//!
+//! # Examples
+//!
+//! Identify a shard, then make it resume the session on a new connection:
+//!
+//! ```rust,no_run
+//! use serde::Deserialize;
+//! use serenity::gateway::fake::FakeGateway;
+//! use serenity::gateway::{ReconnectType, Shard, ShardAction};
+//! use serenity::model::event::GatewayEvent;
+//! use serenity::model::gateway::GatewayIntents;
+//!
+//! # async fn run() -> serenity::Result<()> {
+//! async fn next_action(shard: &mut Shard) -> serenity::Result<Option<ShardAction>> {
+//!     let event = loop {
+//!         if let Some(value) = shard.recv_json().await? {
+//!             break GatewayEvent::deserialize(value).map_err(Into::into);
+//!         }
+//!     };
+//!
+//!     shard.handle_event(&event)
+//! }
+//!
+//! let gateway = FakeGateway::bind().await?;
+//! let (shard, connection) = tokio::join!(
+//!     Shard::new(gateway.ws_url(), "token", [0, 1], GatewayIntents::GUILDS),
+//!     gateway.accept(),
+//! );
+//! let (mut shard, mut connection) = (shard?, connection?);
+//!
+//! connection.hello(45_000).await?;
+//! assert!(matches!(next_action(&mut shard).await?, Some(ShardAction::Identify)));
+//!
+//! shard.identify().await?;
+//! assert_eq!(connection.expect_identify().await?.token, "token");
+//!
+//! connection.ready("session").await?;
+//! next_action(&mut shard).await?;
+//!
+//! connection.reconnect().await?;
+//! let action = next_action(&mut shard).await?;
+//! assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Resume))));
+//!
+//! let (resumed, connection) = tokio::join!(shard.resume(), gateway.accept());
+//! let (_, mut connection) = (resumed?, connection?);
+//! assert_eq!(connection.expect_resume().await?.seq, 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Shard`]: super::Shard
+//! [`TransportCompression::ZlibStream`]: super::TransportCompression::ZlibStream
+//! [`ShardRunner`]: crate::client::bridge::gateway::ShardRunner
+//! [`ShardManager`]: crate::client::bridge::gateway::ShardManager
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_tungstenite::tokio::{accept_hdr_async, TokioAdapter};
+use async_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use async_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use async_tungstenite::tungstenite::protocol::frame::CloseFrame;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::WebSocketStream;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time::timeout;
+use url::form_urlencoded;
+
+use super::{GatewayEncoding, GatewayError};
+use crate::constants::{OpCode, GATEWAY_VERSION};
+use crate::internal::prelude::*;
+use crate::internal::ws_impl::convert_ws_message;
+use crate::json::{self, json};
+use crate::model::gateway::GatewayIntents;
+
+/// How long to wait for the shard to send a payload before panicking.
+const RECV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A local gateway server, accepting connections from shards.
+pub struct FakeGateway {
+    listener: TcpListener,
+    url: String,
+}
+
+impl FakeGateway {
+    /// Binds a gateway to a random port on the local host.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`] if binding the port failed.
+    pub async fn bind() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+
+        Ok(Self {
+            listener,
+            url,
+        })
+    }
+
+    /// Gets the URL of the gateway, to return from a mocked
+    /// [`Http::get_bot_gateway`].
+    ///
+    /// [`Http::get_bot_gateway`]: crate::http::Http::get_bot_gateway
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Gets the URL of the gateway, as passed to [`Shard::new`].
+    ///
+    /// [`Shard::new`]: super::Shard::new
+    #[must_use]
+    pub fn ws_url(&self) -> Arc<Mutex<String>> {
+        Arc::new(Mutex::new(self.url.clone()))
+    }
+
+    /// Waits for a shard to connect, and completes the WebSocket handshake.
+    ///
+    /// The connection uses the encoding the shard requested in the URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`] if accepting the connection failed, or an
+    /// [`Error::Tungstenite`] if the handshake did.
+    pub async fn accept(&self) -> Result<FakeConnection> {
+        let (stream, _) = self.listener.accept().await?;
+
+        let mut encoding = GatewayEncoding::Json;
+        let callback = |request: &Request, response: Response| {
+            encoding = requested_encoding(request);
+
+            Ok::<_, ErrorResponse>(response)
+        };
+        let stream = accept_hdr_async(stream, callback).await?;
+
+        Ok(FakeConnection {
+            stream,
+            encoding,
+            url: self.url.clone(),
+            seq: 0,
+        })
+    }
+}
+
+fn requested_encoding(request: &Request) -> GatewayEncoding {
+    let query = request.uri().query().unwrap_or_default();
+    let encoding = form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "encoding")
+        .map(|(_, value)| value);
+
+    match encoding.as_deref() {
+        #[cfg(feature = "etf")]
+        Some("etf") => GatewayEncoding::Etf,
+        _ => GatewayEncoding::Json,
+    }
+}
+
+impl fmt::Debug for FakeGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakeGateway").field("url", &self.url).finish_non_exhaustive()
+    }
+}
+
+/// The IDENTIFY payload sent by a shard, as returned by
+/// [`FakeConnection::expect_identify`].
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Identify {
+    pub token: String,
+    pub shard: [u64; 2],
+    pub intents: GatewayIntents,
+    pub compress: bool,
+}
+
+/// The RESUME payload sent by a shard, as returned by
+/// [`FakeConnection::expect_resume`].
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct Resume {
+    pub token: String,
+    pub session_id: String,
+    pub seq: u64,
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    op: OpCode,
+    #[serde(default)]
+    d: Value,
+}
+
+/// A shard's connection to a [`FakeGateway`].
+///
+/// The connection keeps the sequence number of the dispatches it sent, which
+/// starts at 0 on every connection. Methods expecting a payload from the shard
+/// panic if it sends a different one, or none within 10 seconds.
+pub struct FakeConnection {
+    stream: WebSocketStream<TokioAdapter<TcpStream>>,
+    encoding: GatewayEncoding,
+    url: String,
+    seq: u64,
+}
+
+impl FakeConnection {
+    /// Gets the sequence number of the last dispatch sent.
+    #[must_use]
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Gets the encoding of the payloads exchanged with the shard.
+    #[must_use]
+    pub fn encoding(&self) -> GatewayEncoding {
+        self.encoding
+    }
+
+    /// Sets the sequence number the next dispatch follows, such as that of the
+    /// session the shard resumes.
+    pub fn set_seq(&mut self, seq: u64) {
+        self.seq = seq;
+    }
+
+    /// Sends a payload to the shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed, or an error
+    /// if the payload could not be serialized.
+    pub async fn send(&mut self, payload: &Value) -> Result<()> {
+        let message = match self.encoding {
+            GatewayEncoding::Json => Message::Text(json::to_string(payload)?),
+            #[cfg(feature = "etf")]
+            GatewayEncoding::Etf => Message::Binary(crate::etf::to_vec(payload)?),
+        };
+
+        Ok(self.stream.send(message).await?)
+    }
+
+    /// Sends a HELLO with the interval the shard should heartbeat at, in
+    /// milliseconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn hello(&mut self, heartbeat_interval: u64) -> Result<()> {
+        self.send(&json!({
+            "op": OpCode::Hello.num(),
+            "d": {
+                "heartbeat_interval": heartbeat_interval,
+            },
+        }))
+        .await
+    }
+
+    /// Sends a dispatch of the given event type, following the last one sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn dispatch(&mut self, kind: &str, data: Value) -> Result<()> {
+        self.seq += 1;
+
+        let payload = json!({
+            "op": OpCode::Event.num(),
+            "s": self.seq,
+            "t": kind,
+            "d": data,
+        });
+
+        self.send(&payload).await
+    }
+
+    /// Dispatches a READY, starting a session with the given Id for a bot
+    /// without guilds. Resuming the session reconnects to this gateway.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn ready(&mut self, session_id: &str) -> Result<()> {
+        let ready = json!({
+            "v": GATEWAY_VERSION,
+            "user": {
+                "id": "1",
+                "username": "serenity",
+                "discriminator": "0000",
+                "avatar": null,
+                "bot": true,
+                "mfa_enabled": false,
+            },
+            "guilds": [],
+            "session_id": session_id,
+            "resume_gateway_url": self.url,
+            "application": {
+                "id": "1",
+                "flags": 0,
+            },
+        });
+
+        self.dispatch("READY", ready).await
+    }
+
+    /// Dispatches a RESUMED, once the shard resumed its session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn resumed(&mut self) -> Result<()> {
+        self.dispatch("RESUMED", json!({ "_trace": [] })).await
+    }
+
+    /// Requests the shard to heartbeat immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn request_heartbeat(&mut self) -> Result<()> {
+        self.send(&json!({
+            "op": OpCode::Heartbeat.num(),
+            "s": self.seq,
+            "d": null,
+        }))
+        .await
+    }
+
+    /// Acknowledges a heartbeat of the shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn heartbeat_ack(&mut self) -> Result<()> {
+        self.send(&json!({
+            "op": OpCode::HeartbeatAck.num(),
+        }))
+        .await
+    }
+
+    /// Requests the shard to reconnect and resume its session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        self.send(&json!({
+            "op": OpCode::Reconnect.num(),
+            "d": null,
+        }))
+        .await
+    }
+
+    /// Invalidates the session of the shard, which may resume it if it is
+    /// `resumable`, and must identify again otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    pub async fn invalid_session(&mut self, resumable: bool) -> Result<()> {
+        self.send(&json!({
+            "op": OpCode::InvalidSession.num(),
+            "d": resumable,
+        }))
+        .await
+    }
+
+    /// Closes the connection with the given close code, such as one of
+    /// [`close_codes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed.
+    ///
+    /// [`close_codes`]: crate::constants::close_codes
+    pub async fn close(&mut self, code: u16, reason: &str) -> Result<()> {
+        let frame = CloseFrame {
+            code: CloseCode::from(code),
+            reason: reason.to_string().into(),
+        };
+
+        Ok(self.stream.close(Some(frame)).await?)
+    }
+
+    /// Receives the next payload sent by the shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Gateway`] if the shard closed the connection, or an
+    /// error if the payload could not be deserialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard sent no payload within 10 seconds.
+    pub async fn recv(&mut self) -> Result<Value> {
+        loop {
+            let message = timeout(RECV_TIMEOUT, self.stream.next())
+                .await
+                .expect("timed out waiting for a payload from the shard")
+                .transpose()?;
+
+            match message {
+                Some(message) => {
+                    if let Some(value) = convert_ws_message(Some(message))? {
+                        return Ok(value);
+                    }
+                },
+                None => return Err(Error::Gateway(GatewayError::Closed(None))),
+            }
+        }
+    }
+
+    /// Receives the IDENTIFY the shard sends after the HELLO.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if receiving or deserializing the payload failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard sent a different payload, or none within 10 seconds.
+    pub async fn expect_identify(&mut self) -> Result<Identify> {
+        json::from_value(self.expect(OpCode::Identify).await?)
+    }
+
+    /// Receives the RESUME the shard sends after reconnecting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if receiving or deserializing the payload failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard sent a different payload, or none within 10 seconds.
+    pub async fn expect_resume(&mut self) -> Result<Resume> {
+        json::from_value(self.expect(OpCode::Resume).await?)
+    }
+
+    /// Receives a heartbeat, returning the sequence number the shard sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if receiving or deserializing the payload failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard sent a different payload, or none within 10 seconds.
+    pub async fn expect_heartbeat(&mut self) -> Result<Option<u64>> {
+        json::from_value(self.expect(OpCode::Heartbeat).await?)
+    }
+
+    async fn expect(&mut self, op: OpCode) -> Result<Value> {
+        let payload: Payload = json::from_value(self.recv().await?)?;
+
+        assert_eq!(payload.op, op, "unexpected payload from the shard");
+
+        Ok(payload.d)
+    }
+}
+
+impl fmt::Debug for FakeConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakeConnection")
+            .field("encoding", &self.encoding)
+            .field("seq", &self.seq)
+            .finish_non_exhaustive()
+    }
+}
@@ -47,13 +47,19 @@
 //! [docs]: https://discordapp.com/developers/docs/topics/gateway#sharding
 
 mod error;
+#[cfg(feature = "fake_gateway")]
+pub mod fake;
 mod shard;
 mod ws_client_ext;
 
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 pub use self::error::Error as GatewayError;
 pub use self::shard::Shard;
+#[cfg(feature = "client")]
+pub(crate) use self::shard::ShardState;
 pub use self::ws_client_ext::WebSocketGatewayClientExt;
 #[cfg(feature = "client")]
 use crate::client::bridge::gateway::ShardClientMessage;
@@ -167,6 +173,34 @@ pub enum ShardAction {
     Reconnect(ReconnectType),
 }
 
+/// The state of a [`Shard`]'s gateway session, which can be used to resume the
+/// session from another connection, even after restarting the process.
+///
+/// This is retrieved via [`Shard::session_state`] or returned by
+/// [`ShardManager::shutdown_resumable`] and
+/// [`ShardManager::shutdown_all_resumable`], and can be passed to
+/// [`ClientBuilder::session_states`] to resume the sessions on startup.
+///
+/// [`ShardManager::shutdown_resumable`]: crate::client::bridge::gateway::ShardManager::shutdown_resumable
+/// [`ShardManager::shutdown_all_resumable`]: crate::client::bridge::gateway::ShardManager::shutdown_all_resumable
+/// [`ClientBuilder::session_states`]: crate::client::ClientBuilder::session_states
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize)]
+#[non_exhaustive]
+pub struct ShardSessionState {
+    /// The Id of the shard and the total number of shards, as in
+    /// [`Shard::shard_info`].
+    pub shard_info: [u64; 2],
+    /// The Id of the session.
+    pub session_id: String,
+    /// The sequence number of the last event received in the session.
+    pub seq: u64,
+    /// The URL to connect to when resuming the session, as given by
+    /// [`Ready::resume_gateway_url`].
+    ///
+    /// [`Ready::resume_gateway_url`]: crate::model::gateway::Ready::resume_gateway_url
+    pub resume_gateway_url: Option<String>,
+}
+
 /// The type of reconnection that should be performed.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -176,3 +210,75 @@ pub enum ReconnectType {
     /// Indicator that a new connection should be made by sending a RESUME.
     Resume,
 }
+
+/// The compression used for payloads received from the gateway.
+///
+/// Refer to [Discord's documentation][docs] for more information.
+///
+/// [docs]: https://discord.com/developers/docs/topics/gateway#encoding-and-compression
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum TransportCompression {
+    /// No compression is used.
+    None,
+    /// Large payloads are compressed individually, by sending `compress` in the
+    /// IDENTIFY payload.
+    Payload,
+    /// The whole connection is compressed with a single zlib context, by
+    /// connecting with `compress=zlib-stream`.
+    ///
+    /// This saves more bandwidth than [`Self::Payload`], since all payloads
+    /// share a compression context and small payloads are compressed as well.
+    ZlibStream,
+}
+
+impl Default for TransportCompression {
+    fn default() -> Self {
+        Self::Payload
+    }
+}
+
+impl TransportCompression {
+    /// The value of the `compress` query parameter in the gateway URL, if any.
+    fn query_param(self) -> Option<&'static str> {
+        match self {
+            Self::ZlibStream => Some("zlib-stream"),
+            Self::None | Self::Payload => None,
+        }
+    }
+}
+
+/// The encoding of payloads exchanged with the gateway.
+///
+/// Refer to [Discord's documentation][docs] for more information.
+///
+/// [docs]: https://discord.com/developers/docs/topics/gateway#encoding-and-compression
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum GatewayEncoding {
+    /// Payloads are encoded as JSON.
+    Json,
+    /// Payloads are encoded in Erlang's External Term Format, by connecting
+    /// with `encoding=etf`.
+    ///
+    /// This is more compact than JSON, especially without compression.
+    #[cfg(feature = "etf")]
+    Etf,
+}
+
+impl Default for GatewayEncoding {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl GatewayEncoding {
+    /// The value of the `encoding` query parameter in the gateway URL, if any.
+    fn query_param(self) -> Option<&'static str> {
+        match self {
+            Self::Json => None,
+            #[cfg(feature = "etf")]
+            Self::Etf => Some("etf"),
+        }
+    }
+}
@@ -7,20 +7,29 @@ use tokio::sync::Mutex;
 use tracing::{debug, error, info, instrument, trace, warn};
 use url::Url;
 
+use super::ws_client_ext::{
+    chunk_guild_payload,
+    heartbeat_payload,
+    identify_payload,
+    presence_update_payload,
+    resume_payload,
+};
 use super::{
     ConnectionStage,
     CurrentPresence,
+    GatewayEncoding,
     GatewayError,
     ReconnectType,
     ShardAction,
-    WebSocketGatewayClientExt,
+    ShardSessionState,
+    TransportCompression,
     WsStream,
 };
 use crate::client::bridge::gateway::ChunkGuildFilter;
 use crate::constants::{self, close_codes};
 use crate::http::Http;
 use crate::internal::prelude::*;
-use crate::internal::ws_impl::create_client;
+use crate::internal::ws_impl::{create_client, ReceiverExt, SenderExt, ZlibStreamInflater};
 use crate::model::event::{Event, GatewayEvent};
 use crate::model::gateway::{Activity, GatewayIntents};
 use crate::model::id::GuildId;
@@ -60,27 +69,13 @@ use crate::model::user::OnlineStatus;
 /// [module docs]: crate::gateway#sharding
 pub struct Shard {
     pub client: WsStream,
+    compression: TransportCompression,
+    state: ShardState,
+    encoding: GatewayEncoding,
     current_presence: CurrentPresence,
-    /// A tuple of:
-    ///
-    /// - the last instant that a heartbeat was sent
-    /// - the last instant that an acknowledgement was received
-    ///
-    /// This can be used to calculate [`latency`].
-    ///
-    /// [`latency`]: fn@Self::latency
-    heartbeat_instants: (Option<Instant>, Option<Instant>),
-    heartbeat_interval: Option<u64>,
-    http: Option<Arc<Http>>,
-    /// This is used by the heartbeater to determine whether the last
-    /// heartbeat was sent without an acknowledgement, and whether to reconnect.
-    // This _must_ be set to `true` in `Shard::handle_event`'s
-    // `Ok(GatewayEvent::HeartbeatAck)` arm.
-    last_heartbeat_acknowledged: bool,
-    seq: u64,
-    session_id: Option<String>,
-    shard_info: [u64; 2],
-    stage: ConnectionStage,
+    /// The inflater of the current connection, if it uses
+    /// [`TransportCompression::ZlibStream`].
+    inflater: Option<ZlibStreamInflater>,
     /// Instant of when the shard was started.
     // This acts as a timeout to determine if the shard has - for some reason -
     // not started within a decent amount of time.
@@ -131,41 +126,149 @@ impl Shard {
         token: &str,
         shard_info: [u64; 2],
         intents: GatewayIntents,
+    ) -> Result<Shard> {
+        Self::new_with_compression(
+            ws_url,
+            token,
+            shard_info,
+            intents,
+            TransportCompression::default(),
+        )
+        .await
+    }
+
+    /// Instantiates a new instance of a Shard which receives payloads with the
+    /// given [`TransportCompression`].
+    ///
+    /// Refer to [`Self::new`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// On Error, will return either [`Error::Gateway`], [`Error::Tungstenite`]
+    /// or a Rustls/native TLS error.
+    pub async fn new_with_compression(
+        ws_url: Arc<Mutex<String>>,
+        token: &str,
+        shard_info: [u64; 2],
+        intents: GatewayIntents,
+        compression: TransportCompression,
+    ) -> Result<Shard> {
+        Self::new_with_encoding(
+            ws_url,
+            token,
+            shard_info,
+            intents,
+            compression,
+            GatewayEncoding::default(),
+        )
+        .await
+    }
+
+    /// Instantiates a new instance of a Shard which receives payloads with the
+    /// given [`TransportCompression`], and exchanges them in the given
+    /// [`GatewayEncoding`].
+    ///
+    /// Refer to [`Self::new`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// On Error, will return either [`Error::Gateway`], [`Error::Tungstenite`]
+    /// or a Rustls/native TLS error.
+    pub async fn new_with_encoding(
+        ws_url: Arc<Mutex<String>>,
+        token: &str,
+        shard_info: [u64; 2],
+        intents: GatewayIntents,
+        compression: TransportCompression,
+        encoding: GatewayEncoding,
     ) -> Result<Shard> {
         let url = ws_url.lock().await.clone();
-        let client = connect(&url).await?;
+        let client = connect(&url, compression, encoding).await?;
+
+        Ok(Self::with_client(client, ws_url, token, shard_info, intents, compression, encoding))
+    }
+
+    /// Instantiates a shard which resumes a session retrieved via
+    /// [`Self::session_state`], for example one of a previous process.
+    ///
+    /// This connects straight to the session's resume URL if it has one, or to
+    /// the given `ws_url` otherwise, and sends a RESUME right away. If the
+    /// session can no longer be resumed, the gateway invalidates it and the
+    /// shard falls back to identifying.
+    ///
+    /// Refer to [`Self::new`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// On Error, will return either [`Error::Gateway`], [`Error::Tungstenite`]
+    /// or a Rustls/native TLS error.
+    pub async fn new_resumed(
+        ws_url: Arc<Mutex<String>>,
+        token: &str,
+        state: ShardSessionState,
+        intents: GatewayIntents,
+        compression: TransportCompression,
+        encoding: GatewayEncoding,
+    ) -> Result<Shard> {
+        let url = match &state.resume_gateway_url {
+            Some(url) => url.clone(),
+            None => ws_url.lock().await.clone(),
+        };
+        let client = connect(&url, compression, encoding).await?;
+
+        let shard_info = state.shard_info;
+        let mut shard =
+            Self::with_client(client, ws_url, token, shard_info, intents, compression, encoding);
+        shard.state.session_id = Some(state.session_id);
+        shard.state.seq = state.seq;
+        shard.state.resume_ws_url = state.resume_gateway_url;
+        shard.send_resume().await?;
+
+        Ok(shard)
+    }
 
+    fn with_client(
+        client: WsStream,
+        ws_url: Arc<Mutex<String>>,
+        token: &str,
+        shard_info: [u64; 2],
+        intents: GatewayIntents,
+        compression: TransportCompression,
+        encoding: GatewayEncoding,
+    ) -> Shard {
         let current_presence = (None, OnlineStatus::Online);
-        let heartbeat_instants = (None, None);
-        let heartbeat_interval = None;
-        let last_heartbeat_acknowledged = true;
-        let seq = 0;
-        let stage = ConnectionStage::Handshake;
-        let session_id = None;
-
-        Ok(Shard {
+
+        Shard {
             client,
+            compression,
+            state: ShardState::new(shard_info),
+            encoding,
             current_presence,
-            heartbeat_instants,
-            heartbeat_interval,
-            http: None,
-            last_heartbeat_acknowledged,
-            seq,
-            stage,
+            inflater: new_inflater(compression),
             started: Instant::now(),
             token: token.to_string(),
-            session_id,
-            shard_info,
             ws_url,
             intents,
-        })
+        }
     }
 
     /// Sets the associated [`Http`] client.
     ///
     /// This will update the client's application id after the shard receives a READY payload.
     pub fn set_http(&mut self, http: Arc<Http>) {
-        self.http = Some(http);
+        self.state.set_http(http);
+    }
+
+    /// Retrieves the compression used for payloads received by the shard.
+    #[inline]
+    pub fn compression(&self) -> TransportCompression {
+        self.compression
+    }
+
+    /// Retrieves the encoding of payloads exchanged by the shard.
+    #[inline]
+    pub fn encoding(&self) -> GatewayEncoding {
+        self.encoding
     }
 
     /// Retrieves the current presence of the shard.
@@ -180,19 +283,19 @@ impl Shard {
     /// acknowledgement was last received.
     #[inline]
     pub fn heartbeat_instants(&self) -> &(Option<Instant>, Option<Instant>) {
-        &self.heartbeat_instants
+        &self.state.heartbeat_instants
     }
 
     /// Retrieves the value of when the last heartbeat was sent.
     #[inline]
     pub fn last_heartbeat_sent(&self) -> Option<&Instant> {
-        self.heartbeat_instants.0.as_ref()
+        self.state.heartbeat_instants.0.as_ref()
     }
 
     /// Retrieves the value of when the last heartbeat ack was received.
     #[inline]
     pub fn last_heartbeat_ack(&self) -> Option<&Instant> {
-        self.heartbeat_instants.1.as_ref()
+        self.state.heartbeat_instants.1.as_ref()
     }
 
     /// Sends a heartbeat to the gateway with the current sequence.
@@ -206,10 +309,13 @@ impl Shard {
     /// a heartbeat.
     #[instrument(skip(self))]
     pub async fn heartbeat(&mut self) -> Result<()> {
-        match self.client.send_heartbeat(&self.shard_info, Some(self.seq)).await {
+        match self
+            .send_payload(&heartbeat_payload(&self.state.shard_info, Some(self.state.seq)))
+            .await
+        {
             Ok(()) => {
-                self.heartbeat_instants.0 = Some(Instant::now());
-                self.last_heartbeat_acknowledged = false;
+                self.state.heartbeat_instants.0 = Some(Instant::now());
+                self.state.last_heartbeat_acknowledged = false;
 
                 Ok(())
             },
@@ -217,11 +323,17 @@ impl Shard {
                 match why {
                     Error::Tungstenite(TungsteniteError::Io(err)) => {
                         if err.raw_os_error() != Some(32) {
-                            debug!("[Shard {:?}] Err heartbeating: {:?}", self.shard_info, err);
+                            debug!(
+                                "[Shard {:?}] Err heartbeating: {:?}",
+                                self.state.shard_info, err
+                            );
                         }
                     },
                     other => {
-                        warn!("[Shard {:?}] Other err w/ keepalive: {:?}", self.shard_info, other);
+                        warn!(
+                            "[Shard {:?}] Other err w/ keepalive: {:?}",
+                            self.state.shard_info, other
+                        );
                     },
                 }
 
@@ -232,22 +344,30 @@ impl Shard {
 
     #[inline]
     pub fn heartbeat_interval(&self) -> Option<&u64> {
-        self.heartbeat_interval.as_ref()
+        self.state.heartbeat_interval.as_ref()
     }
 
     #[inline]
     pub fn last_heartbeat_acknowledged(&self) -> bool {
-        self.last_heartbeat_acknowledged
+        self.state.last_heartbeat_acknowledged
     }
 
     #[inline]
     pub fn seq(&self) -> u64 {
-        self.seq
+        self.state.seq
     }
 
     #[inline]
     pub fn session_id(&self) -> Option<&String> {
-        self.session_id.as_ref()
+        self.state.session_id.as_ref()
+    }
+
+    /// Retrieves the state of the shard's session, which can be used to
+    /// resume it later via [`Self::resume_session`].
+    ///
+    /// Returns `None` if the shard has no session.
+    pub fn session_state(&self) -> Option<ShardSessionState> {
+        self.state.session_state()
     }
 
     #[inline]
@@ -310,159 +430,12 @@ impl Shard {
     /// # }
     /// ```
     pub fn shard_info(&self) -> [u64; 2] {
-        self.shard_info
+        self.state.shard_info
     }
 
     /// Returns the current connection stage of the shard.
     pub fn stage(&self) -> ConnectionStage {
-        self.stage
-    }
-
-    #[instrument(skip(self))]
-    fn handle_gateway_dispatch(&mut self, seq: u64, event: &Event) -> Option<ShardAction> {
-        if seq > self.seq + 1 {
-            warn!("[Shard {:?}] Sequence off; them: {}, us: {}", self.shard_info, seq, self.seq);
-        }
-
-        match event {
-            Event::Ready(ref ready) => {
-                debug!("[Shard {:?}] Received Ready", self.shard_info);
-
-                self.session_id = Some(ready.ready.session_id.clone());
-                self.stage = ConnectionStage::Connected;
-
-                if let Some(ref http) = self.http {
-                    http.set_application_id(ready.ready.application.id.0);
-                }
-            },
-            Event::Resumed(_) => {
-                info!("[Shard {:?}] Resumed", self.shard_info);
-
-                self.stage = ConnectionStage::Connected;
-                self.last_heartbeat_acknowledged = true;
-                self.heartbeat_instants = (Some(Instant::now()), None);
-            },
-            _ => {},
-        }
-
-        self.seq = seq;
-
-        None
-    }
-
-    #[instrument(skip(self))]
-    fn handle_heartbeat_event(&mut self, s: u64) -> ShardAction {
-        info!("[Shard {:?}] Received shard heartbeat", self.shard_info);
-
-        // Received seq is off -- attempt to resume.
-        if s > self.seq + 1 {
-            info!(
-                "[Shard {:?}] Received off sequence (them: {}; us: {}); resuming",
-                self.shard_info, s, self.seq
-            );
-
-            if self.stage == ConnectionStage::Handshake {
-                self.stage = ConnectionStage::Identifying;
-
-                return ShardAction::Identify;
-            }
-            warn!(
-                "[Shard {:?}] Heartbeat during non-Handshake; auto-reconnecting",
-                self.shard_info
-            );
-
-            return ShardAction::Reconnect(self.reconnection_type());
-        }
-
-        ShardAction::Heartbeat
-    }
-
-    #[instrument(skip(self))]
-    fn handle_gateway_closed(
-        &mut self,
-        data: &Option<CloseFrame<'static>>,
-    ) -> Result<Option<ShardAction>> {
-        let num = data.as_ref().map(|d| d.code.into());
-        let clean = num == Some(1000);
-
-        match num {
-            Some(close_codes::UNKNOWN_OPCODE) => {
-                warn!("[Shard {:?}] Sent invalid opcode.", self.shard_info);
-            },
-            Some(close_codes::DECODE_ERROR) => {
-                warn!("[Shard {:?}] Sent invalid message.", self.shard_info);
-            },
-            Some(close_codes::NOT_AUTHENTICATED) => {
-                warn!("[Shard {:?}] Sent no authentication.", self.shard_info);
-
-                return Err(Error::Gateway(GatewayError::NoAuthentication));
-            },
-            Some(close_codes::AUTHENTICATION_FAILED) => {
-                error!(
-                    "[Shard {:?}] Sent invalid authentication, please check the token.",
-                    self.shard_info
-                );
-
-                return Err(Error::Gateway(GatewayError::InvalidAuthentication));
-            },
-            Some(close_codes::ALREADY_AUTHENTICATED) => {
-                warn!("[Shard {:?}] Already authenticated.", self.shard_info);
-            },
-            Some(close_codes::INVALID_SEQUENCE) => {
-                warn!("[Shard {:?}] Sent invalid seq: {}.", self.shard_info, self.seq);
-
-                self.seq = 0;
-            },
-            Some(close_codes::RATE_LIMITED) => {
-                warn!("[Shard {:?}] Gateway ratelimited.", self.shard_info);
-            },
-            Some(close_codes::INVALID_SHARD) => {
-                warn!("[Shard {:?}] Sent invalid shard data.", self.shard_info);
-
-                return Err(Error::Gateway(GatewayError::InvalidShardData));
-            },
-            Some(close_codes::SHARDING_REQUIRED) => {
-                error!("[Shard {:?}] Shard has too many guilds.", self.shard_info);
-
-                return Err(Error::Gateway(GatewayError::OverloadedShard));
-            },
-            Some(4006 | close_codes::SESSION_TIMEOUT) => {
-                info!("[Shard {:?}] Invalid session.", self.shard_info);
-
-                self.session_id = None;
-            },
-            Some(close_codes::INVALID_GATEWAY_INTENTS) => {
-                error!("[Shard {:?}] Invalid gateway intents have been provided.", self.shard_info);
-
-                return Err(Error::Gateway(GatewayError::InvalidGatewayIntents));
-            },
-            Some(close_codes::DISALLOWED_GATEWAY_INTENTS) => {
-                error!(
-                    "[Shard {:?}] Disallowed gateway intents have been provided.",
-                    self.shard_info
-                );
-
-                return Err(Error::Gateway(GatewayError::DisallowedGatewayIntents));
-            },
-            Some(other) if !clean => {
-                warn!(
-                    "[Shard {:?}] Unknown unclean close {}: {:?}",
-                    self.shard_info,
-                    other,
-                    data.as_ref().map(|d| &d.reason),
-                );
-            },
-            _ => {},
-        }
-
-        let resume = num
-            .map_or(true, |x| x != close_codes::AUTHENTICATION_FAILED && self.session_id.is_some());
-
-        Ok(Some(if resume {
-            ShardAction::Reconnect(ReconnectType::Resume)
-        } else {
-            ShardAction::Reconnect(ReconnectType::Reidentify)
-        }))
+        self.state.stage
     }
 
     /// Handles an event from the gateway over the receiver, requiring the
@@ -491,62 +464,8 @@ impl Shard {
     /// many guilds assigned to it.
     #[instrument(skip(self))]
     pub fn handle_event(&mut self, event: &Result<GatewayEvent>) -> Result<Option<ShardAction>> {
-        match *event {
-            Ok(GatewayEvent::Dispatch(seq, ref event)) => {
-                Ok(self.handle_gateway_dispatch(seq, event))
-            },
-            Ok(GatewayEvent::Heartbeat(s)) => Ok(Some(self.handle_heartbeat_event(s))),
-            Ok(GatewayEvent::HeartbeatAck) => {
-                self.heartbeat_instants.1 = Some(Instant::now());
-                self.last_heartbeat_acknowledged = true;
-
-                trace!("[Shard {:?}] Received heartbeat ack", self.shard_info);
-
-                Ok(None)
-            },
-            Ok(GatewayEvent::Hello(interval)) => {
-                debug!("[Shard {:?}] Received a Hello; interval: {}", self.shard_info, interval);
-
-                if self.stage == ConnectionStage::Resuming {
-                    return Ok(None);
-                }
-
-                if interval > 0 {
-                    self.heartbeat_interval = Some(interval);
-                }
-
-                Ok(Some(if self.stage == ConnectionStage::Handshake {
-                    ShardAction::Identify
-                } else {
-                    debug!("[Shard {:?}] Received late Hello; autoreconnecting", self.shard_info);
-
-                    ShardAction::Reconnect(self.reconnection_type())
-                }))
-            },
-            Ok(GatewayEvent::InvalidateSession(resumable)) => {
-                info!("[Shard {:?}] Received session invalidation", self.shard_info,);
-
-                Ok(Some(if resumable {
-                    ShardAction::Reconnect(ReconnectType::Resume)
-                } else {
-                    ShardAction::Reconnect(ReconnectType::Reidentify)
-                }))
-            },
-            Ok(GatewayEvent::Reconnect) => Ok(Some(ShardAction::Reconnect(ReconnectType::Resume))),
-            Err(Error::Gateway(GatewayError::Closed(ref data))) => self.handle_gateway_closed(data),
-            Err(Error::Tungstenite(ref why)) => {
-                warn!("[Shard {:?}] Websocket error: {:?}", self.shard_info, why);
-                info!("[Shard {:?}] Will attempt to auto-reconnect", self.shard_info);
-
-                Ok(Some(ShardAction::Reconnect(self.reconnection_type())))
-            },
-            Err(ref why) => {
-                warn!("[Shard {:?}] Unhandled error: {:?}", self.shard_info, why);
-
-                Ok(None)
-            },
-        }
-    }
+        self.state.handle_event(event)
+    }
 
     /// Checks whether a heartbeat needs to be sent, as well as whether a
     /// heartbeat acknowledgement was received.
@@ -564,7 +483,7 @@ impl Shard {
     #[instrument(skip(self))]
     pub async fn check_heartbeat(&mut self) -> bool {
         let wait = {
-            let heartbeat_interval = match self.heartbeat_interval {
+            let heartbeat_interval = match self.state.heartbeat_interval {
                 Some(heartbeat_interval) => heartbeat_interval,
                 None => {
                     return self.started.elapsed() < StdDuration::from_secs(15);
@@ -576,7 +495,7 @@ impl Shard {
 
         // If a duration of time less than the heartbeat_interval has passed,
         // then don't perform a keepalive or attempt to reconnect.
-        if let Some(last_sent) = self.heartbeat_instants.0 {
+        if let Some(last_sent) = self.state.heartbeat_instants.0 {
             if last_sent.elapsed() <= wait {
                 return true;
             }
@@ -584,19 +503,19 @@ impl Shard {
 
         // If the last heartbeat didn't receive an acknowledgement, then
         // auto-reconnect.
-        if !self.last_heartbeat_acknowledged {
-            debug!("[Shard {:?}] Last heartbeat not acknowledged", self.shard_info,);
+        if !self.state.last_heartbeat_acknowledged {
+            debug!("[Shard {:?}] Last heartbeat not acknowledged", self.state.shard_info,);
 
             return false;
         }
 
         // Otherwise, we're good to heartbeat.
         if let Err(why) = self.heartbeat().await {
-            warn!("[Shard {:?}] Err heartbeating: {:?}", self.shard_info, why);
+            warn!("[Shard {:?}] Err heartbeating: {:?}", self.state.shard_info, why);
 
             false
         } else {
-            trace!("[Shard {:?}] Heartbeat", self.shard_info);
+            trace!("[Shard {:?}] Heartbeat", self.state.shard_info);
 
             true
         }
@@ -607,7 +526,7 @@ impl Shard {
     // <https://github.com/abalabahaha/eris/commit/0ce296ae9a542bcec0edf1c999ee2d9986bed5a6>
     #[instrument(skip(self))]
     pub fn latency(&self) -> Option<StdDuration> {
-        if let (Some(sent), Some(received)) = self.heartbeat_instants {
+        if let (Some(sent), Some(received)) = self.state.heartbeat_instants {
             if received > sent {
                 return Some(received - sent);
             }
@@ -626,7 +545,7 @@ impl Shard {
     /// Note that, if the shard is already in a stage of
     /// [`ConnectionStage::Connecting`], then no action will be performed.
     pub fn should_reconnect(&mut self) -> Option<ReconnectType> {
-        if self.stage == ConnectionStage::Connecting {
+        if self.state.stage == ConnectionStage::Connecting {
             return None;
         }
 
@@ -634,11 +553,7 @@ impl Shard {
     }
 
     pub fn reconnection_type(&self) -> ReconnectType {
-        if self.session_id().is_some() {
-            ReconnectType::Resume
-        } else {
-            ReconnectType::Reidentify
-        }
+        self.state.reconnection_type()
     }
 
     /// Requests that one or multiple [`Guild`]s be chunked.
@@ -720,9 +635,16 @@ impl Shard {
         filter: ChunkGuildFilter,
         nonce: Option<&str>,
     ) -> Result<()> {
-        debug!("[Shard {:?}] Requesting member chunks", self.shard_info);
-
-        self.client.send_chunk_guild(guild_id, &self.shard_info, limit, filter, nonce).await
+        debug!("[Shard {:?}] Requesting member chunks", self.state.shard_info);
+
+        self.send_payload(&chunk_guild_payload(
+            guild_id,
+            &self.state.shard_info,
+            limit,
+            filter,
+            nonce,
+        ))
+        .await
     }
 
     /// Sets the shard as going into identifying stage, which sets:
@@ -731,10 +653,15 @@ impl Shard {
     /// - the `stage` to [`ConnectionStage::Identifying`]
     #[instrument(skip(self))]
     pub async fn identify(&mut self) -> Result<()> {
-        self.client.send_identify(&self.shard_info, &self.token, self.intents).await?;
+        // A new session is resumed from whichever URL its READY specifies.
+        self.state.resume_ws_url = None;
+
+        let compress = self.compression == TransportCompression::Payload;
+        let payload = identify_payload(&self.state.shard_info, &self.token, self.intents, compress);
+        self.send_payload(&payload).await?;
 
-        self.heartbeat_instants.0 = Some(Instant::now());
-        self.stage = ConnectionStage::Identifying;
+        self.state.heartbeat_instants.0 = Some(Instant::now());
+        self.state.stage = ConnectionStage::Identifying;
 
         Ok(())
     }
@@ -745,7 +672,13 @@ impl Shard {
     /// the client.
     #[instrument(skip(self))]
     pub async fn initialize(&mut self) -> Result<WsStream> {
-        debug!("[Shard {:?}] Initializing.", self.shard_info);
+        let url = self.ws_url.lock().await.clone();
+
+        self.initialize_with_url(&url).await
+    }
+
+    async fn initialize_with_url(&mut self, url: &str) -> Result<WsStream> {
+        debug!("[Shard {:?}] Initializing.", self.state.shard_info);
 
         // We need to do two, sort of three things here:
         //
@@ -755,35 +688,88 @@ impl Shard {
         //
         // This is used to accurately assess whether the state of the shard is
         // accurate when a Hello is received.
-        self.stage = ConnectionStage::Connecting;
+        self.state.stage = ConnectionStage::Connecting;
         self.started = Instant::now();
-        let url = &self.ws_url.lock().await.clone();
-        let client = connect(url).await?;
-        self.stage = ConnectionStage::Handshake;
+        let client = connect(url, self.compression, self.encoding).await?;
+        self.inflater = new_inflater(self.compression);
+        self.state.stage = ConnectionStage::Handshake;
 
         Ok(client)
     }
 
     #[instrument(skip(self))]
     pub async fn reset(&mut self) {
-        self.heartbeat_instants = (Some(Instant::now()), None);
-        self.heartbeat_interval = None;
-        self.last_heartbeat_acknowledged = true;
-        self.session_id = None;
-        self.stage = ConnectionStage::Disconnected;
-        self.seq = 0;
+        self.state.heartbeat_instants = (Some(Instant::now()), None);
+        self.state.heartbeat_interval = None;
+        self.state.last_heartbeat_acknowledged = true;
+        self.state.resume_ws_url = None;
+        self.state.session_id = None;
+        self.state.stage = ConnectionStage::Disconnected;
+        self.state.seq = 0;
     }
 
+    /// Reconnects and resumes the current session.
+    ///
+    /// This connects to the session's [`Ready::resume_gateway_url`] if Discord
+    /// gave one, and to the regular gateway URL otherwise.
+    ///
+    /// [`Ready::resume_gateway_url`]: crate::model::gateway::Ready::resume_gateway_url
     #[instrument(skip(self))]
     pub async fn resume(&mut self) -> Result<()> {
-        debug!("[Shard {:?}] Attempting to resume", self.shard_info);
+        debug!("[Shard {:?}] Attempting to resume", self.state.shard_info);
 
-        self.client = self.initialize().await?;
-        self.stage = ConnectionStage::Resuming;
+        self.client = match self.state.resume_ws_url.clone() {
+            Some(url) => self.initialize_with_url(&url).await?,
+            None => self.initialize().await?,
+        };
+        self.send_resume().await
+    }
+
+    /// Resumes a session retrieved via [`Self::session_state`], for example
+    /// one of a previous process.
+    ///
+    /// If the session has a resume URL, this reconnects to it first. Otherwise
+    /// the session is resumed over the shard's current connection. To avoid
+    /// connecting twice, prefer [`Self::new_resumed`] for a new shard.
+    ///
+    /// If the session can no longer be resumed, the gateway invalidates it and
+    /// the shard falls back to identifying.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GatewayError::InvalidShardData`] if the session belongs to a
+    /// different shard, or an error if sending the RESUME failed.
+    #[instrument(skip(self, state))]
+    pub async fn resume_session(&mut self, state: ShardSessionState) -> Result<()> {
+        if state.shard_info != self.state.shard_info {
+            return Err(Error::Gateway(GatewayError::InvalidShardData));
+        }
+
+        debug!(
+            "[Shard {:?}] Attempting to resume session of a previous connection",
+            self.state.shard_info
+        );
+
+        self.state.session_id = Some(state.session_id);
+        self.state.seq = state.seq;
+        self.state.resume_ws_url = state.resume_gateway_url;
+
+        if self.state.resume_ws_url.is_some() {
+            self.resume().await
+        } else {
+            self.send_resume().await
+        }
+    }
 
-        match self.session_id.as_ref() {
+    async fn send_resume(&mut self) -> Result<()> {
+        self.state.stage = ConnectionStage::Resuming;
+
+        match self.state.session_id.as_ref() {
             Some(session_id) => {
-                self.client.send_resume(&self.shard_info, session_id, self.seq, &self.token).await
+                let payload =
+                    resume_payload(&self.state.shard_info, session_id, self.state.seq, &self.token);
+
+                self.send_payload(&payload).await
             },
             None => Err(Error::Gateway(GatewayError::NoSessionId)),
         }
@@ -801,17 +787,342 @@ impl Shard {
 
     #[instrument(skip(self))]
     pub async fn update_presence(&mut self) -> Result<()> {
-        self.client.send_presence_update(&self.shard_info, &self.current_presence).await
+        self.send_payload(&presence_update_payload(&self.state.shard_info, &self.current_presence))
+            .await
+    }
+
+    /// Sends a payload in the shard's [`GatewayEncoding`].
+    pub(crate) async fn send_payload(&mut self, payload: &Value) -> Result<()> {
+        self.client.send_encoded(payload, self.encoding).await
+    }
+
+    /// Receives the next payload from the gateway, decompressing it according
+    /// to the shard's [`TransportCompression`].
+    ///
+    /// Returns `None` if no complete payload was received in time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Tungstenite`] if the connection failed, or an
+    /// [`Error::Gateway`] if it was closed. Returns an [`Error::Io`] or
+    /// a JSON error if the payload could not be decompressed or deserialized.
+    #[instrument(skip(self))]
+    pub async fn recv_json(&mut self) -> Result<Option<Value>> {
+        match &mut self.inflater {
+            Some(inflater) => self.client.recv_json_inflated(inflater).await,
+            None => self.client.recv_json().await,
+        }
+    }
+}
+
+fn new_inflater(compression: TransportCompression) -> Option<ZlibStreamInflater> {
+    match compression {
+        TransportCompression::ZlibStream => Some(ZlibStreamInflater::new()),
+        TransportCompression::None | TransportCompression::Payload => None,
     }
 }
 
-async fn connect(base_url: &str) -> Result<WsStream> {
-    let url =
-        Url::parse(&format!("{}?v={}", base_url, constants::GATEWAY_VERSION)).map_err(|why| {
-            warn!("Error building gateway URL with base `{}`: {:?}", base_url, why);
+/// The state of a shard which does not depend on its connection, such as its
+/// session, and the handling of the events it receives.
+///
+/// This is what a [`Shard`] keeps across reconnects, and lets recorded events
+/// be replayed without a connection.
+#[derive(Debug)]
+pub(crate) struct ShardState {
+    /// A tuple of:
+    ///
+    /// - the last instant that a heartbeat was sent
+    /// - the last instant that an acknowledgement was received
+    ///
+    /// This can be used to calculate [`latency`].
+    ///
+    /// [`latency`]: Shard::latency
+    heartbeat_instants: (Option<Instant>, Option<Instant>),
+    heartbeat_interval: Option<u64>,
+    http: Option<Arc<Http>>,
+    /// This is used by the heartbeater to determine whether the last
+    /// heartbeat was sent without an acknowledgement, and whether to reconnect.
+    // This _must_ be set to `true` in `Self::handle_event`'s
+    // `Ok(GatewayEvent::HeartbeatAck)` arm.
+    last_heartbeat_acknowledged: bool,
+    /// The URL to connect to when resuming the current session.
+    resume_ws_url: Option<String>,
+    seq: u64,
+    session_id: Option<String>,
+    shard_info: [u64; 2],
+    stage: ConnectionStage,
+}
+
+impl ShardState {
+    pub(crate) fn new(shard_info: [u64; 2]) -> Self {
+        Self {
+            heartbeat_instants: (None, None),
+            heartbeat_interval: None,
+            http: None,
+            last_heartbeat_acknowledged: true,
+            resume_ws_url: None,
+            seq: 0,
+            session_id: None,
+            shard_info,
+            stage: ConnectionStage::Handshake,
+        }
+    }
+
+    pub(crate) fn set_http(&mut self, http: Arc<Http>) {
+        self.http = Some(http);
+    }
+
+    pub(crate) fn session_state(&self) -> Option<ShardSessionState> {
+        self.session_id.as_ref().map(|session_id| ShardSessionState {
+            shard_info: self.shard_info,
+            session_id: session_id.clone(),
+            seq: self.seq,
+            resume_gateway_url: self.resume_ws_url.clone(),
+        })
+    }
+
+    fn reconnection_type(&self) -> ReconnectType {
+        if self.session_id.is_some() {
+            ReconnectType::Resume
+        } else {
+            ReconnectType::Reidentify
+        }
+    }
+
+    #[instrument(skip(self))]
+    fn handle_gateway_dispatch(&mut self, seq: u64, event: &Event) -> Option<ShardAction> {
+        if seq > self.seq + 1 {
+            warn!("[Shard {:?}] Sequence off; them: {}, us: {}", self.shard_info, seq, self.seq);
+        }
+
+        match event {
+            Event::Ready(ref ready) => {
+                debug!("[Shard {:?}] Received Ready", self.shard_info);
+
+                self.session_id = Some(ready.ready.session_id.clone());
+                self.resume_ws_url.clone_from(&ready.ready.resume_gateway_url);
+                self.stage = ConnectionStage::Connected;
+
+                if let Some(ref http) = self.http {
+                    http.set_application_id(ready.ready.application.id.0);
+                }
+            },
+            Event::Resumed(_) => {
+                info!("[Shard {:?}] Resumed", self.shard_info);
+
+                self.stage = ConnectionStage::Connected;
+                self.last_heartbeat_acknowledged = true;
+                self.heartbeat_instants = (Some(Instant::now()), None);
+            },
+            _ => {},
+        }
+
+        self.seq = seq;
+
+        None
+    }
+
+    #[instrument(skip(self))]
+    fn handle_heartbeat_event(&mut self, s: u64) -> ShardAction {
+        info!("[Shard {:?}] Received shard heartbeat", self.shard_info);
+
+        // Received seq is off -- attempt to resume.
+        if s > self.seq + 1 {
+            info!(
+                "[Shard {:?}] Received off sequence (them: {}; us: {}); resuming",
+                self.shard_info, s, self.seq
+            );
+
+            if self.stage == ConnectionStage::Handshake {
+                self.stage = ConnectionStage::Identifying;
+
+                return ShardAction::Identify;
+            }
+            warn!(
+                "[Shard {:?}] Heartbeat during non-Handshake; auto-reconnecting",
+                self.shard_info
+            );
+
+            return ShardAction::Reconnect(self.reconnection_type());
+        }
+
+        ShardAction::Heartbeat
+    }
+
+    #[instrument(skip(self))]
+    fn handle_gateway_closed(
+        &mut self,
+        data: &Option<CloseFrame<'static>>,
+    ) -> Result<Option<ShardAction>> {
+        let num = data.as_ref().map(|d| d.code.into());
+        let clean = num == Some(1000);
+
+        match num {
+            Some(close_codes::UNKNOWN_OPCODE) => {
+                warn!("[Shard {:?}] Sent invalid opcode.", self.shard_info);
+            },
+            Some(close_codes::DECODE_ERROR) => {
+                warn!("[Shard {:?}] Sent invalid message.", self.shard_info);
+            },
+            Some(close_codes::NOT_AUTHENTICATED) => {
+                warn!("[Shard {:?}] Sent no authentication.", self.shard_info);
+
+                return Err(Error::Gateway(GatewayError::NoAuthentication));
+            },
+            Some(close_codes::AUTHENTICATION_FAILED) => {
+                error!(
+                    "[Shard {:?}] Sent invalid authentication, please check the token.",
+                    self.shard_info
+                );
+
+                return Err(Error::Gateway(GatewayError::InvalidAuthentication));
+            },
+            Some(close_codes::ALREADY_AUTHENTICATED) => {
+                warn!("[Shard {:?}] Already authenticated.", self.shard_info);
+            },
+            Some(close_codes::INVALID_SEQUENCE) => {
+                warn!("[Shard {:?}] Sent invalid seq: {}.", self.shard_info, self.seq);
+
+                self.seq = 0;
+            },
+            Some(close_codes::RATE_LIMITED) => {
+                warn!("[Shard {:?}] Gateway ratelimited.", self.shard_info);
+            },
+            Some(close_codes::INVALID_SHARD) => {
+                warn!("[Shard {:?}] Sent invalid shard data.", self.shard_info);
+
+                return Err(Error::Gateway(GatewayError::InvalidShardData));
+            },
+            Some(close_codes::SHARDING_REQUIRED) => {
+                error!("[Shard {:?}] Shard has too many guilds.", self.shard_info);
+
+                return Err(Error::Gateway(GatewayError::OverloadedShard));
+            },
+            Some(4006 | close_codes::SESSION_TIMEOUT) => {
+                info!("[Shard {:?}] Invalid session.", self.shard_info);
+
+                self.session_id = None;
+            },
+            Some(close_codes::INVALID_GATEWAY_INTENTS) => {
+                error!("[Shard {:?}] Invalid gateway intents have been provided.", self.shard_info);
+
+                return Err(Error::Gateway(GatewayError::InvalidGatewayIntents));
+            },
+            Some(close_codes::DISALLOWED_GATEWAY_INTENTS) => {
+                error!(
+                    "[Shard {:?}] Disallowed gateway intents have been provided.",
+                    self.shard_info
+                );
+
+                return Err(Error::Gateway(GatewayError::DisallowedGatewayIntents));
+            },
+            Some(other) if !clean => {
+                warn!(
+                    "[Shard {:?}] Unknown unclean close {}: {:?}",
+                    self.shard_info,
+                    other,
+                    data.as_ref().map(|d| &d.reason),
+                );
+            },
+            _ => {},
+        }
+
+        let resume = num
+            .map_or(true, |x| x != close_codes::AUTHENTICATION_FAILED && self.session_id.is_some());
+
+        Ok(Some(if resume {
+            ShardAction::Reconnect(ReconnectType::Resume)
+        } else {
+            ShardAction::Reconnect(ReconnectType::Reidentify)
+        }))
+    }
+
+    /// Handles an event received from the gateway, see [`Shard::handle_event`].
+    #[instrument(skip(self))]
+    pub(crate) fn handle_event(
+        &mut self,
+        event: &Result<GatewayEvent>,
+    ) -> Result<Option<ShardAction>> {
+        match *event {
+            Ok(GatewayEvent::Dispatch(seq, ref event)) => {
+                Ok(self.handle_gateway_dispatch(seq, event))
+            },
+            Ok(GatewayEvent::Heartbeat(s)) => Ok(Some(self.handle_heartbeat_event(s))),
+            Ok(GatewayEvent::HeartbeatAck) => {
+                self.heartbeat_instants.1 = Some(Instant::now());
+                self.last_heartbeat_acknowledged = true;
+
+                trace!("[Shard {:?}] Received heartbeat ack", self.shard_info);
+
+                Ok(None)
+            },
+            Ok(GatewayEvent::Hello(interval)) => {
+                debug!("[Shard {:?}] Received a Hello; interval: {}", self.shard_info, interval);
+
+                if interval > 0 {
+                    self.heartbeat_interval = Some(interval);
+                }
+
+                if self.stage == ConnectionStage::Resuming {
+                    return Ok(None);
+                }
+
+                Ok(Some(if self.stage == ConnectionStage::Handshake {
+                    ShardAction::Identify
+                } else {
+                    debug!("[Shard {:?}] Received late Hello; autoreconnecting", self.shard_info);
+
+                    ShardAction::Reconnect(self.reconnection_type())
+                }))
+            },
+            Ok(GatewayEvent::InvalidateSession(resumable)) => {
+                info!("[Shard {:?}] Received session invalidation", self.shard_info,);
+
+                Ok(Some(if resumable {
+                    ShardAction::Reconnect(ReconnectType::Resume)
+                } else {
+                    ShardAction::Reconnect(ReconnectType::Reidentify)
+                }))
+            },
+            Ok(GatewayEvent::Reconnect) => Ok(Some(ShardAction::Reconnect(ReconnectType::Resume))),
+            Err(Error::Gateway(GatewayError::Closed(ref data))) => self.handle_gateway_closed(data),
+            Err(Error::Tungstenite(ref why)) => {
+                warn!("[Shard {:?}] Websocket error: {:?}", self.shard_info, why);
+                info!("[Shard {:?}] Will attempt to auto-reconnect", self.shard_info);
+
+                Ok(Some(ShardAction::Reconnect(self.reconnection_type())))
+            },
+            Err(ref why) => {
+                warn!("[Shard {:?}] Unhandled error: {:?}", self.shard_info, why);
+
+                Ok(None)
+            },
+        }
+    }
+}
+
+async fn connect(
+    base_url: &str,
+    compression: TransportCompression,
+    encoding: GatewayEncoding,
+) -> Result<WsStream> {
+    let mut url = format!("{}?v={}", base_url, constants::GATEWAY_VERSION);
+
+    if let Some(encoding) = encoding.query_param() {
+        url.push_str("&encoding=");
+        url.push_str(encoding);
+    }
+
+    if let Some(compress) = compression.query_param() {
+        url.push_str("&compress=");
+        url.push_str(compress);
+    }
+
+    let url = Url::parse(&url).map_err(|why| {
+        warn!("Error building gateway URL with base `{}`: {:?}", base_url, why);
 
-            Error::Gateway(GatewayError::BuildingUrl)
-        })?;
+        Error::Gateway(GatewayError::BuildingUrl)
+    })?;
 
     create_client(url).await
 }
@@ -31,6 +31,7 @@ pub trait WebSocketGatewayClientExt {
         shard_info: &[u64; 2],
         token: &str,
         intents: GatewayIntents,
+        compress: bool,
     ) -> Result<()>;
 
     async fn send_presence_update(
@@ -59,39 +60,12 @@ impl WebSocketGatewayClientExt for WsStream {
         filter: ChunkGuildFilter,
         nonce: Option<&str>,
     ) -> Result<()> {
-        debug!("[Shard {:?}] Requesting member chunks", shard_info);
-
-        let mut payload = json!({
-            "op": OpCode::GetGuildMembers.num(),
-            "d": {
-                "guild_id": guild_id.as_ref().0.to_string(),
-                "limit": limit.unwrap_or(0),
-                "nonce": nonce.unwrap_or(""),
-            },
-        });
-
-        match filter {
-            ChunkGuildFilter::None => payload["d"]["query"] = json!(""),
-            ChunkGuildFilter::Query(query) => payload["d"]["query"] = json!(query),
-            ChunkGuildFilter::UserIds(user_ids) => {
-                let ids = user_ids.iter().map(|x| x.0).collect::<Vec<u64>>();
-                payload["d"]["user_ids"] = json!(ids);
-            },
-        };
-
-        self.send_json(&payload).await.map_err(From::from)
+        self.send_json(&chunk_guild_payload(guild_id, shard_info, limit, filter, nonce)).await
     }
 
     #[instrument(skip(self))]
     async fn send_heartbeat(&mut self, shard_info: &[u64; 2], seq: Option<u64>) -> Result<()> {
-        trace!("[Shard {:?}] Sending heartbeat d: {:?}", shard_info, seq);
-
-        self.send_json(&json!({
-            "d": seq,
-            "op": OpCode::Heartbeat.num(),
-        }))
-        .await
-        .map_err(From::from)
+        self.send_json(&heartbeat_payload(shard_info, seq)).await
     }
 
     #[instrument(skip(self, token))]
@@ -100,26 +74,9 @@ impl WebSocketGatewayClientExt for WsStream {
         shard_info: &[u64; 2],
         token: &str,
         intents: GatewayIntents,
+        compress: bool,
     ) -> Result<()> {
-        debug!("[Shard {:?}] Identifying", shard_info);
-
-        self.send_json(&json!({
-            "op": OpCode::Identify.num(),
-            "d": {
-                "compress": true,
-                "large_threshold": constants::LARGE_THRESHOLD,
-                "shard": shard_info,
-                "token": token,
-                "intents": intents,
-                "v": constants::GATEWAY_VERSION,
-                "properties": {
-                    "$browser": "serenity",
-                    "$device": "serenity",
-                    "$os": consts::OS,
-                },
-            },
-        }))
-        .await
+        self.send_json(&identify_payload(shard_info, token, intents, compress)).await
     }
 
     #[instrument(skip(self))]
@@ -128,25 +85,7 @@ impl WebSocketGatewayClientExt for WsStream {
         shard_info: &[u64; 2],
         current_presence: &CurrentPresence,
     ) -> Result<()> {
-        let (activity, status) = current_presence;
-        let now = SystemTime::now();
-
-        debug!("[Shard {:?}] Sending presence update", shard_info);
-
-        self.send_json(&json!({
-            "op": OpCode::StatusUpdate.num(),
-            "d": {
-                "afk": false,
-                "since": now,
-                "status": status.name(),
-                "game": activity.as_ref().map(|x| json!({
-                    "name": x.name,
-                    "type": x.kind,
-                    "url": x.url,
-                })),
-            },
-        }))
-        .await
+        self.send_json(&presence_update_payload(shard_info, current_presence)).await
     }
 
     #[instrument(skip(self, token))]
@@ -157,17 +96,119 @@ impl WebSocketGatewayClientExt for WsStream {
         seq: u64,
         token: &str,
     ) -> Result<()> {
-        debug!("[Shard {:?}] Sending resume; seq: {}", shard_info, seq);
-
-        self.send_json(&json!({
-            "op": OpCode::Resume.num(),
-            "d": {
-                "session_id": session_id,
-                "seq": seq,
-                "token": token,
-            },
-        }))
-        .await
-        .map_err(From::from)
+        self.send_json(&resume_payload(shard_info, session_id, seq, token)).await
     }
 }
+
+// The payloads are built separately from sending them, so that a [`Shard`]
+// can send them in its [`GatewayEncoding`].
+//
+// [`Shard`]: super::Shard
+// [`GatewayEncoding`]: super::GatewayEncoding
+
+pub(crate) fn chunk_guild_payload(
+    guild_id: GuildId,
+    shard_info: &[u64; 2],
+    limit: Option<u16>,
+    filter: ChunkGuildFilter,
+    nonce: Option<&str>,
+) -> Value {
+    debug!("[Shard {:?}] Requesting member chunks", shard_info);
+
+    let mut payload = json!({
+        "op": OpCode::GetGuildMembers.num(),
+        "d": {
+            "guild_id": guild_id.as_ref().0.to_string(),
+            "limit": limit.unwrap_or(0),
+            "nonce": nonce.unwrap_or(""),
+        },
+    });
+
+    match filter {
+        ChunkGuildFilter::None => payload["d"]["query"] = json!(""),
+        ChunkGuildFilter::Query(query) => payload["d"]["query"] = json!(query),
+        ChunkGuildFilter::UserIds(user_ids) => {
+            let ids = user_ids.iter().map(|x| x.0).collect::<Vec<u64>>();
+            payload["d"]["user_ids"] = json!(ids);
+        },
+    }
+
+    payload
+}
+
+pub(crate) fn heartbeat_payload(shard_info: &[u64; 2], seq: Option<u64>) -> Value {
+    trace!("[Shard {:?}] Sending heartbeat d: {:?}", shard_info, seq);
+
+    json!({
+        "d": seq,
+        "op": OpCode::Heartbeat.num(),
+    })
+}
+
+pub(crate) fn identify_payload(
+    shard_info: &[u64; 2],
+    token: &str,
+    intents: GatewayIntents,
+    compress: bool,
+) -> Value {
+    debug!("[Shard {:?}] Identifying", shard_info);
+
+    json!({
+        "op": OpCode::Identify.num(),
+        "d": {
+            "compress": compress,
+            "large_threshold": constants::LARGE_THRESHOLD,
+            "shard": shard_info,
+            "token": token,
+            "intents": intents,
+            "v": constants::GATEWAY_VERSION,
+            "properties": {
+                "$browser": "serenity",
+                "$device": "serenity",
+                "$os": consts::OS,
+            },
+        },
+    })
+}
+
+pub(crate) fn presence_update_payload(
+    shard_info: &[u64; 2],
+    current_presence: &CurrentPresence,
+) -> Value {
+    let (activity, status) = current_presence;
+    let now = SystemTime::now();
+
+    debug!("[Shard {:?}] Sending presence update", shard_info);
+
+    json!({
+        "op": OpCode::StatusUpdate.num(),
+        "d": {
+            "afk": false,
+            "since": now,
+            "status": status.name(),
+            "game": activity.as_ref().map(|x| json!({
+                "name": x.name,
+                "type": x.kind,
+                "url": x.url,
+            })),
+        },
+    })
+}
+
+pub(crate) fn resume_payload(
+    shard_info: &[u64; 2],
+    session_id: &str,
+    seq: u64,
+    token: &str,
+) -> Value {
+    debug!("[Shard {:?}] Sending resume; seq: {}", shard_info, seq);
+
+    json!({
+        "op": OpCode::Resume.num(),
+        "d": {
+            "session_id": session_id,
+            "seq": seq,
+            "token": token,
+        },
+    })
+}
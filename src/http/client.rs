@@ -3,20 +3,28 @@
 use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt;
+use std::future::Future;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+#[cfg(feature = "oauth")]
+use reqwest::header::AUTHORIZATION;
 use reqwest::header::{HeaderMap as Headers, HeaderValue, CONTENT_TYPE};
 use reqwest::{Client, ClientBuilder, Response as ReqwestResponse, StatusCode, Url};
 use serde::de::DeserializeOwned;
 use tracing::{debug, instrument, trace};
+#[cfg(feature = "oauth")]
+use url::form_urlencoded;
 
 use super::multipart::Multipart;
+use super::observer::{self, HttpObserver};
 use super::ratelimiting::{RatelimitedRequest, Ratelimiter};
-use super::request::Request;
+use super::request::{Request, RequestOptions};
+use super::retry::{RetryInfo, RetryPolicy};
 use super::routing::RouteInfo;
+use super::transport::{HttpTransport, TransportRequest};
 use super::typing::Typing;
 use super::{AttachmentType, GuildPagination, HttpError, UserPagination};
 use crate::internal::prelude::*;
@@ -49,6 +57,9 @@ pub struct HttpBuilder {
     client: Option<Client>,
     ratelimiter: Option<Ratelimiter>,
     ratelimiter_disabled: bool,
+    retry_policy: RetryPolicy,
+    transport: Option<Arc<dyn HttpTransport>>,
+    observer: Option<Arc<dyn HttpObserver>>,
     token: String,
     proxy: Option<Url>,
     application_id: Option<u64>,
@@ -62,6 +73,9 @@ impl HttpBuilder {
             client: None,
             ratelimiter: None,
             ratelimiter_disabled: false,
+            retry_policy: RetryPolicy::default(),
+            transport: None,
+            observer: None,
             token: parse_token(token),
             proxy: None,
             application_id: None,
@@ -118,6 +132,38 @@ impl HttpBuilder {
         self
     }
 
+    /// Sets the policy for retrying requests that failed for transient
+    /// reasons. If one isn't provided, [`RetryPolicy::default`] will be used.
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+
+        self
+    }
+
+    /// Sets the transport requests are sent through, which is used by both
+    /// the `Http` client and its ratelimiter. If one isn't provided, requests
+    /// are sent by the [`reqwest::Client`].
+    ///
+    /// This is mainly intended for testing with a [`MockTransport`].
+    ///
+    /// [`MockTransport`]: super::transport::MockTransport
+    #[must_use]
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+
+        self
+    }
+
+    /// Sets an observer to be notified before and after every request, for
+    /// example to collect metrics.
+    #[must_use]
+    pub fn observer(mut self, observer: Arc<dyn HttpObserver>) -> Self {
+        self.observer = Some(observer);
+
+        self
+    }
+
     /// Sets the proxy that Discord HTTP API requests will be passed to. This is
     /// mainly intended for something like [`twilight-http-proxy`] where
     /// multiple processes can make API requests while sharing a single
@@ -153,17 +199,33 @@ impl HttpBuilder {
             builder.build().expect("Cannot build reqwest::Client")
         });
 
-        let ratelimiter = self.ratelimiter.unwrap_or_else(|| {
+        let mut ratelimiter = self.ratelimiter.unwrap_or_else(|| {
             let client = client.clone();
             Ratelimiter::new(client, token.to_string())
         });
 
+        let transport = match self.transport {
+            Some(transport) => {
+                ratelimiter.set_transport(Arc::clone(&transport));
+                transport
+            },
+            None => Arc::new(client.clone()),
+        };
+
+        if let Some(observer) = &self.observer {
+            ratelimiter.set_observer(Arc::clone(observer));
+        }
+
         let ratelimiter_disabled = self.ratelimiter_disabled;
 
         Http {
             client,
+            transport,
+            observer: self.observer,
             ratelimiter,
             ratelimiter_disabled,
+            retry_policy: self.retry_policy,
+            retry_callback: Box::new(|_| {}),
             proxy: self.proxy,
             token,
             application_id,
@@ -171,7 +233,7 @@ impl HttpBuilder {
     }
 }
 
-fn parse_token(token: impl AsRef<str>) -> String {
+pub(super) fn parse_token(token: impl AsRef<str>) -> String {
     let token = token.as_ref().trim();
 
     if token.starts_with("Bot ") || token.starts_with("Bearer ") {
@@ -181,6 +243,21 @@ fn parse_token(token: impl AsRef<str>) -> String {
     }
 }
 
+/// The headers of OAuth2 token requests, authenticating as the application.
+#[cfg(feature = "oauth")]
+fn oauth2_headers(client_id: u64, client_secret: &str) -> Result<Headers> {
+    let credentials = base64::encode(format!("{}:{}", client_id, client_secret));
+    let mut authorization = HeaderValue::from_str(&format!("Basic {}", credentials))
+        .map_err(HttpError::InvalidHeader)?;
+    authorization.set_sensitive(true);
+
+    let mut headers = Headers::new();
+    headers.insert(AUTHORIZATION, authorization);
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+
+    Ok(headers)
+}
+
 fn reason_into_header(reason: &str) -> Headers {
     let mut headers = Headers::new();
 
@@ -198,8 +275,12 @@ fn reason_into_header(reason: &str) -> Headers {
 /// Error kind will be either [`Error::Http`] or [`Error::Json`].
 pub struct Http {
     pub(crate) client: Client,
+    transport: Arc<dyn HttpTransport>,
+    observer: Option<Arc<dyn HttpObserver>>,
     pub ratelimiter: Ratelimiter,
     pub ratelimiter_disabled: bool,
+    pub retry_policy: RetryPolicy,
+    retry_callback: Box<dyn Fn(RetryInfo) + Send + Sync>,
     pub proxy: Option<Url>,
     pub token: String,
     application_id: AtomicU64,
@@ -211,6 +292,7 @@ impl fmt::Debug for Http {
             .field("client", &self.client)
             .field("ratelimiter", &self.ratelimiter)
             .field("ratelimiter_disabled", &self.ratelimiter_disabled)
+            .field("retry_policy", &self.retry_policy)
             .field("proxy", &self.proxy)
             .finish()
     }
@@ -227,9 +309,13 @@ impl Http {
         let token = parse_token(token);
 
         Http {
+            transport: Arc::new(client.clone()),
+            observer: None,
             client,
             ratelimiter: Ratelimiter::new(client2, token.to_string()),
             ratelimiter_disabled: false,
+            retry_policy: RetryPolicy::default(),
+            retry_callback: Box::new(|_| {}),
             proxy: None,
             token,
             application_id: AtomicU64::new(0),
@@ -255,6 +341,19 @@ impl Http {
         }
     }
 
+    /// Sets a callback to be called before a failed request is retried
+    /// according to the [`RetryPolicy`].
+    pub fn set_retry_callback(&mut self, retry_callback: Box<dyn Fn(RetryInfo) + Send + Sync>) {
+        self.retry_callback = retry_callback;
+    }
+
+    /// Sets an observer to be notified before and after every request, both
+    /// by this client and its ratelimiter.
+    pub fn set_observer(&mut self, observer: Arc<dyn HttpObserver>) {
+        self.ratelimiter.set_observer(Arc::clone(&observer));
+        self.observer = Some(observer);
+    }
+
     fn try_application_id(&self) -> Result<u64> {
         self.application_id().ok_or_else(|| HttpError::ApplicationIdMissing.into())
     }
@@ -746,6 +845,31 @@ impl Http {
         .await
     }
 
+    /// Requests an OAuth2 access token, authenticating as the application.
+    ///
+    /// The `params` are the form fields of the grant, such as `grant_type`
+    /// and `code`. Prefer [`OAuth2Client`], which knows the fields of each
+    /// grant.
+    ///
+    /// [`OAuth2Client`]: super::oauth::OAuth2Client
+    #[cfg(feature = "oauth")]
+    pub async fn create_oauth2_token(
+        &self,
+        client_id: u64,
+        client_secret: &str,
+        params: &[(&str, &str)],
+    ) -> Result<AccessToken> {
+        let body = form_urlencoded::Serializer::new(String::new()).extend_pairs(params).finish();
+
+        self.fire(Request {
+            body: Some(body.as_bytes()),
+            multipart: None,
+            headers: Some(oauth2_headers(client_id, client_secret)?),
+            route: RouteInfo::CreateOAuth2Token,
+        })
+        .await
+    }
+
     /// Creates a permission override for a member or a role in a channel.
     pub async fn create_permission(
         &self,
@@ -2381,6 +2505,36 @@ impl Http {
         .await
     }
 
+    /// Gets a page of the users that are banned in specific guild, sorted by
+    /// their Id.
+    ///
+    /// **Note**: The maximum amount of bans per page is 1000.
+    pub async fn get_bans_paginated(
+        &self,
+        guild_id: u64,
+        target: Option<&UserPagination>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Ban>> {
+        let (after, before) = match target {
+            None => (None, None),
+            Some(UserPagination::After(id)) => (Some(id.0), None),
+            Some(UserPagination::Before(id)) => (None, Some(id.0)),
+        };
+
+        self.fire(Request {
+            body: None,
+            multipart: None,
+            headers: None,
+            route: RouteInfo::GetBansOptioned {
+                guild_id,
+                after,
+                before,
+                limit,
+            },
+        })
+        .await
+    }
+
     /// Gets all audit logs in a specific guild.
     pub async fn get_audit_logs(
         &self,
@@ -2562,6 +2716,27 @@ impl Http {
         .await
     }
 
+    /// Gets the archived public threads of a channel which were archived
+    /// before the given timestamp, most recently archived first.
+    pub async fn get_channel_archived_public_threads_by_timestamp(
+        &self,
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    ) -> Result<ThreadsData> {
+        self.fire(Request {
+            body: None,
+            multipart: None,
+            headers: None,
+            route: RouteInfo::GetChannelArchivedPublicThreadsByTimestamp {
+                channel_id,
+                before,
+                limit,
+            },
+        })
+        .await
+    }
+
     /// Gets all archived private threads from a channel.
     pub async fn get_channel_archived_private_threads(
         &self,
@@ -2582,6 +2757,27 @@ impl Http {
         .await
     }
 
+    /// Gets the archived private threads of a channel which were archived
+    /// before the given timestamp, most recently archived first.
+    pub async fn get_channel_archived_private_threads_by_timestamp(
+        &self,
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    ) -> Result<ThreadsData> {
+        self.fire(Request {
+            body: None,
+            multipart: None,
+            headers: None,
+            route: RouteInfo::GetChannelArchivedPrivateThreadsByTimestamp {
+                channel_id,
+                before,
+                limit,
+            },
+        })
+        .await
+    }
+
     /// Gets all archived private threads joined from a channel.
     pub async fn get_channel_joined_archived_private_threads(
         &self,
@@ -3864,6 +4060,27 @@ impl Http {
         .await
     }
 
+    /// Revokes an OAuth2 access or refresh token, authenticating as the
+    /// application.
+    #[cfg(feature = "oauth")]
+    pub async fn revoke_oauth2_token(
+        &self,
+        client_id: u64,
+        client_secret: &str,
+        token: &str,
+    ) -> Result<()> {
+        let body =
+            form_urlencoded::Serializer::new(String::new()).append_pair("token", token).finish();
+
+        self.wind(200, Request {
+            body: Some(body.as_bytes()),
+            multipart: None,
+            headers: Some(oauth2_headers(client_id, client_secret)?),
+            route: RouteInfo::RevokeOAuth2Token,
+        })
+        .await
+    }
+
     /// Returns a list of [`Member`]s in a [`Guild`] whose username or nickname
     /// starts with a provided string.
     pub async fn search_guild_members(
@@ -4039,7 +4256,8 @@ impl Http {
         response.json::<T>().await.map_err(From::from)
     }
 
-    /// Performs a request, ratelimiting it if necessary.
+    /// Performs a request, ratelimiting it if necessary and retrying it
+    /// according to the [`RetryPolicy`] if it failed for a transient reason.
     ///
     /// Returns the raw reqwest Response. Use [`Self::fire`] to deserialize the response
     /// into some type.
@@ -4075,14 +4293,68 @@ impl Http {
     /// # }
     /// ```
     #[instrument]
-    pub async fn request(&self, mut req: Request<'_>) -> Result<ReqwestResponse> {
-        let response = if self.ratelimiter_disabled {
-            let request =
-                req.build(&self.client, &self.token, self.proxy.as_ref()).await?.build()?;
-            self.client.execute(request).await?
-        } else {
-            let ratelimiting_req = RatelimitedRequest::from(req);
-            self.ratelimiter.perform(ratelimiting_req).await?
+    pub async fn request(&self, req: Request<'_>) -> Result<ReqwestResponse> {
+        let options = RequestOptions::current();
+
+        match options.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.request_with(req, &options))
+                .await
+                .map_err(|_| HttpError::Timeout(timeout))?,
+            None => self.request_with(req, &options).await,
+        }
+    }
+
+    /// Runs the future, applying the options to every request it makes through
+    /// this or any other [`Http`] client.
+    ///
+    /// Requests made by tasks the future spawns are not affected. See
+    /// [`RequestOptions`] for details and an example.
+    pub async fn with_options<'a, F, Fut>(&'a self, options: RequestOptions, f: F) -> Fut::Output
+    where
+        F: FnOnce(&'a Self) -> Fut,
+        Fut: Future,
+    {
+        options.scope(f(self)).await
+    }
+
+    async fn request_with(
+        &self,
+        mut req: Request<'_>,
+        options: &RequestOptions,
+    ) -> Result<ReqwestResponse> {
+        if !options.headers.is_empty() {
+            req.headers.get_or_insert_with(Headers::new).extend(options.headers.clone());
+        }
+
+        let retry_policy = options.retry_policy.as_ref().unwrap_or(&self.retry_policy);
+        let ratelimiter_disabled =
+            options.ratelimiter_disabled.unwrap_or(self.ratelimiter_disabled);
+        let token = options.token.as_deref();
+        let mut attempt = 1;
+
+        let response = loop {
+            let result = self.request_once(req.clone(), ratelimiter_disabled, attempt, token).await;
+
+            let (method, _, path) = req.route.deconstruct();
+            let outcome = result.as_ref().map(ReqwestResponse::status);
+
+            if !retry_policy.should_retry(attempt, method, outcome) {
+                break result?;
+            }
+
+            let delay = retry_policy.delay(attempt);
+
+            debug!("Retrying request to {} after attempt {} in {:?}", path, attempt, delay);
+            (self.retry_callback)(RetryInfo {
+                attempt,
+                delay,
+                method,
+                path: path.into_owned(),
+                status: result.ok().map(|response| response.status()),
+            });
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         };
 
         if response.status().is_success() {
@@ -4092,6 +4364,38 @@ impl Http {
         }
     }
 
+    async fn request_once(
+        &self,
+        mut req: Request<'_>,
+        ratelimiter_disabled: bool,
+        attempt: u32,
+        token: Option<&str>,
+    ) -> Result<ReqwestResponse> {
+        if ratelimiter_disabled {
+            let token = token.unwrap_or(&self.token);
+            let request = req.build(&self.client, token, self.proxy.as_ref()).await?.build()?;
+
+            let (method, route, _) = req.route.deconstruct();
+
+            observer::execute(
+                &*self.transport,
+                self.observer.as_deref(),
+                TransportRequest {
+                    method,
+                    route,
+                    request,
+                },
+                attempt,
+                0,
+            )
+            .await
+        } else {
+            let req = RatelimitedRequest::from(req).attempt(attempt).token(token.map(Into::into));
+
+            self.ratelimiter.perform(req).await
+        }
+    }
+
     /// Performs a request and then verifies that the response status code is equal
     /// to the expected value.
     ///
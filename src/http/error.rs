@@ -1,12 +1,596 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::error::Error as StdError;
 use std::fmt;
+use std::time::Duration;
 
 use reqwest::header::InvalidHeaderValue;
 use reqwest::{Error as ReqwestError, Response, StatusCode, Url};
+use serde::de::value::{Error as ValueError, U64Deserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
 use url::ParseError as UrlError;
 
 use crate::http::utils::deserialize_errors;
 
+/// A [JSON error code] returned by Discord along with an unsuccessful
+/// response, describing why the request failed.
+///
+/// [JSON error code]: https://discord.com/developers/docs/topics/opcodes-and-status-codes#json-json-error-codes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum JsonErrorCode {
+    /// General error, such as a malformed request.
+    GeneralError = 0,
+    /// Unknown account.
+    UnknownAccount = 10001,
+    /// Unknown application.
+    UnknownApplication = 10002,
+    /// Unknown channel.
+    UnknownChannel = 10003,
+    /// Unknown guild.
+    UnknownGuild = 10004,
+    /// Unknown integration.
+    UnknownIntegration = 10005,
+    /// Unknown invite.
+    UnknownInvite = 10006,
+    /// Unknown member.
+    UnknownMember = 10007,
+    /// Unknown message.
+    UnknownMessage = 10008,
+    /// Unknown permission overwrite.
+    UnknownPermissionOverwrite = 10009,
+    /// Unknown provider.
+    UnknownProvider = 10010,
+    /// Unknown role.
+    UnknownRole = 10011,
+    /// Unknown token.
+    UnknownToken = 10012,
+    /// Unknown user.
+    UnknownUser = 10013,
+    /// Unknown emoji.
+    UnknownEmoji = 10014,
+    /// Unknown webhook.
+    UnknownWebhook = 10015,
+    /// Unknown webhook service.
+    UnknownWebhookService = 10016,
+    /// Unknown session.
+    UnknownSession = 10020,
+    /// Unknown ban.
+    UnknownBan = 10026,
+    /// Unknown SKU.
+    UnknownSku = 10027,
+    /// Unknown store listing.
+    UnknownStoreListing = 10028,
+    /// Unknown entitlement.
+    UnknownEntitlement = 10029,
+    /// Unknown build.
+    UnknownBuild = 10030,
+    /// Unknown lobby.
+    UnknownLobby = 10031,
+    /// Unknown branch.
+    UnknownBranch = 10032,
+    /// Unknown store directory layout.
+    UnknownStoreDirectoryLayout = 10033,
+    /// Unknown redistributable.
+    UnknownRedistributable = 10036,
+    /// Unknown gift code.
+    UnknownGiftCode = 10038,
+    /// Unknown stream.
+    UnknownStream = 10049,
+    /// Unknown premium server subscribe cooldown.
+    UnknownPremiumServerSubscribeCooldown = 10050,
+    /// Unknown guild template.
+    UnknownGuildTemplate = 10057,
+    /// Unknown discoverable server category.
+    UnknownDiscoverableServerCategory = 10059,
+    /// Unknown sticker.
+    UnknownSticker = 10060,
+    /// Unknown interaction.
+    UnknownInteraction = 10062,
+    /// Unknown application command.
+    UnknownApplicationCommand = 10063,
+    /// Unknown voice state.
+    UnknownVoiceState = 10065,
+    /// Unknown application command permissions.
+    UnknownApplicationCommandPermissions = 10066,
+    /// Unknown stage instance.
+    UnknownStageInstance = 10067,
+    /// Unknown guild member verification form.
+    UnknownGuildMemberVerificationForm = 10068,
+    /// Unknown guild welcome screen.
+    UnknownGuildWelcomeScreen = 10069,
+    /// Unknown guild scheduled event.
+    UnknownGuildScheduledEvent = 10070,
+    /// Unknown guild scheduled event user.
+    UnknownGuildScheduledEventUser = 10071,
+    /// Unknown tag.
+    UnknownTag = 10087,
+    /// Bots cannot use this endpoint.
+    BotsCannotUseEndpoint = 20001,
+    /// Only bots can use this endpoint.
+    OnlyBotsCanUseEndpoint = 20002,
+    /// Explicit content cannot be sent to the desired recipient(s).
+    ExplicitContentCannotBeSent = 20009,
+    /// You are not authorized to perform this action on this application.
+    NotAuthorizedForApplication = 20012,
+    /// This action cannot be performed due to slowmode rate limit.
+    SlowmodeRateLimit = 20016,
+    /// Only the owner of this account can perform this action.
+    OnlyAccountOwner = 20018,
+    /// This message cannot be edited due to announcement rate limits.
+    AnnouncementRateLimit = 20022,
+    /// Under minimum age.
+    UnderMinimumAge = 20024,
+    /// The channel you are writing has hit the write rate limit.
+    ChannelWriteRateLimit = 20028,
+    /// The write action you are performing on the server has hit the write rate limit.
+    ServerWriteRateLimit = 20029,
+    /// Your stage topic, server name, server description, or channel names contain words that are not allowed.
+    WordsNotAllowed = 20031,
+    /// Guild premium subscription level too low.
+    PremiumSubscriptionLevelTooLow = 20035,
+    /// Maximum number of guilds reached (100).
+    MaximumGuilds = 30001,
+    /// Maximum number of friends reached (1000).
+    MaximumFriends = 30002,
+    /// Maximum number of pins reached for the channel (50).
+    MaximumPins = 30003,
+    /// Maximum number of recipients reached (10).
+    MaximumRecipients = 30004,
+    /// Maximum number of guild roles reached (250).
+    MaximumRoles = 30005,
+    /// Maximum number of webhooks reached (15).
+    MaximumWebhooks = 30007,
+    /// Maximum number of emojis reached.
+    MaximumEmojis = 30008,
+    /// Maximum number of reactions reached (20).
+    MaximumReactions = 30010,
+    /// Maximum number of group DMs reached (10).
+    MaximumGroupDms = 30011,
+    /// Maximum number of guild channels reached (500).
+    MaximumChannels = 30013,
+    /// Maximum number of attachments in a message reached (10).
+    MaximumAttachments = 30015,
+    /// Maximum number of invites reached (1000).
+    MaximumInvites = 30016,
+    /// Maximum number of animated emojis reached.
+    MaximumAnimatedEmojis = 30018,
+    /// Maximum number of server members reached.
+    MaximumMembers = 30019,
+    /// Maximum number of server categories has been reached (5).
+    MaximumCategories = 30030,
+    /// Guild already has a template.
+    GuildAlreadyHasTemplate = 30031,
+    /// Maximum number of application commands reached.
+    MaximumApplicationCommands = 30032,
+    /// Maximum number of thread participants has been reached (1000).
+    MaximumThreadParticipants = 30033,
+    /// Maximum number of daily application command creates has been reached (200).
+    MaximumDailyApplicationCommandCreates = 30034,
+    /// Maximum number of bans for non-guild members have been exceeded.
+    MaximumNonMemberBans = 30035,
+    /// Maximum number of bans fetches has been reached.
+    MaximumBanFetches = 30037,
+    /// Maximum number of uncompleted guild scheduled events reached (100).
+    MaximumUncompletedScheduledEvents = 30038,
+    /// Maximum number of stickers reached.
+    MaximumStickers = 30039,
+    /// Maximum number of prune requests has been reached.
+    MaximumPruneRequests = 30040,
+    /// Maximum number of guild widget settings updates has been reached.
+    MaximumWidgetSettingsUpdates = 30042,
+    /// Maximum number of edits to messages older than 1 hour reached.
+    MaximumOldMessageEdits = 30046,
+    /// Maximum number of pinned threads in a forum channel has been reached.
+    MaximumPinnedForumThreads = 30047,
+    /// Maximum number of tags in a forum channel has been reached.
+    MaximumForumTags = 30048,
+    /// Bitrate is too high for channel of this type.
+    BitrateTooHigh = 30052,
+    /// Unauthorized. Provide a valid token and try again.
+    Unauthorized = 40001,
+    /// You need to verify your account in order to perform this action.
+    AccountVerificationRequired = 40002,
+    /// You are opening direct messages too fast.
+    OpeningDmsTooFast = 40003,
+    /// Send messages has been temporarily disabled.
+    SendMessagesDisabled = 40004,
+    /// Request entity too large.
+    RequestEntityTooLarge = 40005,
+    /// This feature has been temporarily disabled server-side.
+    FeatureDisabled = 40006,
+    /// The user is banned from this guild.
+    UserBanned = 40007,
+    /// Connection has been revoked.
+    ConnectionRevoked = 40012,
+    /// Target user is not connected to voice.
+    TargetUserNotInVoice = 40032,
+    /// This message has already been crossposted.
+    MessageAlreadyCrossposted = 40033,
+    /// An application command with that name already exists.
+    ApplicationCommandNameExists = 40041,
+    /// Application interaction failed to send.
+    InteractionFailedToSend = 40043,
+    /// Cannot send a message in a forum channel.
+    CannotSendInForumChannel = 40058,
+    /// Interaction has already been acknowledged.
+    InteractionAlreadyAcknowledged = 40060,
+    /// Tag names must be unique.
+    TagNamesMustBeUnique = 40061,
+    /// Missing access.
+    MissingAccess = 50001,
+    /// Invalid account type.
+    InvalidAccountType = 50002,
+    /// Cannot execute action on a DM channel.
+    CannotExecuteOnDmChannel = 50003,
+    /// Guild widget disabled.
+    GuildWidgetDisabled = 50004,
+    /// Cannot edit a message authored by another user.
+    CannotEditOtherUsersMessage = 50005,
+    /// Cannot send an empty message.
+    CannotSendEmptyMessage = 50006,
+    /// Cannot send messages to this user.
+    CannotSendMessagesToUser = 50007,
+    /// Cannot send messages in a non-text channel.
+    CannotSendMessagesInNonTextChannel = 50008,
+    /// Channel verification level is too high for you to gain access.
+    ChannelVerificationLevelTooHigh = 50009,
+    /// OAuth2 application does not have a bot.
+    OAuth2ApplicationHasNoBot = 50010,
+    /// OAuth2 application limit reached.
+    OAuth2ApplicationLimitReached = 50011,
+    /// Invalid OAuth2 state.
+    InvalidOAuth2State = 50012,
+    /// You lack permissions to perform that action.
+    MissingPermissions = 50013,
+    /// Invalid authentication token provided.
+    InvalidAuthenticationToken = 50014,
+    /// Note was too long.
+    NoteTooLong = 50015,
+    /// Provided too few or too many messages to delete.
+    InvalidBulkDeleteCount = 50016,
+    /// A message can only be pinned to the channel it was sent in.
+    PinnedInOtherChannel = 50019,
+    /// Invite code was either invalid or taken.
+    InvalidInviteCode = 50020,
+    /// Cannot execute action on a system message.
+    CannotExecuteOnSystemMessage = 50021,
+    /// Cannot execute action on this channel type.
+    CannotExecuteOnChannelType = 50024,
+    /// Invalid OAuth2 access token provided.
+    InvalidOAuth2AccessToken = 50025,
+    /// Missing required OAuth2 scope.
+    MissingOAuth2Scope = 50026,
+    /// Invalid webhook token provided.
+    InvalidWebhookToken = 50027,
+    /// Invalid role.
+    InvalidRole = 50028,
+    /// Invalid recipient(s).
+    InvalidRecipients = 50033,
+    /// A message provided was too old to bulk delete.
+    MessageTooOldToBulkDelete = 50034,
+    /// Invalid form body, or invalid `Content-Type` provided.
+    InvalidFormBody = 50035,
+    /// An invite was accepted to a guild the application's bot is not in.
+    InviteAcceptedToGuildWithoutBot = 50036,
+    /// Invalid API version provided.
+    InvalidApiVersion = 50041,
+    /// File uploaded exceeds the maximum size.
+    FileTooLarge = 50045,
+    /// Invalid file uploaded.
+    InvalidFile = 50046,
+    /// Cannot self-redeem this gift.
+    CannotSelfRedeemGift = 50054,
+    /// Invalid guild.
+    InvalidGuild = 50055,
+    /// Invalid message type.
+    InvalidMessageType = 50068,
+    /// Payment source required to redeem gift.
+    PaymentSourceRequired = 50070,
+    /// Cannot delete a channel required for Community guilds.
+    CannotDeleteCommunityChannel = 50074,
+    /// Invalid sticker sent.
+    InvalidSticker = 50081,
+    /// Tried to perform an operation on an archived thread.
+    ThreadArchived = 50083,
+    /// Invalid thread notification settings.
+    InvalidThreadNotificationSettings = 50084,
+    /// `before` value is earlier than the thread creation date.
+    BeforeEarlierThanThreadCreation = 50085,
+    /// Community server channels must be text channels.
+    CommunityChannelsMustBeText = 50086,
+    /// This server is not available in your location.
+    ServerNotAvailableInLocation = 50095,
+    /// This server needs monetization enabled in order to perform this action.
+    MonetizationRequired = 50097,
+    /// This server needs more boosts to perform this action.
+    MoreBoostsRequired = 50101,
+    /// The request body contains invalid JSON.
+    InvalidJson = 50109,
+    /// Ownership cannot be transferred to a bot user.
+    CannotTransferOwnershipToBot = 50132,
+    /// Failed to resize asset below the maximum size.
+    AssetResizeFailed = 50138,
+    /// Uploaded file not found.
+    UploadedFileNotFound = 50146,
+    /// You do not have permission to send this sticker.
+    MissingStickerPermission = 50600,
+    /// Two factor is required for this operation.
+    TwoFactorRequired = 60003,
+    /// No users with DiscordTag exist.
+    NoUsersWithTag = 80004,
+    /// Reaction was blocked.
+    ReactionBlocked = 90001,
+    /// API resource is currently overloaded.
+    ResourceOverloaded = 130000,
+    /// The stage is already open.
+    StageAlreadyOpen = 150006,
+    /// Cannot reply without permission to read message history.
+    CannotReplyWithoutReadMessageHistory = 160002,
+    /// A thread has already been created for this message.
+    ThreadAlreadyCreated = 160004,
+    /// Thread is locked.
+    ThreadLocked = 160005,
+    /// Maximum number of active threads reached.
+    MaximumActiveThreads = 160006,
+    /// Maximum number of active announcement threads reached.
+    MaximumActiveAnnouncementThreads = 160007,
+    /// Invalid JSON for uploaded Lottie file.
+    InvalidLottieJson = 170001,
+    /// Uploaded Lotties cannot contain rasterized images such as PNG or JPEG.
+    LottieContainsRasterizedImages = 170002,
+    /// Sticker maximum framerate exceeded.
+    StickerFramerateExceeded = 170003,
+    /// Sticker frame count exceeds maximum of 1000 frames.
+    StickerFrameCountExceeded = 170004,
+    /// Lottie animation maximum dimensions exceeded.
+    LottieDimensionsExceeded = 170005,
+    /// Sticker frame rate is either too small or too large.
+    InvalidStickerFrameRate = 170006,
+    /// Sticker animation duration exceeds maximum of 5 seconds.
+    StickerDurationExceeded = 170007,
+    /// Cannot update a finished event.
+    CannotUpdateFinishedEvent = 180000,
+    /// Failed to create stage needed for stage event.
+    FailedToCreateStageForEvent = 180002,
+    /// Message was blocked by automatic moderation.
+    MessageBlockedByAutoModeration = 200000,
+    /// Title was blocked by automatic moderation.
+    TitleBlockedByAutoModeration = 200001,
+    /// Webhooks posted to forum channels must have a `thread_name` or `thread_id`.
+    ForumWebhookMissingThread = 220001,
+    /// Webhooks posted to forum channels cannot have both a `thread_name` and `thread_id`.
+    ForumWebhookThreadNameAndId = 220002,
+    /// Webhooks can only create threads in forum channels.
+    WebhookThreadsOnlyInForums = 220003,
+    /// Webhook services cannot be used in forum channels.
+    WebhookServicesInForum = 220004,
+    /// Message blocked by harmful links filter.
+    MessageBlockedByHarmfulLinks = 240000,
+    /// A code which is not known to the library.
+    Unknown = !0,
+}
+
+enum_number!(JsonErrorCode {
+    GeneralError,
+    UnknownAccount,
+    UnknownApplication,
+    UnknownChannel,
+    UnknownGuild,
+    UnknownIntegration,
+    UnknownInvite,
+    UnknownMember,
+    UnknownMessage,
+    UnknownPermissionOverwrite,
+    UnknownProvider,
+    UnknownRole,
+    UnknownToken,
+    UnknownUser,
+    UnknownEmoji,
+    UnknownWebhook,
+    UnknownWebhookService,
+    UnknownSession,
+    UnknownBan,
+    UnknownSku,
+    UnknownStoreListing,
+    UnknownEntitlement,
+    UnknownBuild,
+    UnknownLobby,
+    UnknownBranch,
+    UnknownStoreDirectoryLayout,
+    UnknownRedistributable,
+    UnknownGiftCode,
+    UnknownStream,
+    UnknownPremiumServerSubscribeCooldown,
+    UnknownGuildTemplate,
+    UnknownDiscoverableServerCategory,
+    UnknownSticker,
+    UnknownInteraction,
+    UnknownApplicationCommand,
+    UnknownVoiceState,
+    UnknownApplicationCommandPermissions,
+    UnknownStageInstance,
+    UnknownGuildMemberVerificationForm,
+    UnknownGuildWelcomeScreen,
+    UnknownGuildScheduledEvent,
+    UnknownGuildScheduledEventUser,
+    UnknownTag,
+    BotsCannotUseEndpoint,
+    OnlyBotsCanUseEndpoint,
+    ExplicitContentCannotBeSent,
+    NotAuthorizedForApplication,
+    SlowmodeRateLimit,
+    OnlyAccountOwner,
+    AnnouncementRateLimit,
+    UnderMinimumAge,
+    ChannelWriteRateLimit,
+    ServerWriteRateLimit,
+    WordsNotAllowed,
+    PremiumSubscriptionLevelTooLow,
+    MaximumGuilds,
+    MaximumFriends,
+    MaximumPins,
+    MaximumRecipients,
+    MaximumRoles,
+    MaximumWebhooks,
+    MaximumEmojis,
+    MaximumReactions,
+    MaximumGroupDms,
+    MaximumChannels,
+    MaximumAttachments,
+    MaximumInvites,
+    MaximumAnimatedEmojis,
+    MaximumMembers,
+    MaximumCategories,
+    GuildAlreadyHasTemplate,
+    MaximumApplicationCommands,
+    MaximumThreadParticipants,
+    MaximumDailyApplicationCommandCreates,
+    MaximumNonMemberBans,
+    MaximumBanFetches,
+    MaximumUncompletedScheduledEvents,
+    MaximumStickers,
+    MaximumPruneRequests,
+    MaximumWidgetSettingsUpdates,
+    MaximumOldMessageEdits,
+    MaximumPinnedForumThreads,
+    MaximumForumTags,
+    BitrateTooHigh,
+    Unauthorized,
+    AccountVerificationRequired,
+    OpeningDmsTooFast,
+    SendMessagesDisabled,
+    RequestEntityTooLarge,
+    FeatureDisabled,
+    UserBanned,
+    ConnectionRevoked,
+    TargetUserNotInVoice,
+    MessageAlreadyCrossposted,
+    ApplicationCommandNameExists,
+    InteractionFailedToSend,
+    CannotSendInForumChannel,
+    InteractionAlreadyAcknowledged,
+    TagNamesMustBeUnique,
+    MissingAccess,
+    InvalidAccountType,
+    CannotExecuteOnDmChannel,
+    GuildWidgetDisabled,
+    CannotEditOtherUsersMessage,
+    CannotSendEmptyMessage,
+    CannotSendMessagesToUser,
+    CannotSendMessagesInNonTextChannel,
+    ChannelVerificationLevelTooHigh,
+    OAuth2ApplicationHasNoBot,
+    OAuth2ApplicationLimitReached,
+    InvalidOAuth2State,
+    MissingPermissions,
+    InvalidAuthenticationToken,
+    NoteTooLong,
+    InvalidBulkDeleteCount,
+    PinnedInOtherChannel,
+    InvalidInviteCode,
+    CannotExecuteOnSystemMessage,
+    CannotExecuteOnChannelType,
+    InvalidOAuth2AccessToken,
+    MissingOAuth2Scope,
+    InvalidWebhookToken,
+    InvalidRole,
+    InvalidRecipients,
+    MessageTooOldToBulkDelete,
+    InvalidFormBody,
+    InviteAcceptedToGuildWithoutBot,
+    InvalidApiVersion,
+    FileTooLarge,
+    InvalidFile,
+    CannotSelfRedeemGift,
+    InvalidGuild,
+    InvalidMessageType,
+    PaymentSourceRequired,
+    CannotDeleteCommunityChannel,
+    InvalidSticker,
+    ThreadArchived,
+    InvalidThreadNotificationSettings,
+    BeforeEarlierThanThreadCreation,
+    CommunityChannelsMustBeText,
+    ServerNotAvailableInLocation,
+    MonetizationRequired,
+    MoreBoostsRequired,
+    InvalidJson,
+    CannotTransferOwnershipToBot,
+    AssetResizeFailed,
+    UploadedFileNotFound,
+    MissingStickerPermission,
+    TwoFactorRequired,
+    NoUsersWithTag,
+    ReactionBlocked,
+    ResourceOverloaded,
+    StageAlreadyOpen,
+    CannotReplyWithoutReadMessageHistory,
+    ThreadAlreadyCreated,
+    ThreadLocked,
+    MaximumActiveThreads,
+    MaximumActiveAnnouncementThreads,
+    InvalidLottieJson,
+    LottieContainsRasterizedImages,
+    StickerFramerateExceeded,
+    StickerFrameCountExceeded,
+    LottieDimensionsExceeded,
+    InvalidStickerFrameRate,
+    StickerDurationExceeded,
+    CannotUpdateFinishedEvent,
+    FailedToCreateStageForEvent,
+    MessageBlockedByAutoModeration,
+    TitleBlockedByAutoModeration,
+    ForumWebhookMissingThread,
+    ForumWebhookThreadNameAndId,
+    WebhookThreadsOnlyInForums,
+    WebhookServicesInForum,
+    MessageBlockedByHarmfulLinks,
+});
+
+impl JsonErrorCode {
+    /// Whether the bot lacks the permissions to perform the request.
+    #[must_use]
+    pub fn is_missing_permissions(self) -> bool {
+        self == Self::MissingPermissions
+    }
+
+    /// Whether the bot can't access the resource, for example because it can't
+    /// view the channel.
+    #[must_use]
+    pub fn is_missing_access(self) -> bool {
+        self == Self::MissingAccess
+    }
+
+    /// Whether the requested channel does not exist.
+    #[must_use]
+    pub fn is_unknown_channel(self) -> bool {
+        self == Self::UnknownChannel
+    }
+
+    /// Whether the requested member does not exist, for example because the
+    /// user left the guild.
+    #[must_use]
+    pub fn is_unknown_member(self) -> bool {
+        self == Self::UnknownMember
+    }
+
+    /// Whether the requested message does not exist, for example because it
+    /// was deleted.
+    #[must_use]
+    pub fn is_unknown_message(self) -> bool {
+        self == Self::UnknownMessage
+    }
+
+    /// Whether the interaction does not exist, for example because it was not
+    /// responded to in time.
+    #[must_use]
+    pub fn is_unknown_interaction(self) -> bool {
+        self == Self::UnknownInteraction
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
 #[non_exhaustive]
 pub struct DiscordJsonError {
@@ -20,6 +604,86 @@ pub struct DiscordJsonError {
     pub errors: Vec<DiscordJsonSingleError>,
 }
 
+impl DiscordJsonError {
+    /// The error code as a [`JsonErrorCode`].
+    ///
+    /// Returns [`JsonErrorCode::Unknown`] if the code is not known to the
+    /// library, or the error response could not be decoded.
+    #[must_use]
+    pub fn json_code(&self) -> JsonErrorCode {
+        u64::try_from(self.code)
+            .ok()
+            .and_then(|code| {
+                let code: U64Deserializer<ValueError> = code.into_deserializer();
+                JsonErrorCode::deserialize(code).ok()
+            })
+            .unwrap_or(JsonErrorCode::Unknown)
+    }
+
+    /// The errors of the request body as a tree of its fields.
+    ///
+    /// # Examples
+    ///
+    /// Get the errors of the title of the first embed of a message:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::http::error::DiscordJsonError;
+    /// # fn run(error: &DiscordJsonError) {
+    /// let fields = error.form_fields();
+    ///
+    /// if let Some(title) = fields.get("embeds.0.title") {
+    ///     for error in &title.errors {
+    ///         println!("Invalid title: {}", error.message);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn form_fields(&self) -> FormField {
+        let mut root = FormField::default();
+
+        for error in &self.errors {
+            let mut field = &mut root;
+
+            for name in error.path.split('.').filter(|name| !name.is_empty()) {
+                field = field.fields.entry(name.to_string()).or_default();
+            }
+
+            field.errors.push(error.clone());
+        }
+
+        root
+    }
+}
+
+/// A field of a request body, with the errors Discord found in it and its
+/// nested fields. Built by [`DiscordJsonError::form_fields`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FormField {
+    /// The errors of the field itself.
+    pub errors: Vec<DiscordJsonSingleError>,
+    /// The nested fields by name. Elements of arrays are named by their index.
+    pub fields: BTreeMap<String, FormField>,
+}
+
+impl FormField {
+    /// Gets a nested field by its dot separated path, such as
+    /// `embeds.0.title`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&FormField> {
+        path.split('.')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |field, name| field.fields.get(name))
+    }
+
+    /// Whether neither the field nor any of its nested fields have errors.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.fields.values().all(FormField::is_empty)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct DiscordJsonSingleError {
     /// The error code.
@@ -78,6 +742,11 @@ pub enum Error {
     InvalidPort,
     /// When an application id was expected but missing.
     ApplicationIdMissing,
+    /// When a request did not complete within the timeout set in its
+    /// [`RequestOptions`].
+    ///
+    /// [`RequestOptions`]: super::RequestOptions
+    Timeout(Duration),
 }
 
 impl Error {
@@ -113,6 +782,16 @@ impl Error {
             _ => None,
         }
     }
+
+    /// Returns the JSON error code Discord gave if the error is an
+    /// unsuccessful request
+    #[must_use]
+    pub fn json_error_code(&self) -> Option<JsonErrorCode> {
+        match self {
+            Self::UnsuccessfulRequest(res) => Some(res.error.json_code()),
+            _ => None,
+        }
+    }
 }
 
 impl From<ErrorResponse> for Error {
@@ -172,6 +851,7 @@ impl fmt::Display for Error {
             Self::InvalidScheme => f.write_str("Invalid Url scheme."),
             Self::InvalidPort => f.write_str("Invalid port."),
             Self::ApplicationIdMissing => f.write_str("Application id was expected but missing."),
+            Self::Timeout(timeout) => write!(f, "Request timed out after {:?}.", timeout),
         }
     }
 }
@@ -219,4 +899,66 @@ mod test {
 
         assert_eq!(error_response, known);
     }
+
+    #[tokio::test]
+    async fn test_undecodable_error_response() {
+        let response = Builder::new()
+            .status(502)
+            .url(String::from("https://ferris.crab").parse().unwrap())
+            .body(b"<html>Bad Gateway</html>".to_vec())
+            .unwrap();
+
+        let error_response = ErrorResponse::from_response(response.into()).await;
+
+        assert_eq!(error_response.error.code, -1);
+        assert_eq!(Error::from(error_response).json_error_code(), Some(JsonErrorCode::Unknown));
+    }
+
+    #[test]
+    fn test_json_error_code() {
+        let error = |code| DiscordJsonError {
+            code,
+            message: String::new(),
+            errors: vec![],
+        };
+
+        assert_eq!(error(50013).json_code(), JsonErrorCode::MissingPermissions);
+        assert_eq!(error(0).json_code(), JsonErrorCode::GeneralError);
+        assert_eq!(error(1).json_code(), JsonErrorCode::Unknown);
+        assert_eq!(error(-1).json_code(), JsonErrorCode::Unknown);
+        assert_eq!(JsonErrorCode::UnknownMessage.num(), 10008);
+        assert!(error(10008).json_code().is_unknown_message());
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let error: DiscordJsonError = serde_json::from_str(
+            r#"{
+                "code": 50035,
+                "message": "Invalid Form Body",
+                "errors": {
+                    "content": {
+                        "_errors": [{ "code": "BASE_TYPE_MAX_LENGTH", "message": "Too long" }]
+                    },
+                    "embeds": {
+                        "0": {
+                            "title": {
+                                "_errors": [{ "code": "BASE_TYPE_REQUIRED", "message": "Required" }]
+                            }
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(error.json_code(), JsonErrorCode::InvalidFormBody);
+
+        let fields = error.form_fields();
+        assert!(fields.errors.is_empty());
+        assert!(!fields.is_empty());
+        assert_eq!(fields.get("content").unwrap().errors[0].code, "BASE_TYPE_MAX_LENGTH");
+        assert_eq!(fields.get("embeds.0.title").unwrap().errors[0].path, "embeds.0.title");
+        assert!(fields.get("embeds.0.description").is_none());
+    }
 }
@@ -14,7 +14,9 @@
 //! The former require a [`Client`] to have logged in, while the latter may be
 //! made regardless of any other usage of the library.
 //!
-//! If a request spuriously fails, it will be retried once.
+//! If a request fails for a transient reason, such as Discord being briefly
+//! unavailable, it is retried according to the [`RetryPolicy`] of the [`Http`]
+//! client.
 //!
 //! Note that you may want to perform requests through a [model]s'
 //! instance methods where possible, as they each offer different
@@ -26,9 +28,15 @@
 pub mod client;
 pub mod error;
 pub mod multipart;
+#[cfg(feature = "oauth")]
+pub mod oauth;
+pub mod observer;
+pub mod pagination;
 pub mod ratelimiting;
 pub mod request;
+pub mod retry;
 pub mod routing;
+pub mod transport;
 pub mod typing;
 mod utils;
 
@@ -40,6 +48,8 @@ pub use reqwest::StatusCode;
 pub use self::client::*;
 pub use self::error::Error as HttpError;
 use self::request::Request;
+pub use self::request::RequestOptions;
+pub use self::retry::{RetryInfo, RetryPolicy};
 pub use self::typing::*;
 #[cfg(feature = "cache")]
 use crate::cache::Cache;
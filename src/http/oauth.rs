@@ -0,0 +1,271 @@
+//! Authorizing users and applications through OAuth2, to make requests on
+//! their behalf.
+//!
+//! # Examples
+//!
+//! Log users in with Discord, as a web dashboard would:
+//!
+//! ```rust,no_run
+//! use serenity::http::oauth::{generate_state, OAuth2Client, Pkce};
+//! use serenity::http::{Http, RequestOptions};
+//! use serenity::model::application::oauth::Scope;
+//!
+//! # async fn run() -> serenity::Result<()> {
+//! # let http = Http::new("token");
+//! let client =
+//!     OAuth2Client::new(1234, "client secret").redirect_uri("https://example.com/callback");
+//!
+//! // Redirect the user to the authorization URL, remembering the state and
+//! // the PKCE verifier in their session.
+//! let state = generate_state();
+//! let pkce = Pkce::new();
+//! let url = client.authorization_url(&[Scope::Identify, Scope::Guilds], &state, Some(&pkce));
+//!
+//! // Once Discord redirected the user back with a code and the same state,
+//! // exchange the code for an access token.
+//! # let code = "code";
+//! let token = client.exchange_code(&http, code, Some(&pkce)).await?;
+//!
+//! let options = RequestOptions::new().token(token.bearer());
+//! let user = http.with_options(options, |http| http.get_current_user()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use super::Http;
+use crate::internal::prelude::*;
+use crate::model::application::oauth::{AccessToken, Scope};
+use crate::model::id::ApplicationId;
+
+/// The credentials of an application, used to grant and revoke OAuth2 access
+/// tokens.
+///
+/// Tokens granted on behalf of users can be used to make requests as them by
+/// passing [`AccessToken::bearer`] to [`RequestOptions::token`] or
+/// [`Http::new`].
+///
+/// [`RequestOptions::token`]: super::RequestOptions::token
+#[derive(Clone)]
+pub struct OAuth2Client {
+    client_id: ApplicationId,
+    client_secret: String,
+    redirect_uri: Option<String>,
+}
+
+impl OAuth2Client {
+    /// Creates a client with the application's client Id and secret.
+    pub fn new(client_id: impl Into<ApplicationId>, client_secret: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: None,
+        }
+    }
+
+    /// Sets the URI users are redirected to after authorizing the application,
+    /// which must be one of the redirects of the application.
+    #[must_use]
+    pub fn redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+
+        self
+    }
+
+    /// Gets the client Id of the application.
+    #[must_use]
+    pub fn get_client_id(&self) -> ApplicationId {
+        self.client_id
+    }
+
+    /// Builds the URL to send users to for authorizing the application through
+    /// the authorization code grant.
+    ///
+    /// The `state` is passed back to the redirect URI along with the code, and
+    /// should be a random value such as one from [`generate_state`], checked
+    /// against the user's session to prevent CSRF attacks. The `pkce`
+    /// challenge is optional for confidential clients.
+    #[must_use]
+    pub fn authorization_url(&self, scopes: &[Scope], state: &str, pkce: Option<&Pkce>) -> String {
+        let client_id = self.client_id.0.to_string();
+        let scopes = scopes.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ");
+
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", &*client_id),
+            ("scope", &scopes),
+            ("state", state),
+        ];
+
+        if let Some(redirect_uri) = &self.redirect_uri {
+            params.push(("redirect_uri", redirect_uri));
+        }
+
+        if let Some(pkce) = pkce {
+            params.push(("code_challenge", pkce.challenge()));
+            params.push(("code_challenge_method", "S256"));
+        }
+
+        let url = Url::parse_with_params("https://discord.com/api/oauth2/authorize", &params)
+            .expect("failed to construct URL");
+
+        url.to_string()
+    }
+
+    /// Exchanges the code a user was redirected back with for an access token.
+    ///
+    /// The `pkce` challenge must be the one the authorization URL was built
+    /// with, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the code is invalid or expired, or the client
+    /// credentials are wrong.
+    pub async fn exchange_code(
+        &self,
+        http: impl AsRef<Http>,
+        code: &str,
+        pkce: Option<&Pkce>,
+    ) -> Result<AccessToken> {
+        let mut params = vec![("grant_type", "authorization_code"), ("code", code)];
+
+        if let Some(redirect_uri) = &self.redirect_uri {
+            params.push(("redirect_uri", redirect_uri));
+        }
+
+        if let Some(pkce) = pkce {
+            params.push(("code_verifier", pkce.verifier()));
+        }
+
+        self.create_token(http.as_ref(), &params).await
+    }
+
+    /// Gets a new access token with the refresh token of an expiring one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the refresh token is invalid or was revoked.
+    pub async fn refresh_token(
+        &self,
+        http: impl AsRef<Http>,
+        refresh_token: &str,
+    ) -> Result<AccessToken> {
+        let params = [("grant_type", "refresh_token"), ("refresh_token", refresh_token)];
+
+        self.create_token(http.as_ref(), &params).await
+    }
+
+    /// Gets an access token on behalf of the application's owner through the
+    /// client credentials grant, as is done for testing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the client credentials are wrong.
+    pub async fn client_credentials(
+        &self,
+        http: impl AsRef<Http>,
+        scopes: &[Scope],
+    ) -> Result<AccessToken> {
+        let scopes = scopes.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ");
+        let params = [("grant_type", "client_credentials"), ("scope", &*scopes)];
+
+        self.create_token(http.as_ref(), &params).await
+    }
+
+    /// Revokes an access token or a refresh token, such as when a user logs
+    /// out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Http`] if the client credentials are wrong.
+    pub async fn revoke_token(&self, http: impl AsRef<Http>, token: &str) -> Result<()> {
+        http.as_ref().revoke_oauth2_token(self.client_id.0, &self.client_secret, token).await
+    }
+
+    async fn create_token(&self, http: &Http, params: &[(&str, &str)]) -> Result<AccessToken> {
+        http.create_oauth2_token(self.client_id.0, &self.client_secret, params).await
+    }
+}
+
+impl fmt::Debug for OAuth2Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuth2Client")
+            .field("client_id", &self.client_id)
+            .field("redirect_uri", &self.redirect_uri)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A Proof Key for Code Exchange, proving the code exchanged for an access
+/// token was requested by the same client.
+///
+/// The verifier must be kept in the user's session until the code is
+/// exchanged, and is passed to [`Self::from_verifier`] to restore it.
+#[derive(Clone)]
+pub struct Pkce {
+    verifier: String,
+    challenge: String,
+}
+
+impl Pkce {
+    /// Creates a PKCE with a random verifier.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_verifier(random_string(32))
+    }
+
+    /// Creates a PKCE with the given verifier.
+    pub fn from_verifier(verifier: impl Into<String>) -> Self {
+        let verifier = verifier.into();
+        let challenge = base64::encode_config(Sha256::digest(&verifier), base64::URL_SAFE_NO_PAD);
+
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+
+    /// Gets the verifier, which is sent when exchanging the code.
+    #[must_use]
+    pub fn verifier(&self) -> &str {
+        &self.verifier
+    }
+
+    /// Gets the SHA-256 challenge of the verifier, which is sent in the
+    /// authorization URL.
+    #[must_use]
+    pub fn challenge(&self) -> &str {
+        &self.challenge
+    }
+}
+
+impl Default for Pkce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Pkce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkce").field("challenge", &self.challenge).finish_non_exhaustive()
+    }
+}
+
+/// Generates a random value for the `state` of an authorization URL.
+#[must_use]
+pub fn generate_state() -> String {
+    random_string(16)
+}
+
+/// Generates `len` random bytes, encoded as URL-safe base64.
+fn random_string(len: usize) -> String {
+    let mut bytes = vec![0; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
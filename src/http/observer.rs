@@ -0,0 +1,167 @@
+//! Observing every request made to Discord's HTTP API, to collect metrics or
+//! trace requests.
+
+use std::time::{Duration, Instant};
+
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode, Url};
+
+use super::routing::Route;
+use super::transport::{HttpTransport, TransportRequest};
+use super::LightMethod;
+use crate::internal::prelude::*;
+
+/// Passed to [`HttpObserver::before_request`] before a request is sent.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RequestInfo {
+    /// The HTTP method of the request.
+    pub method: LightMethod,
+    /// The route the request is ratelimited by.
+    pub route: Route,
+    /// The full URL the request is sent to, including the query string.
+    pub url: Url,
+    /// The attempt of the request as counted by the [`RetryPolicy`], starting
+    /// at 1 and increased every time the request is retried after failing.
+    ///
+    /// [`RetryPolicy`]: super::RetryPolicy
+    pub attempt: u32,
+    /// The number of times the request was resent during this attempt after
+    /// hitting a ratelimit.
+    pub ratelimit_retries: u32,
+    /// The size of the request body in bytes, or `None` for multipart bodies.
+    pub body_size: Option<usize>,
+}
+
+/// Passed to [`HttpObserver::after_request`] once a request completed or
+/// failed.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ResponseInfo {
+    /// The HTTP method of the request.
+    pub method: LightMethod,
+    /// The route the request is ratelimited by.
+    pub route: Route,
+    /// The full URL the request was sent to, including the query string.
+    pub url: Url,
+    /// The attempt of the request as counted by the [`RetryPolicy`], see
+    /// [`RequestInfo::attempt`].
+    ///
+    /// [`RetryPolicy`]: super::RetryPolicy
+    pub attempt: u32,
+    /// The number of times the request was resent during this attempt after
+    /// hitting a ratelimit.
+    pub ratelimit_retries: u32,
+    /// The status of the response, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+    /// The time from sending the request until the response headers were
+    /// received or the request failed.
+    pub latency: Duration,
+    /// The size of the response body in bytes, if known in advance.
+    pub body_size: Option<u64>,
+    /// The bucket hash the route counts towards, as given by the
+    /// `x-ratelimit-bucket` header.
+    pub bucket: Option<String>,
+    /// The number of requests remaining in the bucket, as given by the
+    /// `x-ratelimit-remaining` header.
+    pub remaining: Option<i64>,
+    /// The time until the bucket resets, as given by the
+    /// `x-ratelimit-reset-after` header.
+    pub reset_after: Option<Duration>,
+}
+
+/// Called before and after every request sent to Discord, including each
+/// retry of a request.
+///
+/// Both methods are called on the task making the request, and should return
+/// quickly.
+///
+/// # Examples
+///
+/// Count the requests which hit a ratelimit:
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// use serenity::http::observer::{HttpObserver, ResponseInfo};
+/// use serenity::http::{HttpBuilder, StatusCode};
+///
+/// #[derive(Default)]
+/// struct Ratelimits(AtomicU64);
+///
+/// impl HttpObserver for Ratelimits {
+///     fn after_request(&self, info: &ResponseInfo) {
+///         if info.status == Some(StatusCode::TOO_MANY_REQUESTS) {
+///             self.0.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+///
+/// let http = HttpBuilder::new("token").observer(Arc::new(Ratelimits::default())).build();
+/// ```
+pub trait HttpObserver: Send + Sync {
+    /// Called before a request is sent.
+    fn before_request(&self, _info: &RequestInfo) {}
+
+    /// Called after a response to a request was received, or sending it
+    /// failed.
+    fn after_request(&self, _info: &ResponseInfo) {}
+}
+
+/// Sends the request through the transport, notifying the observer if there
+/// is one.
+pub(super) async fn execute(
+    transport: &dyn HttpTransport,
+    observer: Option<&dyn HttpObserver>,
+    request: TransportRequest,
+    attempt: u32,
+    ratelimit_retries: u32,
+) -> Result<Response> {
+    let observer = match observer {
+        Some(observer) => observer,
+        None => return transport.execute(request).await,
+    };
+
+    let method = request.method;
+    let route = request.route;
+    let url = request.request.url().clone();
+
+    observer.before_request(&RequestInfo {
+        method,
+        route,
+        url: url.clone(),
+        attempt,
+        ratelimit_retries,
+        body_size: request.request.body().and_then(reqwest::Body::as_bytes).map(<[u8]>::len),
+    });
+
+    let start = Instant::now();
+    let result = transport.execute(request).await;
+    let latency = start.elapsed();
+
+    let response = result.as_ref().ok();
+    let headers = response.map(Response::headers);
+
+    observer.after_request(&ResponseInfo {
+        method,
+        route,
+        url,
+        attempt,
+        ratelimit_retries,
+        status: response.map(Response::status),
+        latency,
+        body_size: response.and_then(Response::content_length),
+        bucket: headers.and_then(|h| header(h, "x-ratelimit-bucket")).map(str::to_string),
+        remaining: headers.and_then(|h| header(h, "x-ratelimit-remaining")?.parse().ok()),
+        reset_after: headers
+            .and_then(|h| header(h, "x-ratelimit-reset-after")?.parse().ok())
+            .map(Duration::from_secs_f64),
+    });
+
+    result
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
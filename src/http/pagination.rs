@@ -0,0 +1,92 @@
+//! Streaming the items of endpoints which return them in pages.
+
+use std::collections::VecDeque;
+
+use futures::future::BoxFuture;
+use futures::stream::{self, Stream};
+
+use super::Http;
+use crate::internal::prelude::*;
+
+/// A page of items, and the cursor of the next page if there is one.
+pub type Page<T, C> = (Vec<T>, Option<C>);
+
+/// Streams the items of a paginated endpoint, fetching pages as they are
+/// needed.
+///
+/// `fetch` is called with the cursor of the page to fetch, which is `None` for
+/// the first page, and returns the items of the page along with the cursor of
+/// the next one. The stream ends once a page has no next cursor, or after
+/// yielding an error.
+///
+/// The model provides streams for most paginated endpoints, such as
+/// [`GuildId::bans_iter`] and [`ChannelId::reaction_users_iter`]. This is for
+/// endpoints they don't cover.
+///
+/// # Examples
+///
+/// Stream the members of a guild, fetching 1000 at a time:
+///
+/// ```rust,no_run
+/// use serenity::futures::StreamExt;
+/// use serenity::http::pagination::{next_cursor, paginate};
+/// use serenity::http::Http;
+///
+/// # async fn run() -> serenity::Result<()> {
+/// # let http = Http::new("token");
+/// let mut members = paginate(&http, |http, after| {
+///     Box::pin(async move {
+///         let members = http.get_guild_members(1, Some(1000), after).await?;
+///         let next = next_cursor(&members, 1000, |member| member.user.id.0);
+///
+///         Ok((members, next))
+///     })
+/// })
+/// .boxed();
+///
+/// while let Some(member) = members.next().await {
+///     println!("{}", member?.user.tag());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`GuildId::bans_iter`]: crate::model::id::GuildId::bans_iter
+/// [`ChannelId::reaction_users_iter`]: crate::model::id::ChannelId::reaction_users_iter
+pub fn paginate<H, T, C, F>(http: H, fetch: F) -> impl Stream<Item = Result<T>>
+where
+    H: AsRef<Http>,
+    F: for<'a> Fn(&'a Http, Option<C>) -> BoxFuture<'a, Result<Page<T, C>>>,
+{
+    // The cursor is `Some(None)` before the first page, and `None` after the
+    // last one.
+    let state = (http, fetch, VecDeque::new(), Some(None));
+
+    stream::unfold(state, |(http, fetch, mut buffer, mut cursor)| async move {
+        loop {
+            if let Some(item) = buffer.pop_front() {
+                return Some((Ok(item), (http, fetch, buffer, cursor)));
+            }
+
+            match fetch(http.as_ref(), cursor?).await {
+                Ok((items, next)) => {
+                    buffer.extend(items);
+                    cursor = next.map(Some);
+                },
+                Err(error) => return Some((Err(error), (http, fetch, buffer, None))),
+            }
+        }
+    })
+}
+
+/// Gets the cursor of the page after the given one, for endpoints which
+/// return fewer items than requested only on the last page.
+///
+/// Returns the cursor of the last item if the page is full, otherwise `None`.
+pub fn next_cursor<T, C>(items: &[T], limit: u64, cursor: impl FnOnce(&T) -> C) -> Option<C> {
+    if items.len() as u64 >= limit {
+        items.last().map(cursor)
+    } else {
+        None
+    }
+}
@@ -37,13 +37,25 @@
 //! variants) have an associated u64 as data. This is the Id of the parameter,
 //! differentiating between different ratelimits.
 //!
+//! Discord may also share a ratelimit between several routes, or split a route
+//! into several ratelimits. Responses name the ratelimit they count towards in
+//! the `x-ratelimit-bucket` header, which the [`Ratelimiter`] learns for every
+//! [`Route`]. Routes known to be in the same bucket then share a [`Ratelimit`]
+//! if their major parameters are equal.
+//!
+//! Ratelimits which have reset and are not in use are cleared out every
+//! minute, so that those of routes which are no longer requested don't pile
+//! up.
+//!
 //! [Taken from]: https://discord.com/developers/docs/topics/rate-limits#rate-limits
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::fmt;
 use std::str::{self, FromStr};
-use std::sync::Arc;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use reqwest::header::HeaderMap;
 use reqwest::{Client, Response, StatusCode};
@@ -51,10 +63,13 @@ use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, instrument};
 
+use super::observer::{self, HttpObserver};
 pub use super::routing::Route;
 use super::routing::RouteInfo;
+use super::transport::{HttpTransport, TransportRequest};
 use super::{HttpError, LightMethod, Request};
 use crate::internal::prelude::*;
+use crate::internal::tokio::spawn_named;
 
 /// Passed to the [`Ratelimiter::set_ratelimit_callback`] callback. If using Client, that callback
 /// is initialized to call the `EventHandler::ratelimit()` method.
@@ -66,8 +81,43 @@ pub struct RatelimitInfo {
     pub method: LightMethod,
     pub path: String,
     pub global: bool,
+    /// The scope of the ratelimit given by Discord, or `None` for pre-emptive
+    /// ratelimits.
+    pub scope: Option<RatelimitScope>,
 }
 
+/// The scope of a ratelimit, as given by the `x-ratelimit-scope` header.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum RatelimitScope {
+    /// The ratelimit is specific to the bot.
+    User,
+    /// The ratelimit is the global ratelimit of the bot.
+    Global,
+    /// The ratelimit is shared by everyone using the resource. Hitting it does
+    /// not count against the bot, and does not affect its own ratelimits.
+    Shared,
+}
+
+impl FromStr for RatelimitScope {
+    type Err = ();
+
+    fn from_str(s: &str) -> StdResult<Self, Self::Err> {
+        match s {
+            "user" => Ok(Self::User),
+            "global" => Ok(Self::Global),
+            "shared" => Ok(Self::Shared),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A bucket hash and the major parameter of the routes in it.
+type BucketKey = (String, Option<u64>);
+
+/// How often ratelimits which have reset are cleared out.
+const CLEAR_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Ratelimiter for requests to the Discord API.
 ///
 /// This keeps track of ratelimit data for known routes through the
@@ -78,21 +128,40 @@ pub struct RatelimitInfo {
 /// When no tickets are available for some time, then the thread sleeps until
 /// that time passes. The mechanism is known as "pre-emptive ratelimiting".
 ///
+/// Until the bucket of a route is known from the `x-ratelimit-bucket` header
+/// of a response, its ratelimit is tracked per [`Route`]. Afterwards, it is
+/// tracked per bucket and major parameter, shared with all other routes in the
+/// same bucket.
+///
 /// Occasionally for very high traffic bots, a global ratelimit may be reached
 /// which blocks all future requests until the global ratelimit is over,
 /// regardless of route. The value of this global ratelimit is never given
 /// through the API, so it can't be pre-emptively ratelimited. This only affects
 /// the largest of bots.
 ///
+/// Ratelimits which have reset and are not in use by a request are removed
+/// every minute by a background task, which is started by the first request
+/// and stops once the ratelimiter is dropped. They can also be removed by
+/// calling [`Self::clear_expired`].
+///
+/// Requests may be made with other tokens than the ratelimiter's own, as set
+/// by [`RequestOptions::token`]. Ratelimits are tracked separately for every
+/// token.
+///
+/// [`RequestOptions::token`]: super::RequestOptions::token
 /// [`limit`]: Ratelimit::limit
 /// [`remaining`]: Ratelimit::remaining
 /// [`reset`]: Ratelimit::reset
 pub struct Ratelimiter {
     client: Client,
-    global: Arc<Mutex<()>>,
-    // When futures is implemented, make tasks clear out their respective entry
-    // when the 'reset' passes.
-    routes: Arc<RwLock<HashMap<Route, Arc<Mutex<Ratelimit>>>>>,
+    transport: Arc<dyn HttpTransport>,
+    observer: Option<Arc<dyn HttpObserver>>,
+    /// Whether the task clearing out expired ratelimits was started.
+    clearing: AtomicBool,
+    /// The ratelimits of the ratelimiter's own token.
+    ratelimits: Arc<TokenRatelimits>,
+    /// The ratelimits of other tokens requests were made with.
+    other_tokens: Arc<RwLock<HashMap<String, Arc<TokenRatelimits>>>>,
     token: String,
     ratelimit_callback: Box<dyn Fn(RatelimitInfo) + Send + Sync>,
 }
@@ -101,8 +170,7 @@ impl fmt::Debug for Ratelimiter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Ratelimiter")
             .field("client", &self.client)
-            .field("global", &self.global)
-            .field("routes", &self.routes)
+            .field("ratelimits", &self.ratelimits)
             .finish()
     }
 }
@@ -119,9 +187,12 @@ impl Ratelimiter {
 
     fn _new(client: Client, token: String) -> Self {
         Self {
+            transport: Arc::new(client.clone()),
+            observer: None,
             client,
-            global: Arc::default(),
-            routes: Arc::default(),
+            clearing: AtomicBool::new(false),
+            ratelimits: Arc::default(),
+            other_tokens: Arc::default(),
             token,
             ratelimit_callback: Box::new(|_| {}),
         }
@@ -135,6 +206,17 @@ impl Ratelimiter {
         self.ratelimit_callback = ratelimit_callback;
     }
 
+    /// Sets the transport requests are sent through, which is the
+    /// [`reqwest::Client`] passed to [`Self::new`] by default.
+    pub fn set_transport(&mut self, transport: Arc<dyn HttpTransport>) {
+        self.transport = transport;
+    }
+
+    /// Sets an observer to be notified before and after every request.
+    pub fn set_observer(&mut self, observer: Arc<dyn HttpObserver>) {
+        self.observer = Some(observer);
+    }
+
     /// The routes mutex is a HashMap of each [`Route`] and their respective
     /// ratelimit information.
     ///
@@ -164,7 +246,80 @@ impl Ratelimiter {
     /// ```
     #[must_use]
     pub fn routes(&self) -> Arc<RwLock<HashMap<Route, Arc<Mutex<Ratelimit>>>>> {
-        Arc::clone(&self.routes)
+        Arc::clone(&self.ratelimits.routes)
+    }
+
+    /// The bucket hash Discord reported for the route, if any request to it
+    /// has been made.
+    pub async fn bucket_hash(&self, route: &Route) -> Option<String> {
+        self.ratelimits.bucket_hashes.read().await.get(route).cloned()
+    }
+
+    /// The number of ratelimits currently tracked, both of routes and of
+    /// buckets, for all tokens.
+    pub async fn bucket_count(&self) -> usize {
+        let mut count = self.ratelimits.count().await;
+
+        for ratelimits in self.other_tokens.read().await.values() {
+            count += ratelimits.count().await;
+        }
+
+        count
+    }
+
+    /// Removes all ratelimits which have reset and are not in use by a
+    /// request, returning how many were removed.
+    ///
+    /// This is done automatically every minute once requests are performed.
+    pub async fn clear_expired(&self) -> usize {
+        clear_expired(&self.ratelimits, &self.other_tokens).await
+    }
+
+    /// Starts the task clearing out expired ratelimits every
+    /// [`CLEAR_INTERVAL`], unless it was already started.
+    ///
+    /// The task only holds weak references to the ratelimits, so that it
+    /// stops once the ratelimiter is dropped.
+    fn start_clearing(&self) {
+        if self.clearing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let ratelimits = Arc::downgrade(&self.ratelimits);
+        let other_tokens = Arc::downgrade(&self.other_tokens);
+
+        spawn_named("ratelimiter::clear_expired", async move {
+            let mut interval = tokio::time::interval(CLEAR_INTERVAL);
+            // The first tick completes immediately.
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let (ratelimits, other_tokens) =
+                    match (Weak::upgrade(&ratelimits), Weak::upgrade(&other_tokens)) {
+                        (Some(ratelimits), Some(other_tokens)) => (ratelimits, other_tokens),
+                        _ => break,
+                    };
+
+                let removed = clear_expired(&ratelimits, &other_tokens).await;
+
+                debug!("Cleared out {} expired ratelimits", removed);
+            }
+        });
+    }
+
+    /// Gets the ratelimits of the token.
+    async fn ratelimits(&self, token: &str) -> Arc<TokenRatelimits> {
+        if token == self.token {
+            return Arc::clone(&self.ratelimits);
+        }
+
+        if let Some(ratelimits) = self.other_tokens.read().await.get(token) {
+            return Arc::clone(ratelimits);
+        }
+
+        Arc::clone(self.other_tokens.write().await.entry(token.to_string()).or_default())
     }
 
     /// # Errors
@@ -174,11 +329,21 @@ impl Ratelimiter {
     pub async fn perform(&self, req: RatelimitedRequest<'_>) -> Result<Response> {
         let RatelimitedRequest {
             mut req,
+            attempt,
+            token,
         } = req;
+        let mut ratelimit_retries = 0;
+
+        self.start_clearing();
+
+        let token = token.as_deref().unwrap_or(&self.token);
+        let ratelimits = self.ratelimits(token).await;
 
         loop {
-            // This will block if another thread hit the global ratelimit.
-            drop(self.global.lock().await);
+            // This will wait if a request hit the global ratelimit.
+            if let Some(delay) = until(ratelimits.global.load(Ordering::Acquire)) {
+                sleep(delay).await;
+            }
 
             // Destructure the tuple instead of retrieving the third value to
             // take advantage of the type system. If `RouteInfo::deconstruct`
@@ -200,13 +365,24 @@ impl Ratelimiter {
             // - get the global rate;
             // - sleep if there is 0 remaining
             // - then, perform the request
-            let bucket = Arc::clone(self.routes.write().await.entry(route).or_default());
+            let bucket = ratelimits.bucket(route).await;
 
             bucket.lock().await.pre_hook(&req.route, &self.ratelimit_callback).await;
 
-            let request = req.build(&self.client, &self.token, None).await?.build()?;
-
-            let response = self.client.execute(request).await?;
+            let request = req.build(&self.client, token, None).await?.build()?;
+
+            let response = observer::execute(
+                &*self.transport,
+                self.observer.as_deref(),
+                TransportRequest {
+                    method,
+                    route,
+                    request,
+                },
+                attempt,
+                ratelimit_retries,
+            )
+            .await?;
 
             // Check if the request got ratelimited by checking for status 429,
             // and if so, sleep for the value of the header 'retry-after' -
@@ -225,9 +401,22 @@ impl Ratelimiter {
                 return Ok(response);
             }
 
-            let redo = if response.headers().get("x-ratelimit-global").is_some() {
-                drop(self.global.lock().await);
+            // Responses name the bucket they count towards, which may be
+            // shared with other routes.
+            let bucket = match response
+                .headers()
+                .get("x-ratelimit-bucket")
+                .and_then(|hash| hash.to_str().ok())
+            {
+                Some(hash) => ratelimits.learn_bucket(route, hash).await,
+                None => bucket,
+            };
+
+            let scope = parse_scope(response.headers());
 
+            let redo = if response.headers().get("x-ratelimit-global").is_some()
+                || scope == Some(RatelimitScope::Global)
+            {
                 Ok(
                     if let Some(retry_after) =
                         parse_header::<f64>(response.headers(), "retry-after")?
@@ -239,8 +428,13 @@ impl Ratelimiter {
                             method,
                             path,
                             global: true,
+                            scope,
                         });
-                        sleep(Duration::from_secs_f64(retry_after)).await;
+
+                        // Every request waits for the global ratelimit to
+                        // reset before being sent, including this one.
+                        let reset = SystemTime::now() + Duration::from_secs_f64(retry_after);
+                        ratelimits.global.fetch_max(unix_millis(reset), Ordering::AcqRel);
 
                         true
                     } else {
@@ -254,7 +448,99 @@ impl Ratelimiter {
             if !redo.unwrap_or(true) {
                 return Ok(response);
             }
+
+            ratelimit_retries += 1;
+        }
+    }
+}
+
+/// The ratelimits of a token.
+#[derive(Debug, Default)]
+struct TokenRatelimits {
+    /// The Unix timestamp in milliseconds at which the global ratelimit
+    /// resets, which is in the past unless it was hit.
+    global: AtomicU64,
+    routes: Arc<RwLock<HashMap<Route, Arc<Mutex<Ratelimit>>>>>,
+    bucket_hashes: RwLock<HashMap<Route, String>>,
+    buckets: RwLock<HashMap<BucketKey, Arc<Mutex<Ratelimit>>>>,
+}
+
+impl TokenRatelimits {
+    /// Gets the ratelimit the route counts towards: that of its bucket if it
+    /// is known, otherwise that of the route itself.
+    async fn bucket(&self, route: Route) -> Arc<Mutex<Ratelimit>> {
+        if let Some(hash) = self.bucket_hashes.read().await.get(&route) {
+            let key = (hash.clone(), route.major_parameter());
+
+            if let Some(bucket) = self.buckets.read().await.get(&key) {
+                return Arc::clone(bucket);
+            }
+        }
+
+        Arc::clone(self.routes.write().await.entry(route).or_default())
+    }
+
+    /// Records the bucket Discord reported for the route, and gets its
+    /// ratelimit.
+    async fn learn_bucket(&self, route: Route, hash: &str) -> Arc<Mutex<Ratelimit>> {
+        {
+            let mut bucket_hashes = self.bucket_hashes.write().await;
+
+            if bucket_hashes.get(&route).map(String::as_str) != Some(hash) {
+                debug!("Route {:?} is in bucket {}", route, hash);
+                bucket_hashes.insert(route, hash.to_string());
+            }
+        }
+
+        let key = (hash.to_string(), route.major_parameter());
+
+        Arc::clone(self.buckets.write().await.entry(key).or_default())
+    }
+
+    async fn count(&self) -> usize {
+        self.routes.read().await.len() + self.buckets.read().await.len()
+    }
+
+    /// Whether no ratelimits are tracked and the global ratelimit isn't hit.
+    async fn is_unused(&self, now: SystemTime) -> bool {
+        self.count().await == 0 && self.global.load(Ordering::Acquire) <= unix_millis(now)
+    }
+
+    /// Removes all ratelimits which have reset and are not in use by a
+    /// request, returning how many were removed.
+    ///
+    /// Every map is locked on its own, so that requests are only held up
+    /// while it is cleared.
+    async fn clear_expired(&self, now: SystemTime) -> usize {
+        let mut removed = 0;
+
+        {
+            let mut routes = self.routes.write().await;
+            let count = routes.len();
+            routes.retain(|_, ratelimit| !is_expired(ratelimit, now));
+            removed += count - routes.len();
+        }
+
+        {
+            let mut buckets = self.buckets.write().await;
+            let count = buckets.len();
+            buckets.retain(|_, ratelimit| !is_expired(ratelimit, now));
+            removed += count - buckets.len();
         }
+
+        // Forget the buckets of routes without any ratelimits left, which are
+        // learned again on their next request. Take the locks in the same
+        // order as `bucket`.
+        let mut bucket_hashes = self.bucket_hashes.write().await;
+        let buckets = self.buckets.read().await;
+        let routes = self.routes.read().await;
+
+        bucket_hashes.retain(|route, hash| {
+            routes.contains_key(route)
+                || buckets.contains_key(&(hash.clone(), route.major_parameter()))
+        });
+
+        removed
     }
 }
 
@@ -320,6 +606,7 @@ impl Ratelimit {
                 method,
                 path: path.to_string(),
                 global: false,
+                scope: None,
             });
 
             sleep(delay).await;
@@ -337,28 +624,12 @@ impl Ratelimit {
         route: &RouteInfo<'_>,
         ratelimit_callback: &(dyn Fn(RatelimitInfo) + Send + Sync),
     ) -> Result<bool> {
-        if let Some(limit) = parse_header(response.headers(), "x-ratelimit-limit")? {
-            self.limit = limit;
-        }
+        let scope = parse_scope(response.headers());
 
-        if let Some(remaining) = parse_header(response.headers(), "x-ratelimit-remaining")? {
-            self.remaining = remaining;
-        }
-
-        #[cfg(feature = "absolute_ratelimits")]
-        if let Some(reset) = parse_header::<f64>(response.headers(), "x-ratelimit-reset")? {
-            self.reset = Some(std::time::UNIX_EPOCH + Duration::from_secs_f64(reset));
-        }
-
-        if let Some(reset_after) =
-            parse_header::<f64>(response.headers(), "x-ratelimit-reset-after")?
-        {
-            #[cfg(not(feature = "absolute_ratelimits"))]
-            {
-                self.reset = Some(SystemTime::now() + Duration::from_secs_f64(reset_after));
-            }
-
-            self.reset_after = Some(Duration::from_secs_f64(reset_after));
+        // The headers of a ratelimit on a shared resource describe the
+        // resource, not the bot's own bucket.
+        if scope != Some(RatelimitScope::Shared) {
+            self.update(response.headers())?;
         }
 
         Ok(if response.status() != StatusCode::TOO_MANY_REQUESTS {
@@ -373,6 +644,7 @@ impl Ratelimit {
                 method,
                 path: path.to_string(),
                 global: false,
+                scope,
             });
 
             sleep(Duration::from_secs_f64(retry_after)).await;
@@ -383,6 +655,32 @@ impl Ratelimit {
         })
     }
 
+    fn update(&mut self, headers: &HeaderMap) -> Result<()> {
+        if let Some(limit) = parse_header(headers, "x-ratelimit-limit")? {
+            self.limit = limit;
+        }
+
+        if let Some(remaining) = parse_header(headers, "x-ratelimit-remaining")? {
+            self.remaining = remaining;
+        }
+
+        #[cfg(feature = "absolute_ratelimits")]
+        if let Some(reset) = parse_header::<f64>(headers, "x-ratelimit-reset")? {
+            self.reset = Some(std::time::UNIX_EPOCH + Duration::from_secs_f64(reset));
+        }
+
+        if let Some(reset_after) = parse_header::<f64>(headers, "x-ratelimit-reset-after")? {
+            #[cfg(not(feature = "absolute_ratelimits"))]
+            {
+                self.reset = Some(SystemTime::now() + Duration::from_secs_f64(reset_after));
+            }
+
+            self.reset_after = Some(Duration::from_secs_f64(reset_after));
+        }
+
+        Ok(())
+    }
+
     /// The total number of requests that can be made in a period of time.
     #[inline]
     #[must_use]
@@ -429,15 +727,46 @@ impl Default for Ratelimit {
 /// perform a full cycle of making the request and returning the response.
 ///
 /// Use the [`From`] implementations for making one of these.
-#[derive(Debug)]
 pub struct RatelimitedRequest<'a> {
     req: Request<'a>,
+    attempt: u32,
+    token: Option<String>,
+}
+
+impl fmt::Debug for RatelimitedRequest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RatelimitedRequest")
+            .field("req", &self.req)
+            .field("attempt", &self.attempt)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl RatelimitedRequest<'_> {
+    /// Sets the attempt of the request as counted by the [`RetryPolicy`].
+    ///
+    /// [`RetryPolicy`]: super::RetryPolicy
+    pub(super) fn attempt(mut self, attempt: u32) -> Self {
+        self.attempt = attempt;
+
+        self
+    }
+
+    /// Sets the token the request is made with, instead of the ratelimiter's.
+    pub(super) fn token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+
+        self
+    }
 }
 
 impl<'a> From<Request<'a>> for RatelimitedRequest<'a> {
     fn from(req: Request<'a>) -> Self {
         Self {
             req,
+            attempt: 1,
+            token: None,
         }
     }
 }
@@ -456,16 +785,91 @@ fn parse_header<T: FromStr>(headers: &HeaderMap, header: &str) -> Result<Option<
     Ok(Some(num))
 }
 
+fn parse_scope(headers: &HeaderMap) -> Option<RatelimitScope> {
+    headers.get("x-ratelimit-scope")?.to_str().ok()?.parse().ok()
+}
+
+/// Removes all ratelimits which have reset and are not in use by a request,
+/// and the ratelimits of other tokens which are no longer used, returning how
+/// many ratelimits were removed.
+async fn clear_expired(
+    ratelimits: &TokenRatelimits,
+    other_tokens: &RwLock<HashMap<String, Arc<TokenRatelimits>>>,
+) -> usize {
+    let now = SystemTime::now();
+    let mut removed = ratelimits.clear_expired(now).await;
+
+    let tokens = other_tokens.read().await.clone();
+    let mut unused = Vec::new();
+
+    for (token, ratelimits) in tokens {
+        removed += ratelimits.clear_expired(now).await;
+
+        if ratelimits.is_unused(now).await {
+            unused.push(token);
+        }
+    }
+
+    if !unused.is_empty() {
+        let mut other_tokens = other_tokens.write().await;
+
+        for token in unused {
+            // Keep the ratelimits if a request started using them meanwhile.
+            if let Some(ratelimits) = other_tokens.get(&token) {
+                if Arc::strong_count(ratelimits) == 1 && ratelimits.is_unused(now).await {
+                    other_tokens.remove(&token);
+                }
+            }
+        }
+    }
+
+    removed
+}
+
+fn is_expired(ratelimit: &Arc<Mutex<Ratelimit>>, now: SystemTime) -> bool {
+    if Arc::strong_count(ratelimit) > 1 {
+        return false;
+    }
+
+    ratelimit
+        .try_lock()
+        .map_or(false, |ratelimit| ratelimit.reset.map_or(true, |reset| reset <= now))
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// The time left until the Unix timestamp in milliseconds, if it is in the
+/// future.
+fn until(millis: u64) -> Option<Duration> {
+    (UNIX_EPOCH + Duration::from_millis(millis)).duration_since(SystemTime::now()).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error as StdError;
     use std::result::Result as StdResult;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
 
     use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-
-    use super::parse_header;
+    use reqwest::Client;
+
+    use super::{
+        parse_header,
+        parse_scope,
+        RatelimitScope,
+        RatelimitedRequest,
+        Ratelimiter,
+        Route,
+        CLEAR_INTERVAL,
+    };
     use crate::error::Error;
-    use crate::http::HttpError;
+    use crate::http::request::RequestBuilder;
+    use crate::http::routing::RouteInfo;
+    use crate::http::{HttpError, LightMethod};
 
     type Result<T> = StdResult<T, Box<dyn StdError>>;
 
@@ -523,4 +927,88 @@ mod tests {
         assert!(is_err!("x-bad-num", HttpError::RateLimitI64F64));
         assert!(is_err!("x-bad-unicode", HttpError::RateLimitUtf8));
     }
+
+    #[test]
+    fn test_redacts_token() {
+        let req = RatelimitedRequest::from(RequestBuilder::new(RouteInfo::GetGateway).build())
+            .token(Some("Bearer secret".to_string()));
+
+        assert!(!format!("{:?}", req).contains("secret"));
+    }
+
+    #[test]
+    fn test_parse_scope() {
+        let mut headers = headers();
+        assert_eq!(parse_scope(&headers), None);
+
+        headers.insert("x-ratelimit-scope", HeaderValue::from_static("shared"));
+        assert_eq!(parse_scope(&headers), Some(RatelimitScope::Shared));
+
+        headers.insert("x-ratelimit-scope", HeaderValue::from_static("unknown"));
+        assert_eq!(parse_scope(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_shared_buckets() {
+        let ratelimiter = Ratelimiter::new(Client::new(), "Bot token");
+
+        let messages = Route::ChannelsIdMessages(1);
+        let message = Route::ChannelsIdMessagesId(LightMethod::Get, 1);
+        let other_channel = Route::ChannelsIdMessages(2);
+
+        // Until their buckets are known, routes are ratelimited separately.
+        assert!(!Arc::ptr_eq(
+            &ratelimiter.ratelimits.bucket(messages).await,
+            &ratelimiter.ratelimits.bucket(message).await,
+        ));
+
+        for route in [messages, message, other_channel] {
+            ratelimiter.ratelimits.learn_bucket(route, "abcd").await;
+        }
+
+        assert_eq!(ratelimiter.bucket_hash(&message).await.as_deref(), Some("abcd"));
+        assert!(Arc::ptr_eq(
+            &ratelimiter.ratelimits.bucket(messages).await,
+            &ratelimiter.ratelimits.bucket(message).await,
+        ));
+        assert!(!Arc::ptr_eq(
+            &ratelimiter.ratelimits.bucket(messages).await,
+            &ratelimiter.ratelimits.bucket(other_channel).await,
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_clear_expired() {
+        let ratelimiter = Ratelimiter::new(Client::new(), "Bot token");
+
+        let expired = Route::ChannelsIdMessages(1);
+        let limited = Route::ChannelsIdMessages(2);
+        let in_use = Route::ChannelsIdMessages(3);
+
+        ratelimiter.ratelimits.learn_bucket(expired, "abcd").await;
+        ratelimiter.ratelimits.bucket(limited).await.lock().await.reset =
+            Some(SystemTime::now() + Duration::from_secs(60));
+        let _held = ratelimiter.ratelimits.bucket(in_use).await;
+
+        assert_eq!(ratelimiter.bucket_count().await, 3);
+        assert_eq!(ratelimiter.clear_expired().await, 1);
+        assert_eq!(ratelimiter.bucket_count().await, 2);
+        assert_eq!(ratelimiter.bucket_hash(&expired).await, None);
+        assert!(ratelimiter.ratelimits.routes.read().await.contains_key(&limited));
+        assert!(ratelimiter.ratelimits.routes.read().await.contains_key(&in_use));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_clear_expired_in_background() {
+        let ratelimiter = Ratelimiter::new(Client::new(), "Bot token");
+
+        ratelimiter.ratelimits.learn_bucket(Route::ChannelsIdMessages(1), "abcd").await;
+        ratelimiter.start_clearing();
+
+        tokio::time::sleep(CLEAR_INTERVAL / 2).await;
+        assert_eq!(ratelimiter.bucket_count().await, 1);
+
+        tokio::time::sleep(CLEAR_INTERVAL).await;
+        assert_eq!(ratelimiter.bucket_count().await, 0);
+    }
 }
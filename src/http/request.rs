@@ -1,7 +1,11 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
 
 use reqwest::header::{
     HeaderMap as Headers,
+    HeaderName,
     HeaderValue,
     AUTHORIZATION,
     CONTENT_LENGTH,
@@ -11,9 +15,10 @@ use reqwest::header::{
 use reqwest::{Client, RequestBuilder as ReqwestRequestBuilder, Url};
 use tracing::instrument;
 
+use super::client::parse_token;
 use super::multipart::Multipart;
 use super::routing::RouteInfo;
-use super::HttpError;
+use super::{HttpError, RetryPolicy};
 use crate::constants;
 use crate::internal::prelude::*;
 
@@ -177,3 +182,174 @@ impl<'a> Request<'a> {
         &mut self.route
     }
 }
+
+tokio::task_local! {
+    static REQUEST_OPTIONS: RequestOptions;
+}
+
+/// Options for requests made through [`Http`], overriding the configuration
+/// of the client for those requests.
+///
+/// Options apply to every request made while running a future, either
+/// through [`Http::with_options`] or [`Self::scope`], which also works for
+/// requests made by model methods. Unset options fall back to those of the
+/// client.
+///
+/// The options are stored in the task running the future, rather than in a
+/// client. This means that they:
+///
+/// - apply to requests made through _every_ [`Http`] client while the future
+///   runs, not only through the client [`Http::with_options`] was called on;
+/// - do not apply to requests made by tasks the future spawns, for example
+///   via [`tokio::spawn`]. Wrap the spawned future in [`Self::scope`] to apply
+///   them there as well.
+///
+/// To cancel requests, drop the future making them.
+///
+/// # Examples
+///
+/// Respond to an interaction, giving up after 2 seconds instead of waiting
+/// for a ratelimit to reset:
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use serenity::http::{Http, RequestOptions};
+/// use serenity::json::json;
+///
+/// # async fn run() -> serenity::Result<()> {
+/// # let http = Http::new("token");
+/// let options = RequestOptions::new().timeout(Duration::from_secs(2));
+/// let data = json!({ "type": 4, "data": { "content": "Pong!" } });
+///
+/// http.with_options(options, |http| http.create_interaction_response(1, "token", &data)).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Make requests on behalf of another bot, sharing the client's connection
+/// pool:
+///
+/// ```rust,no_run
+/// use serenity::http::{Http, RequestOptions};
+///
+/// # async fn run() -> serenity::Result<()> {
+/// # let http = Http::new("token");
+/// let options = RequestOptions::new().token("other token");
+///
+/// let user = http.with_options(options, |http| http.get_current_user()).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Http`]: super::Http
+/// [`Http::with_options`]: super::Http::with_options
+#[derive(Clone, Default)]
+pub struct RequestOptions {
+    pub(super) timeout: Option<Duration>,
+    pub(super) ratelimiter_disabled: Option<bool>,
+    pub(super) retry_policy: Option<RetryPolicy>,
+    pub(super) headers: Headers,
+    pub(super) token: Option<String>,
+}
+
+impl fmt::Debug for RequestOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestOptions")
+            .field("timeout", &self.timeout)
+            .field("ratelimiter_disabled", &self.ratelimiter_disabled)
+            .field("retry_policy", &self.retry_policy)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RequestOptions {
+    /// Creates options which don't override anything.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time after which a request fails with [`HttpError::Timeout`],
+    /// including time spent waiting for ratelimits and retries.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets whether requests skip the ratelimiter.
+    ///
+    /// **Note**: See [`HttpBuilder::ratelimiter_disabled`] for why you should
+    /// not disable the ratelimiter without another form of rate limiting.
+    ///
+    /// [`HttpBuilder::ratelimiter_disabled`]: super::HttpBuilder::ratelimiter_disabled
+    #[must_use]
+    pub fn ratelimiter_disabled(mut self, ratelimiter_disabled: bool) -> Self {
+        self.ratelimiter_disabled = Some(ratelimiter_disabled);
+
+        self
+    }
+
+    /// Sets the policy for retrying failed requests.
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+
+        self
+    }
+
+    /// Adds a header to requests, replacing any header of the same name set by
+    /// the library.
+    #[must_use]
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+
+        self
+    }
+
+    /// Sets the token requests are made with, instead of the client's.
+    ///
+    /// As with [`Http::new`], the token is prefixed with `"Bot "` unless it
+    /// already is, or is a `"Bearer "` token. Ratelimits are tracked separately
+    /// for every token.
+    ///
+    /// [`Http::new`]: super::Http::new
+    #[must_use]
+    pub fn token(mut self, token: impl AsRef<str>) -> Self {
+        self.token = Some(parse_token(token));
+
+        self
+    }
+
+    /// Gets the timeout of requests, if set.
+    #[must_use]
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Gets the headers added to requests.
+    #[must_use]
+    pub fn get_headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Runs the future, applying these options to every request it makes
+    /// through [`Http`].
+    ///
+    /// Options of an outer scope are replaced, not merged. Tasks spawned by the
+    /// future do not inherit the options, see the [type docs] for details.
+    ///
+    /// [`Http`]: super::Http
+    /// [type docs]: Self
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        REQUEST_OPTIONS.scope(self, future).await
+    }
+
+    /// The options of the scope the current task is running in.
+    pub(super) fn current() -> Self {
+        REQUEST_OPTIONS.try_with(Clone::clone).unwrap_or_default()
+    }
+}
@@ -0,0 +1,246 @@
+//! Retrying requests that failed for transient reasons, such as Discord being
+//! briefly unavailable or a connection being reset.
+
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use super::{HttpError, LightMethod};
+use crate::internal::prelude::*;
+
+/// Passed to the [`Http::set_retry_callback`] callback before a failed request
+/// is retried.
+///
+/// [`Http::set_retry_callback`]: super::Http::set_retry_callback
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RetryInfo {
+    /// The attempt that failed, starting at 1.
+    pub attempt: u32,
+    /// The time waited before the next attempt.
+    pub delay: Duration,
+    pub method: LightMethod,
+    pub path: String,
+    /// The status code of the failed attempt, or `None` if no response was
+    /// received.
+    pub status: Option<StatusCode>,
+}
+
+/// Decides which failed requests are retried, how often and after how long.
+///
+/// A request is retried if its method is one of [`Self::methods`] and either
+/// no response was received due to a connection error or timeout, or the
+/// response had one of [`Self::statuses`]. Ratelimited requests are handled by
+/// the [`Ratelimiter`] instead.
+///
+/// Between attempts, the policy waits for an exponentially increasing delay,
+/// starting at the base delay and doubling up to the maximum delay. A random
+/// jitter of up to half the delay is subtracted, to avoid many clients
+/// retrying at the same time.
+///
+/// By default, requests are attempted up to 3 times, with delays starting at
+/// 500 milliseconds and capped at 8 seconds. Only the idempotent methods `GET`,
+/// `PUT` and `DELETE` are retried, on statuses 500, 502, 503 and 504.
+///
+/// # Examples
+///
+/// Retry up to 5 times, including on `POST` requests:
+///
+/// ```rust
+/// use serenity::http::{HttpBuilder, LightMethod, RetryPolicy};
+///
+/// let policy = RetryPolicy::new().max_attempts(5).methods(vec![
+///     LightMethod::Get,
+///     LightMethod::Put,
+///     LightMethod::Delete,
+///     LightMethod::Post,
+/// ]);
+///
+/// let http = HttpBuilder::new("token").retry_policy(policy).build();
+/// ```
+///
+/// [`Ratelimiter`]: super::ratelimiting::Ratelimiter
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    statuses: Vec<StatusCode>,
+    methods: Vec<LightMethod>,
+}
+
+impl RetryPolicy {
+    /// Creates the default policy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy which never retries.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Sets the number of times a request is attempted in total, including the
+    /// first attempt. A value of 1 disables retrying.
+    ///
+    /// **Note**: Values below 1 are treated as 1.
+    #[must_use]
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+
+        self
+    }
+
+    /// Sets the delay before the first retry, which doubles for every further
+    /// retry up to `max_delay`.
+    #[must_use]
+    pub fn backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay.max(base_delay);
+
+        self
+    }
+
+    /// Sets the response statuses a request is retried on.
+    #[must_use]
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = StatusCode>) -> Self {
+        self.statuses = statuses.into_iter().collect();
+
+        self
+    }
+
+    /// Sets the methods of requests which are retried.
+    ///
+    /// **Note**: Retrying requests which are not idempotent, such as `POST`
+    /// requests, may perform them twice if a response was lost.
+    #[must_use]
+    pub fn methods(mut self, methods: impl IntoIterator<Item = LightMethod>) -> Self {
+        self.methods = methods.into_iter().collect();
+
+        self
+    }
+
+    /// Gets the number of times a request is attempted in total.
+    #[must_use]
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Gets the response statuses a request is retried on.
+    #[must_use]
+    pub fn get_statuses(&self) -> &[StatusCode] {
+        &self.statuses
+    }
+
+    /// Gets the methods of requests which are retried.
+    #[must_use]
+    pub fn get_methods(&self) -> &[LightMethod] {
+        &self.methods
+    }
+
+    /// Whether the request should be retried after the given failed attempt,
+    /// which either received a response with the given status or failed with
+    /// the given error.
+    pub(crate) fn should_retry(
+        &self,
+        attempt: u32,
+        method: LightMethod,
+        outcome: StdResult<StatusCode, &Error>,
+    ) -> bool {
+        if attempt >= self.max_attempts || !self.methods.contains(&method) {
+            return false;
+        }
+
+        match outcome {
+            Ok(status) => self.statuses.contains(&status),
+            Err(Error::Http(e)) => match &**e {
+                HttpError::Request(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+                _ => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// The delay before retrying after the given failed attempt.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        let delay =
+            self.base_delay.checked_mul(factor).map_or(self.max_delay, |d| d.min(self.max_delay));
+
+        delay.saturating_sub(jitter(delay / 2))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            statuses: vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            methods: vec![LightMethod::Get, LightMethod::Put, LightMethod::Delete],
+        }
+    }
+}
+
+/// A random duration of at most `max`.
+fn jitter(max: Duration) -> Duration {
+    // Every `RandomState` is seeded differently, which is random enough to
+    // spread out retries.
+    let random = RandomState::new().build_hasher().finish();
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+
+    Duration::from_nanos(random.checked_rem(nanos.saturating_add(1)).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use super::RetryPolicy;
+    use crate::http::LightMethod;
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy::new();
+
+        assert!(policy.should_retry(1, LightMethod::Get, Ok(StatusCode::BAD_GATEWAY)));
+        assert!(policy.should_retry(2, LightMethod::Delete, Ok(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(!policy.should_retry(3, LightMethod::Get, Ok(StatusCode::BAD_GATEWAY)));
+        assert!(!policy.should_retry(1, LightMethod::Post, Ok(StatusCode::BAD_GATEWAY)));
+        assert!(!policy.should_retry(1, LightMethod::Get, Ok(StatusCode::NOT_FOUND)));
+        assert!(!RetryPolicy::disabled().should_retry(
+            1,
+            LightMethod::Get,
+            Ok(StatusCode::BAD_GATEWAY)
+        ));
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy::new().backoff(Duration::from_secs(1), Duration::from_secs(3));
+
+        for _ in 0..32 {
+            let first = policy.delay(1);
+            assert!(first >= Duration::from_millis(500) && first <= Duration::from_secs(1));
+
+            let second = policy.delay(2);
+            assert!(second >= Duration::from_secs(1) && second <= Duration::from_secs(2));
+
+            let capped = policy.delay(10);
+            assert!(capped >= Duration::from_millis(1500) && capped <= Duration::from_secs(3));
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::{Display, Write};
 
 use super::LightMethod;
 use crate::constants;
+use crate::model::Timestamp;
 
 /// A representation of all routes registered within the library. These are safe
 /// and memory-efficient representations of each path that functions exist for
@@ -375,6 +376,10 @@ pub enum Route {
     GuildsIdThreadsActive,
     /// Route for the `/invites/:code` path.
     InvitesCode,
+    /// Route for the `/oauth2/token` path.
+    Oauth2Token,
+    /// Route for the `/oauth2/token/revoke` path.
+    Oauth2TokenRevoke,
     /// Route for the `/sticker-packs` path.
     StickerPacks,
     /// Route for the `/stickers/:sticker_id` path.
@@ -470,6 +475,87 @@ pub enum Route {
 }
 
 impl Route {
+    /// The ID of the route's major parameter, such as the channel ID of
+    /// [`Self::ChannelsIdMessages`], if it has one.
+    ///
+    /// Routes sharing a ratelimit bucket are only ratelimited together if their
+    /// major parameters are equal.
+    #[must_use]
+    pub fn major_parameter(&self) -> Option<u64> {
+        match self {
+            Self::ChannelsId(id)
+            | Self::ChannelsIdInvites(id)
+            | Self::ChannelsIdMessages(id)
+            | Self::ChannelsIdMessagesBulkDelete(id)
+            | Self::ChannelsIdMessagesIdAck(id)
+            | Self::ChannelsIdMessagesId(_, id)
+            | Self::ChannelsIdMessagesIdReactions(id)
+            | Self::ChannelsIdMessagesIdReactionsUserIdType(id)
+            | Self::ChannelsIdPermissionsOverwriteId(id)
+            | Self::ChannelsIdPins(id)
+            | Self::ChannelsIdPinsMessageId(id)
+            | Self::ChannelsIdCrosspostsMessageId(id)
+            | Self::ChannelsIdTyping(id)
+            | Self::ChannelsIdWebhooks(id)
+            | Self::ChannelsIdMessagesIdThreads(id)
+            | Self::ChannelsIdThreads(id)
+            | Self::ChannelsIdThreadMembersMe(id)
+            | Self::ChannelsIdThreadMembersUserId(id)
+            | Self::ChannelsIdThreadMembers(id)
+            | Self::ChannelsIdArchivedPublicThreads(id)
+            | Self::ChannelsIdArchivedPrivateThreads(id)
+            | Self::ChannelsIdMeJoindedArchivedPrivateThreads(id)
+            | Self::FollowNewsChannel(id)
+            | Self::GuildsId(id)
+            | Self::GuildsIdAutoModRules(id)
+            | Self::GuildsIdAutoModRulesId(id)
+            | Self::GuildsIdBans(id)
+            | Self::GuildsIdAuditLogs(id)
+            | Self::GuildsIdBansUserId(id)
+            | Self::GuildsIdChannels(id)
+            | Self::GuildsIdWidget(id)
+            | Self::GuildsIdPreview(id)
+            | Self::GuildsIdEmojis(id)
+            | Self::GuildsIdEmojisId(id)
+            | Self::GuildsIdIntegrations(id)
+            | Self::GuildsIdIntegrationsId(id)
+            | Self::GuildsIdIntegrationsIdSync(id)
+            | Self::GuildsIdInvites(id)
+            | Self::GuildsIdMembers(id)
+            | Self::GuildsIdMembersId(id)
+            | Self::GuildsIdMembersIdRolesId(id)
+            | Self::GuildsIdMembersMe(id)
+            | Self::GuildsIdMembersMeNick(id)
+            | Self::GuildsIdMembersSearch(id)
+            | Self::GuildsIdPrune(id)
+            | Self::GuildsIdRegions(id)
+            | Self::GuildsIdRoles(id)
+            | Self::GuildsIdRolesId(id)
+            | Self::GuildsIdScheduledEvents(id)
+            | Self::GuildsIdScheduledEventsId(id)
+            | Self::GuildsIdScheduledEventsIdUsers(id)
+            | Self::GuildsIdStickers(id)
+            | Self::GuildsIdStickersId(id)
+            | Self::GuildsIdVanityUrl(id)
+            | Self::GuildsIdVoiceStates(id)
+            | Self::GuildsIdVoiceStatesMe(id)
+            | Self::GuildsIdWebhooks(id)
+            | Self::GuildsIdWelcomeScreen(id)
+            | Self::WebhooksId(id)
+            | Self::WebhooksIdMessagesId(id)
+            | Self::WebhooksApplicationId(id)
+            | Self::InteractionsId(id)
+            | Self::ApplicationsIdCommands(id)
+            | Self::ApplicationsIdCommandsId(id)
+            | Self::ApplicationsIdGuildsIdCommands(id)
+            | Self::ApplicationsIdGuildsIdCommandsPermissions(id)
+            | Self::ApplicationsIdGuildsIdCommandIdPermissions(id)
+            | Self::ApplicationsIdGuildsIdCommandsId(id)
+            | Self::StageInstancesChannelId(id) => Some(*id),
+            _ => None,
+        }
+    }
+
     #[must_use]
     pub fn channel(channel_id: u64) -> String {
         api!("/channels/{}", channel_id)
@@ -621,7 +707,7 @@ impl Route {
         before: Option<u64>,
         limit: Option<u64>,
     ) -> String {
-        let mut s = api!("/channels/{}/threads/archived/public", channel_id);
+        let mut s = api!("/channels/{}/threads/archived/public?", channel_id);
 
         if let Some(id) = before {
             write!(s, "&before={}", id).unwrap();
@@ -634,13 +720,32 @@ impl Route {
         s
     }
 
+    #[must_use]
+    pub fn channel_archived_public_threads_by_timestamp(
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    ) -> String {
+        let mut s = api!("/channels/{}/threads/archived/public?", channel_id);
+
+        if let Some(timestamp) = before {
+            write!(s, "&before={}", timestamp).unwrap();
+        }
+
+        if let Some(limit) = limit {
+            write!(s, "&limit={}", limit).unwrap();
+        }
+
+        s
+    }
+
     #[must_use]
     pub fn channel_archived_private_threads(
         channel_id: u64,
         before: Option<u64>,
         limit: Option<u64>,
     ) -> String {
-        let mut s = api!("/channels/{}/threads/archived/private", channel_id);
+        let mut s = api!("/channels/{}/threads/archived/private?", channel_id);
 
         if let Some(id) = before {
             write!(s, "&before={}", id).unwrap();
@@ -653,13 +758,32 @@ impl Route {
         s
     }
 
+    #[must_use]
+    pub fn channel_archived_private_threads_by_timestamp(
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    ) -> String {
+        let mut s = api!("/channels/{}/threads/archived/private?", channel_id);
+
+        if let Some(timestamp) = before {
+            write!(s, "&before={}", timestamp).unwrap();
+        }
+
+        if let Some(limit) = limit {
+            write!(s, "&limit={}", limit).unwrap();
+        }
+
+        s
+    }
+
     #[must_use]
     pub fn channel_joined_private_threads(
         channel_id: u64,
         before: Option<u64>,
         limit: Option<u64>,
     ) -> String {
-        let mut s = api!("/channels/{}/users/@me/threads/archived/private", channel_id);
+        let mut s = api!("/channels/{}/users/@me/threads/archived/private?", channel_id);
 
         if let Some(id) = before {
             write!(s, "&before={}", id).unwrap();
@@ -751,6 +875,30 @@ impl Route {
         api!("/guilds/{}/bans", guild_id)
     }
 
+    #[must_use]
+    pub fn guild_bans_optioned(
+        guild_id: u64,
+        after: Option<u64>,
+        before: Option<u64>,
+        limit: Option<u64>,
+    ) -> String {
+        let mut s = api!("/guilds/{}/bans?", guild_id);
+
+        if let Some(after) = after {
+            write!(s, "&after={}", after).unwrap();
+        }
+
+        if let Some(before) = before {
+            write!(s, "&before={}", before).unwrap();
+        }
+
+        if let Some(limit) = limit {
+            write!(s, "&limit={}", limit).unwrap();
+        }
+
+        s
+    }
+
     #[must_use]
     pub fn guild_channels(guild_id: u64) -> String {
         api!("/guilds/{}/channels", guild_id)
@@ -985,6 +1133,16 @@ impl Route {
         api!("/oauth2/applications/@me")
     }
 
+    #[must_use]
+    pub fn oauth2_token() -> &'static str {
+        api!("/oauth2/token")
+    }
+
+    #[must_use]
+    pub fn oauth2_token_revoke() -> &'static str {
+        api!("/oauth2/token/revoke")
+    }
+
     #[must_use]
     pub fn private_channel() -> &'static str {
         api!("/users/@me/channels")
@@ -1275,6 +1433,7 @@ pub enum RouteInfo<'a> {
         channel_id: u64,
         target_id: u64,
     },
+    CreateOAuth2Token,
     CreatePrivateChannel,
     CreateReaction {
         channel_id: u64,
@@ -1538,6 +1697,12 @@ pub enum RouteInfo<'a> {
     GetBans {
         guild_id: u64,
     },
+    GetBansOptioned {
+        guild_id: u64,
+        after: Option<u64>,
+        before: Option<u64>,
+        limit: Option<u64>,
+    },
     GetBotGateway,
     GetChannel {
         channel_id: u64,
@@ -1562,11 +1727,21 @@ pub enum RouteInfo<'a> {
         before: Option<u64>,
         limit: Option<u64>,
     },
+    GetChannelArchivedPublicThreadsByTimestamp {
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    },
     GetChannelArchivedPrivateThreads {
         channel_id: u64,
         before: Option<u64>,
         limit: Option<u64>,
     },
+    GetChannelArchivedPrivateThreadsByTimestamp {
+        channel_id: u64,
+        before: Option<Timestamp>,
+        limit: Option<u64>,
+    },
     GetChannelJoinedPrivateArchivedThreads {
         channel_id: u64,
         before: Option<u64>,
@@ -1772,6 +1947,7 @@ pub enum RouteInfo<'a> {
         days: u64,
         guild_id: u64,
     },
+    RevokeOAuth2Token,
     StartIntegrationSync {
         guild_id: u64,
         integration_id: u64,
@@ -1945,6 +2121,9 @@ impl<'a> RouteInfo<'a> {
                 Route::ChannelsIdPermissionsOverwriteId(channel_id),
                 Cow::from(Route::channel_permission(channel_id, target_id)),
             ),
+            RouteInfo::CreateOAuth2Token => {
+                (LightMethod::Post, Route::Oauth2Token, Cow::from(Route::oauth2_token()))
+            },
             RouteInfo::CreatePrivateChannel => (
                 LightMethod::Post,
                 Route::UsersMeChannels,
@@ -2471,6 +2650,16 @@ impl<'a> RouteInfo<'a> {
                 Route::GuildsIdBans(guild_id),
                 Cow::from(Route::guild_bans(guild_id)),
             ),
+            RouteInfo::GetBansOptioned {
+                guild_id,
+                after,
+                before,
+                limit,
+            } => (
+                LightMethod::Get,
+                Route::GuildsIdBans(guild_id),
+                Cow::from(Route::guild_bans_optioned(guild_id, after, before, limit)),
+            ),
             RouteInfo::GetBotGateway => {
                 (LightMethod::Get, Route::GatewayBot, Cow::from(Route::gateway_bot()))
             },
@@ -2525,6 +2714,17 @@ impl<'a> RouteInfo<'a> {
                 Route::ChannelsIdArchivedPublicThreads(channel_id),
                 Cow::from(Route::channel_archived_public_threads(channel_id, before, limit)),
             ),
+            RouteInfo::GetChannelArchivedPublicThreadsByTimestamp {
+                channel_id,
+                before,
+                limit,
+            } => (
+                LightMethod::Get,
+                Route::ChannelsIdArchivedPublicThreads(channel_id),
+                Cow::from(Route::channel_archived_public_threads_by_timestamp(
+                    channel_id, before, limit,
+                )),
+            ),
             RouteInfo::GetChannelArchivedPrivateThreads {
                 channel_id,
                 before,
@@ -2534,6 +2734,17 @@ impl<'a> RouteInfo<'a> {
                 Route::ChannelsIdArchivedPrivateThreads(channel_id),
                 Cow::from(Route::channel_archived_private_threads(channel_id, before, limit)),
             ),
+            RouteInfo::GetChannelArchivedPrivateThreadsByTimestamp {
+                channel_id,
+                before,
+                limit,
+            } => (
+                LightMethod::Get,
+                Route::ChannelsIdArchivedPrivateThreads(channel_id),
+                Cow::from(Route::channel_archived_private_threads_by_timestamp(
+                    channel_id, before, limit,
+                )),
+            ),
             RouteInfo::GetChannelJoinedPrivateArchivedThreads {
                 channel_id,
                 before,
@@ -2964,6 +3175,11 @@ impl<'a> RouteInfo<'a> {
                 Route::GuildsIdMembersIdRolesId(guild_id),
                 Cow::from(Route::guild_member_role(guild_id, user_id, role_id)),
             ),
+            RouteInfo::RevokeOAuth2Token => (
+                LightMethod::Post,
+                Route::Oauth2TokenRevoke,
+                Cow::from(Route::oauth2_token_revoke()),
+            ),
             RouteInfo::SearchGuildMembers {
                 guild_id,
                 query,
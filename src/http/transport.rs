@@ -0,0 +1,382 @@
+//! The transport which [`Http`] and the [`Ratelimiter`] send requests
+//! through, and a [`MockTransport`] to test code using them without a
+//! connection to Discord.
+//!
+//! [`Http`]: super::Http
+//! [`Ratelimiter`]: super::ratelimiting::Ratelimiter
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, Request, Response, ResponseBuilderExt, StatusCode, Url};
+use serde::Serialize;
+
+use super::routing::Route;
+use super::LightMethod;
+use crate::internal::prelude::*;
+use crate::json::{self, Value};
+
+/// A request about to be sent through a [`HttpTransport`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TransportRequest {
+    /// The method of the request.
+    pub method: LightMethod,
+    /// The route the request is ratelimited by.
+    pub route: Route,
+    /// The request itself, including its URL, headers and body.
+    pub request: Request,
+}
+
+/// Sends requests to Discord, or wherever else they should go.
+///
+/// [`reqwest::Client`] implements this to send requests over the network,
+/// which is what [`Http`] uses by default. To use another transport, pass it
+/// to [`HttpBuilder::transport`].
+///
+/// [`Http`]: super::Http
+/// [`HttpBuilder::transport`]: super::HttpBuilder::transport
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// Sends the request, returning the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no response could be received.
+    async fn execute(&self, request: TransportRequest) -> Result<Response>;
+}
+
+#[async_trait]
+impl HttpTransport for Client {
+    async fn execute(&self, request: TransportRequest) -> Result<Response> {
+        Ok(Client::execute(self, request.request).await?)
+    }
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MockRequest {
+    /// The method of the request.
+    pub method: LightMethod,
+    /// The route the request is ratelimited by.
+    pub route: Route,
+    /// The URL of the request.
+    pub url: Url,
+    /// The headers of the request.
+    pub headers: HeaderMap,
+    /// The body of the request, which is `None` for requests without a body
+    /// and multipart requests.
+    pub body: Option<Vec<u8>>,
+}
+
+impl MockRequest {
+    /// Deserializes the body of the request as JSON.
+    ///
+    /// Returns `None` if the request has no body, or it is not valid JSON.
+    #[must_use]
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice::<serde_json::Value>(self.body.as_ref()?)
+            .ok()
+            .and_then(|value| json::to_value(value).ok())
+    }
+}
+
+type RequestMatcher = Box<dyn Fn(&MockRequest) -> bool + Send + Sync>;
+
+/// Describes requests a [`MockTransport`] answers with a [`MockResponse`].
+///
+/// # Examples
+///
+/// Expect a message with the content `"Hello"` to be sent to channel 1:
+///
+/// ```rust
+/// use serenity::http::routing::Route;
+/// use serenity::http::transport::Expectation;
+/// use serenity::http::LightMethod;
+///
+/// let expectation = Expectation::new(LightMethod::Post, Route::ChannelsIdMessages(1))
+///     .matching(|request| request.json().map_or(false, |body| body["content"] == "Hello"));
+/// ```
+pub struct Expectation {
+    method: Option<LightMethod>,
+    route: Option<Route>,
+    body: Option<Value>,
+    matchers: Vec<RequestMatcher>,
+    times: usize,
+}
+
+impl Expectation {
+    /// Expects a request with the given method to the given route.
+    #[must_use]
+    pub fn new(method: LightMethod, route: Route) -> Self {
+        Self {
+            method: Some(method),
+            route: Some(route),
+            ..Self::any()
+        }
+    }
+
+    /// Expects any request.
+    #[must_use]
+    pub fn any() -> Self {
+        Self {
+            method: None,
+            route: None,
+            body: None,
+            matchers: Vec::new(),
+            times: 1,
+        }
+    }
+
+    /// Only matches requests whose body is equal to the given JSON.
+    #[must_use]
+    pub fn json_body(mut self, body: Value) -> Self {
+        self.body = Some(body);
+
+        self
+    }
+
+    /// Only matches requests for which the function returns `true`.
+    #[must_use]
+    pub fn matching<F>(mut self, matcher: F) -> Self
+    where
+        F: Fn(&MockRequest) -> bool + Send + Sync + 'static,
+    {
+        self.matchers.push(Box::new(matcher));
+
+        self
+    }
+
+    /// Sets the number of matching requests that are answered, 1 by default.
+    #[must_use]
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = times;
+
+        self
+    }
+
+    fn matches(&self, request: &MockRequest) -> bool {
+        self.method.map_or(true, |method| method == request.method)
+            && self.route.map_or(true, |route| route == request.route)
+            && self.body.as_ref().map_or(true, |body| request.json().as_ref() == Some(body))
+            && self.matchers.iter().all(|matcher| matcher(request))
+    }
+}
+
+impl fmt::Debug for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Expectation")
+            .field("method", &self.method)
+            .field("route", &self.route)
+            .field("body", &self.body)
+            .field("matchers", &self.matchers.len())
+            .field("times", &self.times)
+            .finish()
+    }
+}
+
+/// A canned response returned by a [`MockTransport`].
+#[derive(Clone, Debug)]
+pub struct MockResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// Creates an empty response with the given status.
+    #[must_use]
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Creates a `429 Too Many Requests` response for a ratelimit which resets
+    /// after the given number of seconds.
+    #[must_use]
+    pub fn ratelimited(retry_after: f64) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS)
+            .header("retry-after", &retry_after.to_string())
+            .header("x-ratelimit-remaining", "0")
+            .header("x-ratelimit-reset-after", &retry_after.to_string())
+            .header("x-ratelimit-scope", "user")
+            .json(&json::json!({
+                "message": "You are being rate limited.",
+                "retry_after": retry_after,
+                "global": false,
+            }))
+    }
+
+    /// Sets the body to the given value serialized as JSON.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be serialized.
+    #[must_use]
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        self.body = serde_json::to_vec(body).expect("response body must be serializable");
+        self.headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        self
+    }
+
+    /// Sets the body to the given bytes.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+
+        self
+    }
+
+    /// Adds a header, such as `x-ratelimit-remaining`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name or value is not a valid header name or value.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::from_bytes(name.as_bytes()).expect("invalid header name");
+        let value = HeaderValue::from_str(value).expect("invalid header value");
+        self.headers.insert(name, value);
+
+        self
+    }
+
+    fn into_response(self, url: Url) -> Response {
+        let mut response = http_crate::Response::builder().status(self.status).url(url);
+
+        if let Some(headers) = response.headers_mut() {
+            headers.extend(self.headers);
+        }
+
+        response.body(self.body).expect("status and headers are valid").into()
+    }
+}
+
+/// A [`HttpTransport`] answering requests with canned responses, to test code
+/// making requests without a connection to Discord.
+///
+/// Responses are registered along with an [`Expectation`] describing the
+/// requests they answer. A request is answered by the first registered
+/// expectation it matches, which is removed once it answered as many requests
+/// as it expects. All requests are recorded and can be inspected via
+/// [`Self::requests`].
+///
+/// # Panics
+///
+/// Sending a request that no expectation matches panics.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use serenity::http::routing::Route;
+/// use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+/// use serenity::http::{HttpBuilder, LightMethod, StatusCode};
+///
+/// # async fn run() -> serenity::Result<()> {
+/// let transport = Arc::new(MockTransport::new());
+/// transport.expect(
+///     Expectation::new(LightMethod::Post, Route::ChannelsIdTyping(1)),
+///     MockResponse::new(StatusCode::NO_CONTENT),
+/// );
+///
+/// let http = HttpBuilder::new("token").transport(transport.clone()).build();
+/// http.broadcast_typing(1).await?;
+///
+/// assert_eq!(transport.requests()[0].url.path(), "/api/v10/channels/1/typing");
+/// assert_eq!(transport.pending(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    expectations: Mutex<VecDeque<(Expectation, MockResponse)>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl MockTransport {
+    /// Creates a transport without any expectations.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a response for requests matching the expectation.
+    pub fn expect(&self, expectation: Expectation, response: MockResponse) {
+        self.expectations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push_back((expectation, response));
+    }
+
+    /// The requests received so far, in the order they were sent.
+    #[must_use]
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// The number of registered responses which have not been returned yet.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.expectations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(expectation, _)| expectation.times)
+            .sum()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for MockTransport {
+    async fn execute(&self, request: TransportRequest) -> Result<Response> {
+        let TransportRequest {
+            method,
+            route,
+            request,
+        } = request;
+
+        let request = MockRequest {
+            method,
+            route,
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request.body().and_then(reqwest::Body::as_bytes).map(<[u8]>::to_vec),
+        };
+
+        let response = {
+            let mut expectations = self.expectations.lock().unwrap_or_else(PoisonError::into_inner);
+
+            let index = expectations
+                .iter()
+                .position(|(expectation, _)| expectation.matches(&request))
+                .unwrap_or_else(|| {
+                    panic!("MockTransport received an unexpected request: {:?}", request)
+                });
+
+            let (expectation, response) = &mut expectations[index];
+            expectation.times = expectation.times.saturating_sub(1);
+            let response = response.clone();
+
+            if expectation.times == 0 {
+                expectations.remove(index);
+            }
+
+            response
+        };
+
+        let url = request.url.clone();
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).push(request);
+
+        Ok(response.into_response(url))
+    }
+}
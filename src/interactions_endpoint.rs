@@ -0,0 +1,605 @@
+//! An endpoint receiving interactions over HTTP, for applications which set an
+//! [Interactions Endpoint URL] instead of receiving interactions via the
+//! gateway.
+//!
+//! Discord signs every request sent to the endpoint. The [`Verifier`] checks
+//! the signature against the public key of the application, which can be used
+//! on its own when integrating with another web server. The
+//! [`InteractionsEndpoint`] is a complete server built on top of it, which
+//! answers [`Interaction::Ping`]s, replies to all other interactions with the
+//! response of an [`InteractionHandler`] and sends them on to
+//! [`EventHandler::interaction_create`].
+//!
+//! [Interactions Endpoint URL]: https://discord.com/developers/docs/interactions/receiving-and-responding#receiving-an-interaction
+
+use std::convert::{Infallible, TryFrom};
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use ed25519_dalek::{PublicKey, Signature, Verifier as _, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use futures::channel::mpsc;
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use typemap_rev::{TypeMap, TypeMapKey};
+
+use crate::builder::CreateInteractionResponse;
+#[cfg(feature = "cache")]
+use crate::cache::Cache;
+use crate::client::{Context, EventHandler};
+use crate::http::Http;
+use crate::internal::prelude::*;
+use crate::internal::tokio::spawn_named;
+use crate::json;
+use crate::model::application::interaction::{Interaction, InteractionResponseType};
+
+/// The header containing the signature of a request.
+pub const SIGNATURE_HEADER: &str = "X-Signature-Ed25519";
+/// The header containing the timestamp of a request, which is signed along
+/// with its body.
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// The largest request body the [`InteractionsEndpoint`] reads, in bytes.
+pub const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+/// How far the timestamp of a request may be from the current time before it
+/// is rejected, which keeps captured requests from being replayed later.
+pub const MAX_TIMESTAMP_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// An error verifying a request or running an [`InteractionsEndpoint`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InteractionsEndpointError {
+    /// The public key is not a valid hex-encoded Ed25519 public key.
+    InvalidPublicKey,
+    /// The signature is malformed or does not match the request.
+    InvalidSignature,
+    /// The timestamp is malformed, or further than [`MAX_TIMESTAMP_SKEW`] from
+    /// the current time.
+    InvalidTimestamp,
+    /// An error from the HTTP server.
+    Server(hyper::Error),
+}
+
+impl fmt::Display for InteractionsEndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPublicKey => f.write_str("Invalid Ed25519 public key"),
+            Self::InvalidSignature => f.write_str("Invalid request signature"),
+            Self::InvalidTimestamp => f.write_str("Invalid or expired request timestamp"),
+            Self::Server(inner) => fmt::Display::fmt(inner, f),
+        }
+    }
+}
+
+impl StdError for InteractionsEndpointError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Server(inner) => Some(inner),
+            _ => None,
+        }
+    }
+}
+
+impl From<hyper::Error> for InteractionsEndpointError {
+    fn from(e: hyper::Error) -> Self {
+        Self::Server(e)
+    }
+}
+
+/// The handler responding to interactions received by an
+/// [`InteractionsEndpoint`].
+///
+/// Discord expects the response to an interaction as the reply to its request,
+/// within three seconds of sending it.
+#[async_trait]
+pub trait InteractionHandler: Send + Sync {
+    /// Creates the response to an interaction, which is sent as the reply to
+    /// its request.
+    ///
+    /// Returning [`None`] defers the response, see [`InteractionsEndpoint`].
+    ///
+    /// **Note**: Files can't be sent in the reply. Attach them to a follow-up
+    /// message or an edit of the original response instead.
+    async fn respond(
+        &self,
+        ctx: Context,
+        interaction: &Interaction,
+    ) -> Option<CreateInteractionResponse<'static>>;
+}
+
+/// Verifies the signatures of requests sent to an interactions endpoint.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use serenity::interactions_endpoint::Verifier;
+///
+/// # fn run(signature: &str, timestamp: &str, body: &[u8]) -> serenity::Result<()> {
+/// let verifier = Verifier::new("the public key of the application")?;
+///
+/// if verifier.verify(signature, timestamp, body).is_err() {
+///     // Respond with `401 Unauthorized`.
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Verifier {
+    public_key: PublicKey,
+}
+
+impl Verifier {
+    /// Creates a verifier from the hex-encoded public key shown on the general
+    /// information page of the application.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::InvalidPublicKey`] if the key is
+    /// not a valid Ed25519 public key.
+    pub fn new(public_key: &str) -> StdResult<Self, InteractionsEndpointError> {
+        let mut bytes = [0; PUBLIC_KEY_LENGTH];
+        decode_hex(public_key, &mut bytes).ok_or(InteractionsEndpointError::InvalidPublicKey)?;
+
+        let public_key = PublicKey::from_bytes(&bytes)
+            .map_err(|_| InteractionsEndpointError::InvalidPublicKey)?;
+
+        Ok(Self {
+            public_key,
+        })
+    }
+
+    /// Verifies the signature of a request, given the values of its
+    /// [`SIGNATURE_HEADER`] and [`TIMESTAMP_HEADER`] and its raw body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::InvalidTimestamp`] if the timestamp
+    /// is not within [`MAX_TIMESTAMP_SKEW`] of the current time.
+    ///
+    /// Returns [`InteractionsEndpointError::InvalidSignature`] if the signature
+    /// is malformed or was not made over the timestamp and body by the
+    /// application's key.
+    pub fn verify(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        body: &[u8],
+    ) -> StdResult<(), InteractionsEndpointError> {
+        let sent =
+            timestamp.parse::<u64>().map_err(|_| InteractionsEndpointError::InvalidTimestamp)?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        if now.max(sent) - now.min(sent) > MAX_TIMESTAMP_SKEW.as_secs() {
+            return Err(InteractionsEndpointError::InvalidTimestamp);
+        }
+
+        let mut bytes = [0; SIGNATURE_LENGTH];
+        decode_hex(signature, &mut bytes).ok_or(InteractionsEndpointError::InvalidSignature)?;
+
+        let signature = Signature::try_from(&bytes[..])
+            .map_err(|_| InteractionsEndpointError::InvalidSignature)?;
+
+        let mut message = Vec::with_capacity(timestamp.len() + body.len());
+        message.extend_from_slice(timestamp.as_bytes());
+        message.extend_from_slice(body);
+
+        self.public_key
+            .verify(&message, &signature)
+            .map_err(|_| InteractionsEndpointError::InvalidSignature)
+    }
+}
+
+/// Decodes a hex string into `out`, failing unless it is exactly as long.
+fn decode_hex(input: &str, out: &mut [u8]) -> Option<()> {
+    let input = input.as_bytes();
+
+    if input.len() != out.len() * 2 {
+        return None;
+    }
+
+    for (byte, pair) in out.iter_mut().zip(input.chunks_exact(2)) {
+        let high = char::from(pair[0]).to_digit(16)?;
+        let low = char::from(pair[1]).to_digit(16)?;
+
+        // Both digits are below 16, so this can't truncate.
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            *byte = (high << 4 | low) as u8;
+        }
+    }
+
+    Some(())
+}
+
+/// A server receiving interactions over HTTP.
+///
+/// Requests with an invalid signature or timestamp are rejected with
+/// `401 Unauthorized`, and those with a body larger than [`MAX_BODY_SIZE`] with
+/// `413 Payload Too Large`.
+/// [`Interaction::Ping`]s are answered with a [`Pong`], while all other
+/// interactions are answered with the response created by the
+/// [`InteractionHandler`].
+///
+/// If there is no interaction handler, or it returns [`None`], the response is
+/// deferred: application commands and modal submits are answered with a
+/// [`DeferredChannelMessageWithSource`], message components with a
+/// [`DeferredUpdateMessage`], and autocompletes with no choices.
+///
+/// Afterwards, the interaction is sent on to
+/// [`EventHandler::interaction_create`]. As it has already been responded to,
+/// the event handler can't create another response, but must use the
+/// interaction's webhook via [`Http`] instead, for example with
+/// [`ApplicationCommandInteraction::edit_original_interaction_response`] or
+/// [`ApplicationCommandInteraction::create_followup_message`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use serenity::async_trait;
+/// use serenity::builder::CreateInteractionResponse;
+/// use serenity::interactions_endpoint::{InteractionHandler, InteractionsEndpoint};
+/// use serenity::model::application::interaction::Interaction;
+/// use serenity::prelude::*;
+///
+/// struct Handler;
+///
+/// #[async_trait]
+/// impl InteractionHandler for Handler {
+///     async fn respond(
+///         &self,
+///         _: Context,
+///         interaction: &Interaction,
+///     ) -> Option<CreateInteractionResponse<'static>> {
+///         let command = match interaction {
+///             Interaction::ApplicationCommand(command) => command,
+///             _ => return None,
+///         };
+///
+///         let mut response = CreateInteractionResponse::default();
+///         response.interaction_response_data(|d| d.content(format!("Ran {}", command.data.name)));
+///
+///         Some(response)
+///     }
+/// }
+///
+/// # async fn run() -> serenity::Result<()> {
+/// let public_key = std::env::var("DISCORD_PUBLIC_KEY").expect("public key");
+/// let token = std::env::var("DISCORD_TOKEN").expect("token");
+///
+/// InteractionsEndpoint::new(&public_key, &token)?
+///     .interaction_handler(Handler)
+///     .serve(([0, 0, 0, 0], 8080))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Pong`]: InteractionResponseType::Pong
+/// [`DeferredChannelMessageWithSource`]: InteractionResponseType::DeferredChannelMessageWithSource
+/// [`DeferredUpdateMessage`]: InteractionResponseType::DeferredUpdateMessage
+/// [`ApplicationCommandInteraction::edit_original_interaction_response`]: crate::model::application::interaction::application_command::ApplicationCommandInteraction::edit_original_interaction_response
+/// [`ApplicationCommandInteraction::create_followup_message`]: crate::model::application::interaction::application_command::ApplicationCommandInteraction::create_followup_message
+pub struct InteractionsEndpoint {
+    verifier: Verifier,
+    data: Arc<RwLock<TypeMap>>,
+    http: Arc<Http>,
+    #[cfg(feature = "cache")]
+    cache: Arc<Cache>,
+    event_handler: Option<Arc<dyn EventHandler>>,
+    interaction_handler: Option<Arc<dyn InteractionHandler>>,
+}
+
+impl InteractionsEndpoint {
+    /// Creates an endpoint verifying requests against the hex-encoded public
+    /// key of the application. The `token` will automatically be prefixed
+    /// "Bot " if not already.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::InvalidPublicKey`] if the key is
+    /// not a valid Ed25519 public key.
+    pub fn new(public_key: &str, token: impl AsRef<str>) -> Result<Self> {
+        Self::new_with_http(public_key, Http::new(token.as_ref()))
+    }
+
+    /// Creates an endpoint with a pre-configured [`Http`] client.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::InvalidPublicKey`] if the key is
+    /// not a valid Ed25519 public key.
+    pub fn new_with_http(public_key: &str, http: Http) -> Result<Self> {
+        Ok(Self {
+            verifier: Verifier::new(public_key)?,
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            http: Arc::new(http),
+            #[cfg(feature = "cache")]
+            cache: Arc::new(Cache::new()),
+            event_handler: None,
+            interaction_handler: None,
+        })
+    }
+
+    /// Sets the event handler that interactions are sent to.
+    #[must_use]
+    pub fn event_handler<H: EventHandler + 'static>(self, event_handler: H) -> Self {
+        self.event_handler_arc(Arc::new(event_handler))
+    }
+
+    /// Sets the event handler that interactions are sent to, from an [`Arc`].
+    #[must_use]
+    pub fn event_handler_arc(mut self, event_handler: Arc<dyn EventHandler>) -> Self {
+        self.event_handler = Some(event_handler);
+
+        self
+    }
+
+    /// Sets the handler responding to interactions.
+    #[must_use]
+    pub fn interaction_handler<H: InteractionHandler + 'static>(
+        self,
+        interaction_handler: H,
+    ) -> Self {
+        self.interaction_handler_arc(Arc::new(interaction_handler))
+    }
+
+    /// Sets the handler responding to interactions, from an [`Arc`].
+    #[must_use]
+    pub fn interaction_handler_arc(
+        mut self,
+        interaction_handler: Arc<dyn InteractionHandler>,
+    ) -> Self {
+        self.interaction_handler = Some(interaction_handler);
+
+        self
+    }
+
+    /// Sets the data available via [`Context::data`], for example to share it
+    /// with a [`Client`] running alongside the endpoint.
+    ///
+    /// [`Client`]: crate::Client
+    #[must_use]
+    pub fn data(mut self, data: Arc<RwLock<TypeMap>>) -> Self {
+        self.data = data;
+
+        self
+    }
+
+    /// Inserts a single value into the data available via [`Context::data`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data is currently shared, for example after being passed
+    /// to [`Self::data`].
+    #[must_use]
+    pub fn type_map_insert<T: TypeMapKey>(mut self, value: T::Value) -> Self {
+        Arc::get_mut(&mut self.data)
+            .expect("data must not be shared when inserting into it")
+            .get_mut()
+            .insert::<T>(value);
+
+        self
+    }
+
+    /// Sets the cache available via [`Context::cache`]. As the endpoint does
+    /// not receive gateway events, it remains empty unless shared with a
+    /// [`Client`].
+    ///
+    /// [`Client`]: crate::Client
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn cache(mut self, cache: Arc<Cache>) -> Self {
+        self.cache = cache;
+
+        self
+    }
+
+    /// Binds to the given address and serves requests until an error occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::Server`] if binding to the address
+    /// fails or the server encounters an error.
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<()> {
+        let builder = Server::try_bind(&addr.into()).map_err(InteractionsEndpointError::from)?;
+
+        self.run(builder).await
+    }
+
+    /// Serves requests accepted from an already bound listener until an error
+    /// occurs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InteractionsEndpointError::Server`] if the listener can't be
+    /// used or the server encounters an error.
+    pub async fn serve_with_listener(self, listener: TcpListener) -> Result<()> {
+        let builder = Server::from_tcp(listener).map_err(InteractionsEndpointError::from)?;
+
+        self.run(builder).await
+    }
+
+    async fn run(
+        self,
+        builder: hyper::server::Builder<hyper::server::conn::AddrIncoming>,
+    ) -> Result<()> {
+        let endpoint = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let endpoint = Arc::clone(&endpoint);
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let endpoint = Arc::clone(&endpoint);
+
+                    async move { Ok::<_, Infallible>(endpoint.handle(request).await) }
+                }))
+            }
+        });
+
+        builder.serve(make_service).await.map_err(|e| InteractionsEndpointError::from(e).into())
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        if request.method() != Method::POST {
+            return status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+
+        let headers = request.headers();
+        let (signature, timestamp) = match (
+            headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok()),
+            headers.get(TIMESTAMP_HEADER).and_then(|v| v.to_str().ok()),
+        ) {
+            (Some(signature), Some(timestamp)) => (signature.to_string(), timestamp.to_string()),
+            _ => return status(StatusCode::UNAUTHORIZED),
+        };
+
+        let length = headers.get(CONTENT_LENGTH).and_then(|v| v.to_str().ok()?.parse::<u64>().ok());
+
+        if length.map_or(false, |length| length > MAX_BODY_SIZE as u64) {
+            return status(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        let body = match read_body(request.into_body()).await {
+            Ok(body) => body,
+            Err(status_code) => return status(status_code),
+        };
+
+        if self.verifier.verify(&signature, &timestamp, &body).is_err() {
+            return status(StatusCode::UNAUTHORIZED);
+        }
+
+        let interaction = match String::from_utf8(body)
+            .map_err(|_| Error::Other("Interaction body is not valid UTF-8"))
+            .and_then(|mut body| json::from_str::<Interaction>(&mut body))
+        {
+            Ok(interaction) => interaction,
+            Err(why) => {
+                warn!("Failed to deserialize interaction: {:?}", why);
+
+                return status(StatusCode::BAD_REQUEST);
+            },
+        };
+
+        if let Interaction::Ping(_) = interaction {
+            return json_response(&json::json!({ "type": InteractionResponseType::Pong as u8 }));
+        }
+
+        let mut reply = None;
+
+        if let Some(interaction_handler) = &self.interaction_handler {
+            reply = interaction_handler.respond(self.context(), &interaction).await;
+        }
+
+        let reply = match reply {
+            Some(reply) => {
+                if !reply.1.is_empty() {
+                    warn!("Files can't be sent in the reply to an interaction, dropping them");
+                }
+
+                Value::from(json::hashmap_to_json_map(reply.0))
+            },
+            None => deferred_response(&interaction),
+        };
+
+        if let Some(event_handler) = &self.event_handler {
+            let event_handler = Arc::clone(event_handler);
+            let ctx = self.context();
+
+            spawn_named("interactions_endpoint::interaction_create", async move {
+                event_handler.interaction_create(ctx, interaction).await;
+            });
+        }
+
+        json_response(&reply)
+    }
+
+    fn context(&self) -> Context {
+        // There is no shard to communicate with, so messages sent to it are
+        // discarded.
+        let (runner_tx, _) = mpsc::unbounded();
+
+        #[cfg(feature = "cache")]
+        let ctx = Context::new(
+            Arc::clone(&self.data),
+            runner_tx,
+            0,
+            Arc::clone(&self.http),
+            Arc::clone(&self.cache),
+        );
+        #[cfg(not(feature = "cache"))]
+        let ctx = Context::new(Arc::clone(&self.data), runner_tx, 0, Arc::clone(&self.http));
+
+        ctx
+    }
+}
+
+impl fmt::Debug for InteractionsEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InteractionsEndpoint")
+            .field("verifier", &self.verifier)
+            .field("event_handler", &self.event_handler.as_ref().map(|_| "<handler>"))
+            .field("interaction_handler", &self.interaction_handler.as_ref().map(|_| "<handler>"))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads a request body, failing once it grows larger than [`MAX_BODY_SIZE`]
+/// as a sender may not announce its length up front.
+async fn read_body(mut body: Body) -> StdResult<Vec<u8>, StatusCode> {
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|why| {
+            debug!("Failed to read interaction request body: {:?}", why);
+
+            StatusCode::BAD_REQUEST
+        })?;
+
+        if bytes.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+/// The response deferring an interaction, for when there is no other response
+/// to it.
+fn deferred_response(interaction: &Interaction) -> Value {
+    match interaction {
+        Interaction::MessageComponent(_) => {
+            json::json!({ "type": InteractionResponseType::DeferredUpdateMessage as u8 })
+        },
+        // Autocompletes can't be deferred, so offer no choices instead.
+        Interaction::Autocomplete(_) => json::json!({
+            "type": InteractionResponseType::Autocomplete as u8,
+            "data": { "choices": [] },
+        }),
+        _ => {
+            json::json!({ "type": InteractionResponseType::DeferredChannelMessageWithSource as u8 })
+        },
+    }
+}
+
+fn json_response(value: &Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(value.to_string()));
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    response
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+
+    response
+}
@@ -3,37 +3,75 @@ use std::io::Read;
 use async_trait::async_trait;
 use async_tungstenite::tungstenite::Message;
 use flate2::read::ZlibDecoder;
+use flate2::{Decompress, FlushDecompress};
 use futures::{SinkExt, StreamExt};
 use tokio::time::timeout;
 use tracing::{instrument, warn};
 use url::Url;
 
-use crate::gateway::{GatewayError, WsStream};
+use crate::gateway::{GatewayEncoding, GatewayError, WsStream};
 use crate::internal::prelude::*;
 use crate::json::{from_str, to_string};
 
 #[async_trait]
 pub trait ReceiverExt {
     async fn recv_json(&mut self) -> Result<Option<Value>>;
+
+    /// Receives a payload from a connection compressed with `zlib-stream`.
+    async fn recv_json_inflated(
+        &mut self,
+        inflater: &mut ZlibStreamInflater,
+    ) -> Result<Option<Value>>;
 }
 
 #[async_trait]
 pub trait SenderExt {
     async fn send_json(&mut self, value: &Value) -> Result<()>;
+
+    /// Sends a payload in the given encoding.
+    async fn send_encoded(&mut self, value: &Value, encoding: GatewayEncoding) -> Result<()>;
 }
 
 #[async_trait]
 impl ReceiverExt for WsStream {
     async fn recv_json(&mut self) -> Result<Option<Value>> {
-        const TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_millis(500);
+        convert_ws_message(recv_message(self).await?)
+    }
+
+    async fn recv_json_inflated(
+        &mut self,
+        inflater: &mut ZlibStreamInflater,
+    ) -> Result<Option<Value>> {
+        match recv_message(self).await? {
+            Some(Message::Binary(bytes)) => {
+                let payload = match inflater.inflate(&bytes) {
+                    Ok(Some(payload)) => payload,
+                    Ok(None) => return Ok(None),
+                    Err(why) => {
+                        warn!("Err decompressing bytes: {:?}; bytes: {:?}", why, bytes);
+
+                        return Err(why.into());
+                    },
+                };
+
+                decode_binary(payload).map(Some).map_err(|why| {
+                    warn!("Err deserializing bytes: {:?}; bytes: {:?}", why, bytes);
+
+                    why
+                })
+            },
+            message => convert_ws_message(message),
+        }
+    }
+}
 
-        let ws_message = match timeout(TIMEOUT, self.next()).await {
-            Ok(Some(Ok(v))) => Some(v),
-            Ok(Some(Err(e))) => return Err(e.into()),
-            Ok(None) | Err(_) => None,
-        };
+async fn recv_message(stream: &mut WsStream) -> Result<Option<Message>> {
+    const TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_millis(500);
 
-        convert_ws_message(ws_message)
+    match timeout(TIMEOUT, stream.next()).await {
+        Ok(Some(Ok(v))) => Ok(Some(v)),
+        Ok(Some(Err(e))) => Err(e.into()),
+        Ok(None) | Err(_) => Ok(None),
     }
 }
 
@@ -42,6 +80,33 @@ impl SenderExt for WsStream {
     async fn send_json(&mut self, value: &Value) -> Result<()> {
         Ok(to_string(value).map(Message::Text).map_err(Error::from).map(|m| self.send(m))?.await?)
     }
+
+    async fn send_encoded(&mut self, value: &Value, encoding: GatewayEncoding) -> Result<()> {
+        match encoding {
+            GatewayEncoding::Json => self.send_json(value).await,
+            #[cfg(feature = "etf")]
+            GatewayEncoding::Etf => {
+                let payload = crate::etf::to_vec(value)?;
+
+                Ok(self.send(Message::Binary(payload)).await?)
+            },
+        }
+    }
+}
+
+/// Deserializes a payload received in a binary frame, after decompression.
+///
+/// ETF payloads are told apart from JSON ones by their version byte.
+fn decode_binary(payload: &mut [u8]) -> Result<Value> {
+    #[cfg(feature = "etf")]
+    if payload.first() == Some(&crate::etf::VERSION) {
+        return Ok(crate::etf::from_slice(payload)?);
+    }
+
+    let payload = std::str::from_utf8_mut(payload)
+        .map_err(|why| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, why)))?;
+
+    from_str(payload)
 }
 
 #[inline]
@@ -49,16 +114,25 @@ pub(crate) fn convert_ws_message(message: Option<Message>) -> Result<Option<Valu
     const DECOMPRESSION_MULTIPLIER: usize = 3;
 
     Ok(match message {
+        // Uncompressed ETF payloads are sent in binary frames as well.
+        #[cfg(feature = "etf")]
+        Some(Message::Binary(mut bytes)) if bytes.first() == Some(&crate::etf::VERSION) => {
+            decode_binary(&mut bytes).map(Some).map_err(|why| {
+                warn!("Err deserializing bytes: {:?}; bytes: {:?}", why, bytes);
+
+                why
+            })?
+        },
         Some(Message::Binary(bytes)) => {
-            let mut decompressed = String::with_capacity(bytes.len() * DECOMPRESSION_MULTIPLIER);
+            let mut decompressed = Vec::with_capacity(bytes.len() * DECOMPRESSION_MULTIPLIER);
 
-            ZlibDecoder::new(&bytes[..]).read_to_string(&mut decompressed).map_err(|why| {
+            ZlibDecoder::new(&bytes[..]).read_to_end(&mut decompressed).map_err(|why| {
                 warn!("Err decompressing bytes: {:?}; bytes: {:?}", why, bytes);
 
                 why
             })?;
 
-            from_str(decompressed.as_mut_str()).map(Some).map_err(|why| {
+            decode_binary(&mut decompressed).map(Some).map_err(|why| {
                 warn!("Err deserializing bytes: {:?}; bytes: {:?}", why, bytes);
 
                 why
@@ -77,6 +151,103 @@ pub(crate) fn convert_ws_message(message: Option<Message>) -> Result<Option<Valu
     })
 }
 
+/// The suffix of every complete message on a `zlib-stream` connection.
+const ZLIB_SUFFIX: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Inflates the binary frames of a gateway connection compressed with
+/// `zlib-stream`.
+///
+/// The whole connection shares a single zlib context, so one inflater must be
+/// kept per connection and replaced when reconnecting. A message may also be
+/// split across multiple frames, so frames are buffered until one ends with the
+/// `Z_SYNC_FLUSH` suffix.
+pub struct ZlibStreamInflater {
+    decompress: Decompress,
+    compressed: Vec<u8>,
+    inflated: Vec<u8>,
+}
+
+impl ZlibStreamInflater {
+    const DECOMPRESSION_MULTIPLIER: usize = 3;
+
+    pub fn new() -> Self {
+        Self {
+            decompress: Decompress::new(true),
+            compressed: Vec::new(),
+            inflated: Vec::new(),
+        }
+    }
+
+    /// Buffers a binary frame, returning the inflated message if the frame
+    /// completed one.
+    ///
+    /// The returned message borrows the inflater's buffer, which is reused for
+    /// the following messages.
+    pub fn inflate(&mut self, bytes: &[u8]) -> std::io::Result<Option<&mut [u8]>> {
+        self.compressed.extend_from_slice(bytes);
+
+        if !self.compressed.ends_with(&ZLIB_SUFFIX) {
+            return Ok(None);
+        }
+
+        self.inflated.clear();
+        let mut offset = 0;
+
+        loop {
+            if self.inflated.len() == self.inflated.capacity() {
+                self.inflated.reserve(self.compressed.len() * Self::DECOMPRESSION_MULTIPLIER);
+            }
+
+            let (total_in, total_out) = (self.decompress.total_in(), self.decompress.total_out());
+            let result = self.decompress.decompress_vec(
+                &self.compressed[offset..],
+                &mut self.inflated,
+                FlushDecompress::Sync,
+            );
+
+            if let Err(why) = result {
+                self.reset();
+
+                return Err(why.into());
+            }
+
+            let consumed = (self.decompress.total_in() - total_in) as usize;
+            let produced = self.decompress.total_out() - total_out;
+            offset += consumed;
+
+            // Everything was inflated once all input is consumed and the output
+            // buffer has room to spare.
+            let finished =
+                offset == self.compressed.len() && self.inflated.len() < self.inflated.capacity();
+
+            if finished || consumed == 0 && produced == 0 {
+                break;
+            }
+        }
+
+        self.compressed.clear();
+
+        Ok(Some(&mut self.inflated))
+    }
+
+    /// Discards the zlib context and any buffered frames, so that the
+    /// inflater can be used for a new connection.
+    ///
+    /// This is done when inflating fails, since the context is unusable
+    /// afterwards.
+    pub fn reset(&mut self) {
+        self.decompress.reset(true);
+        self.compressed.clear();
+        self.inflated.clear();
+    }
+}
+
+impl Default for ZlibStreamInflater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[instrument]
 pub(crate) async fn create_client(url: Url) -> Result<WsStream> {
     let config = async_tungstenite::tungstenite::protocol::WebSocketConfig {
@@ -90,3 +261,100 @@ pub(crate) async fn create_client(url: Url) -> Result<WsStream> {
 
     Ok(stream)
 }
+
+#[cfg(test)]
+mod test {
+    #[cfg(feature = "etf")]
+    use serde::Deserialize;
+
+    use super::ZlibStreamInflater;
+    #[cfg(feature = "etf")]
+    use crate::model::event::GatewayEvent;
+
+    // Recorded from a single zlib context, with a `Z_SYNC_FLUSH` after each
+    // payload, the way the gateway sends them.
+    const HELLO: [u8; 50] = [
+        0x78, 0x9c, 0xaa, 0x56, 0xca, 0x2f, 0x50, 0xb2, 0x32, 0x34, 0xd0, 0x51, 0x4a, 0x51, 0xb2,
+        0xaa, 0x56, 0xca, 0x48, 0x4d, 0x2c, 0x2a, 0x49, 0x4a, 0x4d, 0x2c, 0x89, 0xcf, 0xcc, 0x2b,
+        0x49, 0x2d, 0x2a, 0x4b, 0xcc, 0x51, 0xb2, 0x32, 0x31, 0x34, 0x32, 0x35, 0xa8, 0xad, 0x05,
+        0x00, 0x00, 0x00, 0xff, 0xff,
+    ];
+    const HEARTBEAT_ACK: [u8; 17] = [
+        0xaa, 0x86, 0xa8, 0x34, 0x04, 0xab, 0xcc, 0x2b, 0xcd, 0xc9, 0xa9, 0x05, 0x00, 0x00, 0x00,
+        0xff, 0xff,
+    ];
+    const RESUMED: [u8; 31] = [
+        0x82, 0x88, 0x00, 0xb5, 0x16, 0x03, 0xc5, 0x75, 0x94, 0x4a, 0x94, 0xac, 0x94, 0x82, 0x5c,
+        0x83, 0x43, 0x7d, 0x5d, 0x5d, 0x94, 0x20, 0xc6, 0xd5, 0xd6, 0x02, 0x00, 0x00, 0x00, 0xff,
+        0xff,
+    ];
+
+    #[test]
+    fn test_inflate_shared_context() {
+        let mut inflater = ZlibStreamInflater::new();
+
+        assert_eq!(
+            inflater.inflate(&HELLO).unwrap().as_deref(),
+            Some(&br#"{"op":10,"d":{"heartbeat_interval":41250}}"#[..]),
+        );
+        assert_eq!(
+            inflater.inflate(&HEARTBEAT_ACK).unwrap().as_deref(),
+            Some(&br#"{"op":11,"d":null}"#[..]),
+        );
+        assert_eq!(
+            inflater.inflate(&RESUMED).unwrap().as_deref(),
+            Some(&br#"{"op":0,"s":1,"t":"RESUMED","d":{}}"#[..]),
+        );
+    }
+
+    #[test]
+    fn test_inflate_split_frames() {
+        let mut inflater = ZlibStreamInflater::new();
+
+        assert_eq!(inflater.inflate(&HELLO[..20]).unwrap(), None);
+        assert_eq!(inflater.inflate(&HELLO[20..47]).unwrap(), None);
+        assert_eq!(
+            inflater.inflate(&HELLO[47..]).unwrap().as_deref(),
+            Some(&br#"{"op":10,"d":{"heartbeat_interval":41250}}"#[..]),
+        );
+    }
+
+    #[test]
+    fn test_inflate_requires_shared_context() {
+        // A payload compressed against a previous one can't be inflated by a
+        // fresh context.
+        assert!(ZlibStreamInflater::new().inflate(&HEARTBEAT_ACK).is_err());
+    }
+
+    #[test]
+    fn test_inflate_after_error() {
+        let mut inflater = ZlibStreamInflater::new();
+
+        assert!(inflater.inflate(&[0xff; 8]).unwrap().is_none());
+        assert!(inflater.inflate(&RESUMED).is_err());
+
+        // The context is reset, so the first payload of a new connection can
+        // be inflated.
+        assert_eq!(
+            inflater.inflate(&HELLO).unwrap().as_deref(),
+            Some(&br#"{"op":10,"d":{"heartbeat_interval":41250}}"#[..]),
+        );
+    }
+
+    #[cfg(feature = "etf")]
+    #[test]
+    fn test_etf_round_trip() {
+        let mut inflater = ZlibStreamInflater::new();
+
+        for payload in [&HELLO[..], &HEARTBEAT_ACK[..], &RESUMED[..]] {
+            let value = super::decode_binary(inflater.inflate(payload).unwrap().unwrap()).unwrap();
+            let mut term = crate::etf::to_vec(&value).unwrap();
+            assert_eq!(super::decode_binary(&mut term).unwrap(), value);
+
+            // Both fail for the RESUMED, which lacks the `_trace` of a real one.
+            let from_json = GatewayEvent::deserialize(value).ok();
+            let from_etf = crate::etf::from_slice::<GatewayEvent>(&term).ok();
+            assert_eq!(format!("{:?}", from_etf), format!("{:?}", from_json));
+        }
+    }
+}
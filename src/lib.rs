@@ -88,6 +88,8 @@ extern crate serde;
 mod internal;
 
 pub mod constants;
+#[cfg(feature = "etf")]
+pub mod etf;
 pub mod json;
 pub mod model;
 pub mod prelude;
@@ -106,6 +108,8 @@ pub mod framework;
 pub mod gateway;
 #[cfg(feature = "http")]
 pub mod http;
+#[cfg(feature = "interactions_endpoint")]
+pub mod interactions_endpoint;
 #[cfg(feature = "utils")]
 pub mod utils;
 
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
 
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer};
@@ -33,6 +35,7 @@ use crate::model::id::{
     TargetId,
     UserId,
 };
+use crate::model::mention::Mention;
 use crate::model::user::User;
 use crate::model::utils::deserialize_options_with_resolved;
 use crate::model::Permissions;
@@ -738,3 +741,270 @@ impl From<TargetId> for UserId {
         Self(id.0)
     }
 }
+
+/// Extracts a typed value from a [`CommandDataOption`].
+///
+/// This is implemented for the types of values options can hold, for the Ids of
+/// users, roles, channels and attachments, for [`Mention`]s of the user or role
+/// given to a mentionable option, for [`Option`]s of these, whose value is
+/// `None` if the option was not given, and for types deriving it.
+///
+/// The derive, available with the `command_attr` feature (which is enabled by
+/// `standard_framework`), maps the fields of a struct onto the options of a
+/// command or subcommand by name. A field can be renamed via
+/// `#[option(name = "...")]`. Deriving it for an enum maps each variant onto a
+/// subcommand or subcommand group, whose options are extracted into the
+/// variant's single field. Variant names are converted to snake case, unless
+/// renamed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use serenity::model::application::interaction::application_command::{
+///     ApplicationCommandInteraction,
+///     FromCommandOption,
+/// };
+/// use serenity::model::guild::Role;
+/// use serenity::model::user::User;
+///
+/// # #[cfg(feature = "command_attr")]
+/// #[derive(FromCommandOption)]
+/// struct Give {
+///     user: User,
+///     role: Role,
+///     #[option(name = "reason")]
+///     audit_log_reason: Option<String>,
+/// }
+///
+/// # #[cfg(feature = "command_attr")]
+/// #[derive(FromCommandOption)]
+/// enum RoleCommand {
+///     Give(Give),
+///     List,
+/// }
+///
+/// # #[cfg(feature = "command_attr")]
+/// fn run(interaction: &ApplicationCommandInteraction) {
+///     match interaction.data.parse_options::<RoleCommand>() {
+///         Ok(RoleCommand::Give(give)) => {
+///             println!("Giving {} to {}", give.role.name, give.user.tag())
+///         },
+///         Ok(RoleCommand::List) => println!("Listing roles"),
+///         Err(why) => println!("Invalid options: {}", why),
+///     }
+/// }
+/// ```
+pub trait FromCommandOption: Sized {
+    /// Extracts the value from a given option.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CommandOptionError`] if the option holds a different type
+    /// of value, or its value could not be resolved.
+    fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError>;
+
+    /// Produces the value of an option that was not given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommandOptionError::Missing`] by default, as the option is
+    /// required.
+    fn missing(name: &str) -> StdResult<Self, CommandOptionError> {
+        Err(CommandOptionError::Missing(name.to_string()))
+    }
+}
+
+/// Extracts a typed value from the options of a command, subcommand or
+/// subcommand group.
+///
+/// This is implemented by deriving [`FromCommandOption`].
+pub trait FromCommandOptions: Sized {
+    /// Extracts the value from the given options.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CommandOptionError`] if an option is missing, or could not
+    /// be extracted.
+    fn from_options(options: &[CommandDataOption]) -> StdResult<Self, CommandOptionError>;
+}
+
+#[cfg(feature = "command_attr")]
+pub use command_attr::FromCommandOption;
+
+impl CommandData {
+    /// Extracts the command's options into a type implementing
+    /// [`FromCommandOptions`], such as one deriving [`FromCommandOption`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CommandOptionError`] if the options don't match the type.
+    pub fn parse_options<T: FromCommandOptions>(&self) -> StdResult<T, CommandOptionError> {
+        T::from_options(&self.options)
+    }
+}
+
+/// An error returned when extracting typed values from
+/// [`CommandDataOption`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CommandOptionError {
+    /// A required option with the given name was not given.
+    Missing(String),
+    /// An option holds a different type of value than expected.
+    UnexpectedType {
+        /// The name of the option.
+        name: String,
+        /// The type of option expected.
+        expected: CommandOptionType,
+        /// The type of the option given.
+        found: CommandOptionType,
+    },
+    /// The value of the option with the given name could not be resolved, for
+    /// example because the resolved data lacks the member of a user.
+    Unresolved(String),
+    /// None of the expected subcommands was given.
+    MissingSubCommand,
+    /// A subcommand or subcommand group with the given name is not known.
+    UnknownSubCommand(String),
+}
+
+impl fmt::Display for CommandOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "Required option `{}` is missing", name),
+            Self::UnexpectedType {
+                name,
+                expected,
+                found,
+            } => write!(f, "Option `{}` is a {:?} option, expected {:?}", name, found, expected),
+            Self::Unresolved(name) => write!(f, "Value of option `{}` is not resolved", name),
+            Self::MissingSubCommand => f.write_str("No subcommand was given"),
+            Self::UnknownSubCommand(name) => write!(f, "Unknown subcommand `{}`", name),
+        }
+    }
+}
+
+impl StdError for CommandOptionError {}
+
+fn unexpected_type(option: &CommandDataOption, expected: CommandOptionType) -> CommandOptionError {
+    CommandOptionError::UnexpectedType {
+        name: option.name.clone(),
+        expected,
+        found: option.kind,
+    }
+}
+
+macro_rules! impl_from_command_option {
+    ($($ty:ty => $kind:ident, |$option:ident| $value:pat => $extract:expr;)*) => {
+        $(
+            impl FromCommandOption for $ty {
+                fn from_option($option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+                    match &$option.resolved {
+                        Some($value) => $extract,
+                        None if $option.value.is_some() => {
+                            Err(CommandOptionError::Unresolved($option.name.clone()))
+                        },
+                        _ => Err(unexpected_type($option, CommandOptionType::$kind)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_command_option! {
+    String => String, |option| CommandDataOptionValue::String(value) => Ok(value.clone());
+    i64 => Integer, |option| CommandDataOptionValue::Integer(value) => Ok(*value);
+    bool => Boolean, |option| CommandDataOptionValue::Boolean(value) => Ok(*value);
+    f64 => Number, |option| CommandDataOptionValue::Number(value) => Ok(*value);
+    User => User, |option| CommandDataOptionValue::User(user, _) => Ok(user.clone());
+    PartialMember => User, |option| CommandDataOptionValue::User(_, member) => {
+        member.clone().ok_or_else(|| CommandOptionError::Unresolved(option.name.clone()))
+    };
+    Role => Role, |option| CommandDataOptionValue::Role(role) => Ok(role.clone());
+    PartialChannel => Channel, |option| CommandDataOptionValue::Channel(channel) => {
+        Ok(channel.clone())
+    };
+    Attachment => Attachment, |option| CommandDataOptionValue::Attachment(attachment) => {
+        Ok(attachment.clone())
+    };
+}
+
+impl FromCommandOption for (User, Option<PartialMember>) {
+    fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+        match &option.resolved {
+            Some(CommandDataOptionValue::User(user, member)) => Ok((user.clone(), member.clone())),
+            None if option.value.is_some() => {
+                Err(CommandOptionError::Unresolved(option.name.clone()))
+            },
+            _ => Err(unexpected_type(option, CommandOptionType::User)),
+        }
+    }
+}
+
+macro_rules! impl_from_command_option_id {
+    ($($ty:ident => $kind:ident;)*) => {
+        $(
+            impl FromCommandOption for $ty {
+                fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+                    option_id(option, CommandOptionType::$kind).map($ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_command_option_id! {
+    UserId => User;
+    RoleId => Role;
+    ChannelId => Channel;
+    AttachmentId => Attachment;
+}
+
+/// Parses the Id an option of the given type holds, which is available even if
+/// the value it refers to is not resolved.
+fn option_id(
+    option: &CommandDataOption,
+    expected: CommandOptionType,
+) -> StdResult<u64, CommandOptionError> {
+    if option.kind != expected {
+        return Err(unexpected_type(option, expected));
+    }
+
+    option
+        .value
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| CommandOptionError::Unresolved(option.name.clone()))
+}
+
+impl FromCommandOption for Mention {
+    fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+        if option.kind != CommandOptionType::Mentionable {
+            return Err(unexpected_type(option, CommandOptionType::Mentionable));
+        }
+
+        match &option.resolved {
+            Some(CommandDataOptionValue::User(user, _)) => Ok(Mention::User(user.id)),
+            Some(CommandDataOptionValue::Role(role)) => Ok(Mention::Role(role.id)),
+            _ => Err(CommandOptionError::Unresolved(option.name.clone())),
+        }
+    }
+}
+
+impl FromCommandOption for CommandDataOptionValue {
+    fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+        option.resolved.clone().ok_or_else(|| CommandOptionError::Unresolved(option.name.clone()))
+    }
+}
+
+impl<T: FromCommandOption> FromCommandOption for Option<T> {
+    fn from_option(option: &CommandDataOption) -> StdResult<Self, CommandOptionError> {
+        T::from_option(option).map(Some)
+    }
+
+    fn missing(_: &str) -> StdResult<Self, CommandOptionError> {
+        Ok(None)
+    }
+}
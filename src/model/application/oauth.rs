@@ -1,5 +1,7 @@
 use std::fmt;
 
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
 
 /// The available OAuth2 Scopes.
@@ -96,3 +98,59 @@ impl fmt::Display for Scope {
         self.serialize(f)
     }
 }
+
+/// An access token granted through OAuth2.
+///
+/// [Discord docs](https://discord.com/developers/docs/topics/oauth2#authorization-code-grant-access-token-response).
+#[derive(Clone, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct AccessToken {
+    /// The token to authorize requests with, as a [`Self::token_type`] token.
+    pub access_token: String,
+    /// The type of the token, which is always `Bearer`.
+    pub token_type: String,
+    /// The number of seconds until the token expires.
+    pub expires_in: u64,
+    /// The token to get a new access token with once this one expired, which
+    /// isn't given for client credentials grants.
+    pub refresh_token: Option<String>,
+    /// The space separated scopes the token was granted.
+    pub scope: String,
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("token_type", &self.token_type)
+            .field("expires_in", &self.expires_in)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AccessToken {
+    /// The token as passed to [`Http::new`] or [`RequestOptions::token`] to
+    /// make requests on behalf of the user, such as
+    /// [`Http::get_current_user`].
+    ///
+    /// [`Http::new`]: crate::http::Http::new
+    /// [`Http::get_current_user`]: crate::http::Http::get_current_user
+    /// [`RequestOptions::token`]: crate::http::RequestOptions::token
+    #[must_use]
+    pub fn bearer(&self) -> String {
+        format!("Bearer {}", self.access_token)
+    }
+
+    /// The scopes the token was granted, leaving out those unknown to the
+    /// library.
+    #[must_use]
+    pub fn scopes(&self) -> Vec<Scope> {
+        self.scope
+            .split_whitespace()
+            .filter_map(|scope| {
+                let scope: StrDeserializer<'_, ValueError> = scope.into_deserializer();
+                Scope::deserialize(scope).ok()
+            })
+            .collect()
+    }
+}
@@ -30,6 +30,8 @@ use crate::collector::{
     ReactionCollectorBuilder,
 };
 #[cfg(feature = "model")]
+use crate::http::pagination::{next_cursor, paginate};
+#[cfg(feature = "model")]
 use crate::http::{CacheHttp, Http, Typing};
 #[cfg(feature = "model")]
 use crate::json::{self, json};
@@ -658,6 +660,67 @@ impl ChannelId {
             .await
     }
 
+    /// Streams over all the users that have reacted to a message with the
+    /// given reaction type, sorted by their Id.
+    ///
+    /// Users are fetched 100 at a time, as they are needed.
+    ///
+    /// **Note**: Requires the [Read Message History] permission. See
+    /// [`Self::reaction_users`] for which reaction types work.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use serenity::model::id::ChannelId;
+    /// # use serenity::http::Http;
+    /// #
+    /// # async fn run() {
+    /// # let channel_id = ChannelId::default();
+    /// # let ctx = Http::new("token");
+    /// use serenity::futures::StreamExt;
+    ///
+    /// let mut users = channel_id.reaction_users_iter(&ctx, 1, '👍').boxed();
+    /// while let Some(user_result) = users.next().await {
+    ///     match user_result {
+    ///         Ok(user) => println!("{} reacted", user.tag()),
+    ///         Err(error) => eprintln!("Uh oh! Error: {}", error),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [Read Message History]: Permissions::READ_MESSAGE_HISTORY
+    pub fn reaction_users_iter<H: AsRef<Http>>(
+        self,
+        http: H,
+        message_id: impl Into<MessageId>,
+        reaction_type: impl Into<ReactionType>,
+    ) -> impl Stream<Item = Result<User>> {
+        const LIMIT: u8 = 100;
+
+        let message_id = message_id.into();
+        let reaction_type = reaction_type.into();
+
+        paginate(http, move |http, after: Option<UserId>| {
+            let reaction_type = reaction_type.clone();
+
+            Box::pin(async move {
+                let users = http
+                    .get_reaction_users(
+                        self.0,
+                        message_id.0,
+                        &reaction_type,
+                        LIMIT,
+                        after.map(|u| u.0),
+                    )
+                    .await?;
+                let next = next_cursor(&users, u64::from(LIMIT), |user| user.id);
+
+                Ok((users, next))
+            })
+        })
+    }
+
     /// Sends a message with just the given message content in the channel.
     ///
     /// # Errors
@@ -1177,6 +1240,124 @@ impl ChannelId {
     ) -> Result<ThreadsData> {
         http.as_ref().get_channel_joined_archived_private_threads(self.0, before, limit).await
     }
+
+    /// Streams over all private archived threads of a channel, most recently
+    /// archived first.
+    ///
+    /// Threads are fetched 100 at a time, as they are needed. Pages are
+    /// requested by archive timestamp, so if every thread of a page was
+    /// archived at the same time, the other threads archived at that time are
+    /// skipped.
+    pub fn archived_private_threads_iter<H: AsRef<Http>>(
+        self,
+        http: H,
+    ) -> impl Stream<Item = Result<GuildChannel>> {
+        paginate(http, move |http, cursor: Option<ArchivedThreadsCursor>| {
+            Box::pin(async move {
+                let (before, seen) = cursor.unwrap_or_default();
+                let data = http
+                    .get_channel_archived_private_threads_by_timestamp(self.0, before, Some(100))
+                    .await?;
+
+                Ok(archived_threads_page(data, &seen))
+            })
+        })
+    }
+
+    /// Streams over all public archived threads of a channel, most recently
+    /// archived first.
+    ///
+    /// Threads are fetched 100 at a time, as they are needed. Pages are
+    /// requested by archive timestamp, so if every thread of a page was
+    /// archived at the same time, the other threads archived at that time are
+    /// skipped.
+    pub fn archived_public_threads_iter<H: AsRef<Http>>(
+        self,
+        http: H,
+    ) -> impl Stream<Item = Result<GuildChannel>> {
+        paginate(http, move |http, cursor: Option<ArchivedThreadsCursor>| {
+            Box::pin(async move {
+                let (before, seen) = cursor.unwrap_or_default();
+                let data = http
+                    .get_channel_archived_public_threads_by_timestamp(self.0, before, Some(100))
+                    .await?;
+
+                Ok(archived_threads_page(data, &seen))
+            })
+        })
+    }
+
+    /// Streams over all private archived threads of a channel joined by the
+    /// current user, sorted by their Id in descending order.
+    ///
+    /// Threads are fetched 100 at a time, as they are needed.
+    pub fn joined_archived_private_threads_iter<H: AsRef<Http>>(
+        self,
+        http: H,
+    ) -> impl Stream<Item = Result<GuildChannel>> {
+        paginate(http, move |http, before: Option<ChannelId>| {
+            Box::pin(async move {
+                let data = http
+                    .get_channel_joined_archived_private_threads(
+                        self.0,
+                        before.map(|c| c.0),
+                        Some(100),
+                    )
+                    .await?;
+                let next = if data.has_more { data.threads.last().map(|t| t.id) } else { None };
+
+                Ok((data.threads, next))
+            })
+        })
+    }
+}
+
+/// The cursor of a page of archived threads: the archive timestamp to get
+/// threads before, and the threads of the previous page which are returned
+/// again and need to be skipped.
+#[cfg(feature = "model")]
+type ArchivedThreadsCursor = (Option<Timestamp>, Vec<ChannelId>);
+
+/// Splits a page of archived threads into the threads which were not `seen`
+/// on the previous page, and the cursor of the next page.
+#[cfg(feature = "model")]
+fn archived_threads_page(
+    data: ThreadsData,
+    seen: &[ChannelId],
+) -> (Vec<GuildChannel>, Option<ArchivedThreadsCursor>) {
+    fn archived_at(thread: &GuildChannel) -> Option<Timestamp> {
+        thread.thread_metadata?.archive_timestamp
+    }
+
+    let last = data.threads.last().and_then(archived_at).filter(|_| data.has_more);
+    let next = last.map(|last| {
+        // Discord only returns threads archived strictly before the cursor, so
+        // getting the next page before the last timestamp would skip threads
+        // archived at the same time which didn't fit on this page. Instead,
+        // get it before the previous timestamp and skip the threads archived at
+        // the last one, which were already returned.
+        let previous = data.threads.iter().rev().filter_map(archived_at).find(|&t| t != last);
+
+        match previous {
+            Some(previous) => {
+                let seen = data
+                    .threads
+                    .iter()
+                    .filter(|thread| archived_at(thread) == Some(last))
+                    .map(|thread| thread.id)
+                    .collect();
+
+                (Some(previous), seen)
+            },
+            // Every thread of the page was archived at the same time, so there
+            // is no way to get the rest of them.
+            None => (Some(last), Vec::new()),
+        }
+    });
+
+    let threads = data.threads.into_iter().filter(|thread| !seen.contains(&thread.id)).collect();
+
+    (threads, next)
 }
 
 #[cfg(feature = "model")]
@@ -165,15 +165,11 @@ impl Emoji {
     #[cfg(feature = "cache")]
     #[must_use]
     pub fn find_guild_id(&self, cache: impl AsRef<Cache>) -> Option<GuildId> {
-        for guild_entry in cache.as_ref().guilds.iter() {
-            let guild = guild_entry.value();
+        let cache = cache.as_ref();
 
-            if guild.emojis.contains_key(&self.id) {
-                return Some(guild.id);
-            }
-        }
-
-        None
+        cache.backend.guild_ids().into_iter().find(|guild_id| {
+            cache.guild_field(*guild_id, |guild| guild.emojis.contains_key(&self.id)) == Some(true)
+        })
     }
 
     /// Generates a URL to the emoji's image.
@@ -33,6 +33,8 @@ use crate::collector::{
     ReactionCollectorBuilder,
 };
 #[cfg(feature = "model")]
+use crate::http::pagination::{next_cursor, paginate};
+#[cfg(feature = "model")]
 use crate::http::{CacheHttp, Http, UserPagination};
 #[cfg(feature = "model")]
 use crate::internal::prelude::*;
@@ -282,6 +284,48 @@ impl GuildId {
         http.as_ref().get_bans(self.0).await
     }
 
+    /// Streams over all the guild's bans, sorted by the Id of the banned user.
+    ///
+    /// Bans are fetched 1000 at a time, as they are needed.
+    ///
+    /// **Note**: Requires the [Ban Members] permission.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use serenity::model::id::GuildId;
+    /// # use serenity::http::Http;
+    /// #
+    /// # async fn run() {
+    /// # let guild_id = GuildId::default();
+    /// # let ctx = Http::new("token");
+    /// use serenity::futures::StreamExt;
+    ///
+    /// let mut bans = guild_id.bans_iter(&ctx).boxed();
+    /// while let Some(ban_result) = bans.next().await {
+    ///     match ban_result {
+    ///         Ok(ban) => println!("{} is banned", ban.user.tag()),
+    ///         Err(error) => eprintln!("Uh oh!  Error: {}", error),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [Ban Members]: Permissions::BAN_MEMBERS
+    pub fn bans_iter<H: AsRef<Http>>(self, http: H) -> impl Stream<Item = Result<Ban>> {
+        const LIMIT: u64 = 1000;
+
+        paginate(http, move |http, after: Option<UserId>| {
+            Box::pin(async move {
+                let target = after.map(UserPagination::After);
+                let bans = http.get_bans_paginated(self.0, target.as_ref(), Some(LIMIT)).await?;
+                let next = next_cursor(&bans, LIMIT, |ban| ban.user.id);
+
+                Ok((bans, next))
+            })
+        })
+    }
+
     /// Gets a list of the guild's audit log entries
     ///
     /// **Note**: Requires the [View Audit Log] permission.
@@ -306,6 +350,41 @@ impl GuildId {
             .await
     }
 
+    /// Streams over the guild's audit log entries, newest first, optionally
+    /// filtered by action type and the user who performed them.
+    ///
+    /// Entries are fetched 100 at a time, as they are needed.
+    ///
+    /// **Note**: Requires the [View Audit Log] permission.
+    ///
+    /// [View Audit Log]: Permissions::VIEW_AUDIT_LOG
+    pub fn audit_logs_iter<H: AsRef<Http>>(
+        self,
+        http: H,
+        action_type: Option<u8>,
+        user_id: Option<UserId>,
+    ) -> impl Stream<Item = Result<AuditLogEntry>> {
+        const LIMIT: u8 = 100;
+
+        paginate(http, move |http, before: Option<AuditLogEntryId>| {
+            Box::pin(async move {
+                let entries = http
+                    .get_audit_logs(
+                        self.0,
+                        action_type,
+                        user_id.map(|u| u.0),
+                        before.map(|e| e.0),
+                        Some(LIMIT),
+                    )
+                    .await?
+                    .entries;
+                let next = next_cursor(&entries, u64::from(LIMIT), |entry| entry.id);
+
+                Ok((entries, next))
+            })
+        })
+    }
+
     /// Gets all of the guild's channels over the REST API.
     ///
     /// # Errors
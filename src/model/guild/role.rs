@@ -235,19 +235,9 @@ impl RoleId {
     /// Tries to find the [`Role`] by its Id in the cache.
     #[cfg(feature = "cache")]
     pub fn to_role_cached(self, cache: impl AsRef<Cache>) -> Option<Role> {
-        for guild_entry in cache.as_ref().guilds.iter() {
-            let guild = guild_entry.value();
+        let cache = cache.as_ref();
 
-            if !guild.roles.contains_key(&self) {
-                continue;
-            }
-
-            if let Some(role) = guild.roles.get(&self) {
-                return Some(role.clone());
-            }
-        }
-
-        None
+        cache.backend.guild_ids().into_iter().find_map(|guild_id| cache.role(guild_id, self))
     }
 }
 
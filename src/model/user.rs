@@ -6,6 +6,8 @@ use std::fmt::Write;
 
 #[cfg(feature = "model")]
 use futures::future::{BoxFuture, FutureExt};
+#[cfg(feature = "model")]
+use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
 
 use super::prelude::*;
@@ -23,6 +25,8 @@ use crate::collector::{
     ReactionCollectorBuilder,
 };
 #[cfg(feature = "model")]
+use crate::http::pagination::{next_cursor, paginate};
+#[cfg(feature = "model")]
 use crate::http::GuildPagination;
 #[cfg(feature = "model")]
 use crate::http::{CacheHttp, Http};
@@ -343,6 +347,42 @@ impl CurrentUser {
         Ok(guilds)
     }
 
+    /// Streams over the guilds the current user is in, sorted by their Id.
+    ///
+    /// Unlike [`Self::guilds`], guilds are fetched 200 at a time as they are
+    /// needed, rather than all at once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use serenity::http::Http;
+    /// # use serenity::model::user::CurrentUser;
+    /// #
+    /// # async fn run() {
+    /// #     let user = CurrentUser::default();
+    /// #     let http = Http::new("token");
+    /// use serenity::futures::StreamExt;
+    ///
+    /// let mut guilds = user.guilds_iter(&http).boxed();
+    /// while let Some(Ok(guild)) = guilds.next().await {
+    ///     println!("{}", guild.name);
+    /// }
+    /// # }
+    /// ```
+    pub fn guilds_iter<H: AsRef<Http>>(&self, http: H) -> impl Stream<Item = Result<GuildInfo>> {
+        const LIMIT: u64 = 200;
+
+        paginate(http, |http, after: Option<GuildId>| {
+            Box::pin(async move {
+                let target = after.map(GuildPagination::After);
+                let guilds = http.get_guilds(target.as_ref(), Some(LIMIT)).await?;
+                let next = next_cursor(&guilds, LIMIT, |guild| guild.id);
+
+                Ok((guilds, next))
+            })
+        })
+    }
+
     /// Returns the invite url for the bot with the given permissions.
     ///
     /// This queries the REST API for the client id.
@@ -51,14 +51,9 @@ async fn lookup_channel_global(
     }
 
     #[cfg(feature = "cache")]
-    if let Some(channel) = ctx.cache.channels.iter().find_map(|m| {
-        let channel = m.value();
-        if channel.name.eq_ignore_ascii_case(s) {
-            Some(channel.clone())
-        } else {
-            None
-        }
-    }) {
+    if let Some(channel) =
+        ctx.cache.backend.find_channel(&mut |channel| channel.name.eq_ignore_ascii_case(s))
+    {
         return Ok(Channel::Guild(channel));
     }
 
@@ -50,7 +50,7 @@ impl ArgumentConvert for Emoji {
         // Get Guild or PartialGuild
         let guild_id = guild_id.ok_or(EmojiParseError::OutsideGuild)?;
         #[cfg(feature = "cache")]
-        let guild = ctx.cache.guilds.get(&guild_id);
+        let guild = ctx.cache.guild(guild_id);
         #[cfg(not(feature = "cache"))]
         let guild = ctx.http.get_guild(guild_id.0).await.ok();
         let guild = guild.ok_or(EmojiParseError::FailedToRetrieveGuild)?;
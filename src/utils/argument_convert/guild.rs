@@ -39,20 +39,12 @@ impl ArgumentConvert for Guild {
         _channel_id: Option<ChannelId>,
         s: &str,
     ) -> Result<Self, Self::Err> {
-        let guilds = &ctx.cache.guilds;
-
-        let lookup_by_id = || guilds.get(&GuildId(s.parse().ok()?)).map(|g| g.clone());
-
-        let lookup_by_name = || {
-            guilds.iter().find_map(|m| {
-                let guild = m.value();
-                if guild.name.eq_ignore_ascii_case(s) {
-                    Some(guild.clone())
-                } else {
-                    None
-                }
-            })
-        };
+        let cache = &ctx.cache;
+
+        let lookup_by_id = || cache.guild(GuildId(s.parse().ok()?));
+
+        let lookup_by_name =
+            || cache.backend.find_guild(&mut |guild| guild.name.eq_ignore_ascii_case(s));
 
         lookup_by_id().or_else(lookup_by_name).ok_or(GuildParseError::NotFoundOrMalformed)
     }
@@ -26,35 +26,20 @@ impl fmt::Display for UserParseError {
 
 #[cfg(feature = "cache")]
 fn lookup_by_global_cache(ctx: &Context, s: &str) -> Option<User> {
-    let users = &ctx.cache.users;
+    let users = &ctx.cache.backend;
 
-    let lookup_by_id = || users.get(&UserId(s.parse().ok()?)).map(|u| u.clone());
+    let lookup_by_id = || users.user(UserId(s.parse().ok()?));
 
-    let lookup_by_mention =
-        || users.get(&UserId(crate::utils::parse_username(s)?)).map(|u| u.clone());
+    let lookup_by_mention = || users.user(UserId(crate::utils::parse_username(s)?));
 
     let lookup_by_name_and_discrim = || {
         let (name, discrim) = crate::utils::parse_user_tag(s)?;
-        users.iter().find_map(|m| {
-            let user = m.value();
-            if user.discriminator == discrim && user.name.eq_ignore_ascii_case(name) {
-                Some(user.clone())
-            } else {
-                None
-            }
+        users.find_user(&mut |user| {
+            user.discriminator == discrim && user.name.eq_ignore_ascii_case(name)
         })
     };
 
-    let lookup_by_name = || {
-        users.iter().find_map(|m| {
-            let user = m.value();
-            if user.name == s {
-                Some(user.clone())
-            } else {
-                None
-            }
-        })
-    };
+    let lookup_by_name = || users.find_user(&mut |user| user.name == s);
 
     lookup_by_id()
         .or_else(lookup_by_mention)
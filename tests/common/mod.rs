@@ -0,0 +1,11 @@
+//! Helpers shared by the integration tests.
+
+use std::sync::Arc;
+
+use serenity::http::transport::MockTransport;
+use serenity::http::{Http, HttpBuilder};
+
+/// Creates a client sending its requests to the mock transport.
+pub fn http(token: &str, transport: &Arc<MockTransport>) -> Http {
+    HttpBuilder::new(token).transport(Arc::clone(transport) as _).build()
+}
@@ -0,0 +1,183 @@
+#![cfg(all(feature = "model", feature = "command_attr"))]
+#![allow(clippy::large_enum_variant)]
+
+use serenity::model::application::interaction::application_command::{
+    CommandData,
+    CommandOptionError,
+    FromCommandOption,
+};
+use serenity::model::guild::{PartialMember, Role};
+use serenity::model::id::{RoleId, UserId};
+use serenity::model::mention::Mention;
+use serenity::model::user::User;
+
+#[derive(FromCommandOption)]
+struct Give {
+    user: User,
+    member: Option<PartialMember>,
+    role: Role,
+    #[option(name = "reason")]
+    audit_log_reason: Option<String>,
+}
+
+#[derive(FromCommandOption)]
+enum Roles {
+    Give(Give),
+    List,
+}
+
+#[derive(FromCommandOption)]
+enum Command {
+    Roles(Roles),
+    ServerInfo,
+}
+
+#[derive(FromCommandOption)]
+struct Mute {
+    user: UserId,
+    role: RoleId,
+    target: Mention,
+    also: Option<Mention>,
+}
+
+fn command_data(options: &str) -> CommandData {
+    let data = format!(
+        r#"{{
+            "id": "1",
+            "name": "admin",
+            "type": 1,
+            "options": {},
+            "resolved": {{
+                "users": {{
+                    "2": {{ "id": "2", "username": "ferris", "discriminator": "0001", "avatar": null }}
+                }},
+                "members": {{
+                    "2": {{ "roles": [], "joined_at": "2022-01-01T00:00:00Z", "deaf": false, "mute": false }}
+                }},
+                "roles": {{
+                    "3": {{
+                        "id": "3",
+                        "guild_id": "5",
+                        "name": "crab",
+                        "color": 0,
+                        "hoist": false,
+                        "position": 1,
+                        "permissions": "0",
+                        "managed": false,
+                        "mentionable": false
+                    }}
+                }}
+            }}
+        }}"#,
+        options
+    );
+
+    serde_json::from_str(&data).unwrap()
+}
+
+#[test]
+fn subcommand_group_options() {
+    let data = command_data(
+        r#"[{
+            "name": "roles",
+            "type": 2,
+            "options": [{
+                "name": "give",
+                "type": 1,
+                "options": [
+                    { "name": "user", "type": 6, "value": "2" },
+                    { "name": "role", "type": 8, "value": "3" }
+                ]
+            }]
+        }]"#,
+    );
+
+    let give = match data.parse_options::<Command>().unwrap() {
+        Command::Roles(Roles::Give(give)) => give,
+        _ => panic!("expected the give subcommand"),
+    };
+
+    assert_eq!(give.user.id, UserId(2));
+    assert_eq!(give.role.id, RoleId(3));
+    assert!(give.member.is_none());
+    assert!(give.audit_log_reason.is_none());
+}
+
+#[test]
+fn unit_subcommand() {
+    let data = command_data(r#"[{ "name": "server_info", "type": 1, "options": [] }]"#);
+
+    assert!(matches!(data.parse_options::<Command>(), Ok(Command::ServerInfo)));
+}
+
+#[test]
+fn ids_and_mentionables() {
+    let data = command_data(
+        r#"[
+            { "name": "user", "type": 6, "value": "4" },
+            { "name": "role", "type": 8, "value": "3" },
+            { "name": "target", "type": 9, "value": "2" },
+            { "name": "also", "type": 9, "value": "3" }
+        ]"#,
+    );
+
+    let mute = data.parse_options::<Mute>().unwrap();
+
+    // The Id is available even though the user is not resolved.
+    assert_eq!(mute.user, UserId(4));
+    assert_eq!(mute.role, RoleId(3));
+    assert!(matches!(mute.target, Mention::User(UserId(2))));
+    assert!(matches!(mute.also, Some(Mention::Role(RoleId(3)))));
+
+    let unexpected = command_data(
+        r#"[
+            { "name": "user", "type": 8, "value": "3" },
+            { "name": "role", "type": 8, "value": "3" },
+            { "name": "target", "type": 9, "value": "2" }
+        ]"#,
+    );
+    assert!(matches!(
+        unexpected.parse_options::<Mute>().err(),
+        Some(CommandOptionError::UnexpectedType { ref name, .. }) if name == "user"
+    ));
+}
+
+#[test]
+fn option_errors() {
+    let missing = command_data(
+        r#"[{ "name": "give", "type": 1, "options": [{ "name": "user", "type": 6, "value": "2" }] }]"#,
+    );
+    assert_eq!(
+        missing.parse_options::<Roles>().err(),
+        Some(CommandOptionError::Missing("role".to_string())),
+    );
+
+    let unexpected = command_data(
+        r#"[{ "name": "give", "type": 1, "options": [
+            { "name": "user", "type": 6, "value": "2" },
+            { "name": "role", "type": 8, "value": "3" },
+            { "name": "reason", "type": 4, "value": 5 }
+        ] }]"#,
+    );
+    assert!(matches!(
+        unexpected.parse_options::<Roles>().err(),
+        Some(CommandOptionError::UnexpectedType { ref name, .. }) if name == "reason"
+    ));
+
+    let unresolved = command_data(
+        r#"[{ "name": "give", "type": 1, "options": [
+            { "name": "user", "type": 6, "value": "4" },
+            { "name": "role", "type": 8, "value": "3" }
+        ] }]"#,
+    );
+    assert_eq!(
+        unresolved.parse_options::<Roles>().err(),
+        Some(CommandOptionError::Unresolved("user".to_string())),
+    );
+
+    let unknown = command_data(r#"[{ "name": "kick", "type": 1, "options": [] }]"#);
+    assert_eq!(
+        unknown.parse_options::<Command>().err(),
+        Some(CommandOptionError::UnknownSubCommand("kick".to_string())),
+    );
+}
@@ -0,0 +1,259 @@
+#![cfg(feature = "fake_gateway")]
+
+mod common;
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serenity::constants::close_codes;
+use serenity::gateway::fake::{FakeConnection, FakeGateway};
+use serenity::gateway::{
+    ConnectionStage,
+    GatewayEncoding,
+    GatewayError,
+    ReconnectType,
+    Shard,
+    ShardAction,
+    TransportCompression,
+};
+use serenity::model::event::GatewayEvent;
+use serenity::model::gateway::GatewayIntents;
+use serenity::Error;
+
+async fn next_action(shard: &mut Shard) -> serenity::Result<Option<ShardAction>> {
+    let event = loop {
+        match shard.recv_json().await {
+            Ok(Some(value)) => break GatewayEvent::deserialize(value).map_err(Into::into),
+            Ok(None) => continue,
+            Err(why) => break Err(why),
+        }
+    };
+
+    shard.handle_event(&event)
+}
+
+/// Connects a shard and identifies it, starting the session `session`.
+async fn connect(gateway: &FakeGateway) -> (Shard, FakeConnection) {
+    connect_with_encoding(gateway, GatewayEncoding::Json).await
+}
+
+async fn connect_with_encoding(
+    gateway: &FakeGateway,
+    encoding: GatewayEncoding,
+) -> (Shard, FakeConnection) {
+    let (shard, connection) = tokio::join!(
+        Shard::new_with_encoding(
+            gateway.ws_url(),
+            "token",
+            [0, 1],
+            GatewayIntents::GUILDS,
+            TransportCompression::default(),
+            encoding,
+        ),
+        gateway.accept(),
+    );
+    let (mut shard, mut connection) = (shard.unwrap(), connection.unwrap());
+    assert_eq!(connection.encoding(), encoding);
+
+    connection.hello(45_000).await.unwrap();
+    assert!(matches!(next_action(&mut shard).await.unwrap(), Some(ShardAction::Identify)));
+
+    shard.identify().await.unwrap();
+    let identify = connection.expect_identify().await.unwrap();
+    assert_eq!(identify.token, "token");
+    assert_eq!(identify.shard, [0, 1]);
+    assert_eq!(identify.intents, GatewayIntents::GUILDS);
+
+    connection.ready("session").await.unwrap();
+    assert!(next_action(&mut shard).await.unwrap().is_none());
+    assert_eq!(shard.stage(), ConnectionStage::Connected);
+    assert_eq!(shard.session_id().map(String::as_str), Some("session"));
+
+    (shard, connection)
+}
+
+#[tokio::test]
+async fn resumes_after_reconnect() {
+    let gateway = FakeGateway::bind().await.unwrap();
+    let (mut shard, mut connection) = connect(&gateway).await;
+
+    connection.reconnect().await.unwrap();
+    let action = next_action(&mut shard).await.unwrap();
+    assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Resume))));
+
+    let (resumed, connection) = tokio::join!(shard.resume(), gateway.accept());
+    resumed.unwrap();
+    let mut connection = connection.unwrap();
+
+    let resume = connection.expect_resume().await.unwrap();
+    assert_eq!(resume.session_id, "session");
+    assert_eq!(resume.seq, 1);
+    assert_eq!(shard.stage(), ConnectionStage::Resuming);
+
+    connection.set_seq(resume.seq);
+    connection.resumed().await.unwrap();
+    assert!(next_action(&mut shard).await.unwrap().is_none());
+    assert_eq!(shard.stage(), ConnectionStage::Connected);
+    assert_eq!(shard.seq(), 2);
+}
+
+#[tokio::test]
+async fn handles_invalid_sessions() {
+    let gateway = FakeGateway::bind().await.unwrap();
+    let (mut shard, mut connection) = connect(&gateway).await;
+
+    connection.invalid_session(true).await.unwrap();
+    let action = next_action(&mut shard).await.unwrap();
+    assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Resume))));
+
+    connection.invalid_session(false).await.unwrap();
+    let action = next_action(&mut shard).await.unwrap();
+    assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Reidentify))));
+}
+
+#[tokio::test]
+async fn heartbeats_on_request() {
+    let gateway = FakeGateway::bind().await.unwrap();
+    let (mut shard, mut connection) = connect(&gateway).await;
+
+    connection.request_heartbeat().await.unwrap();
+    assert!(matches!(next_action(&mut shard).await.unwrap(), Some(ShardAction::Heartbeat)));
+
+    shard.heartbeat().await.unwrap();
+    assert_eq!(connection.expect_heartbeat().await.unwrap(), Some(1));
+    assert!(!shard.last_heartbeat_acknowledged());
+
+    connection.heartbeat_ack().await.unwrap();
+    assert!(next_action(&mut shard).await.unwrap().is_none());
+    assert!(shard.last_heartbeat_acknowledged());
+    assert!(shard.latency().is_some());
+}
+
+#[cfg(feature = "etf")]
+#[tokio::test]
+async fn exchanges_etf_payloads() {
+    let gateway = FakeGateway::bind().await.unwrap();
+    let (mut shard, mut connection) = connect_with_encoding(&gateway, GatewayEncoding::Etf).await;
+    assert_eq!(shard.encoding(), GatewayEncoding::Etf);
+
+    connection.request_heartbeat().await.unwrap();
+    assert!(matches!(next_action(&mut shard).await.unwrap(), Some(ShardAction::Heartbeat)));
+
+    shard.heartbeat().await.unwrap();
+    assert_eq!(connection.expect_heartbeat().await.unwrap(), Some(1));
+}
+
+#[tokio::test]
+async fn handles_close_codes() {
+    let gateway = FakeGateway::bind().await.unwrap();
+
+    let (mut shard, mut connection) = connect(&gateway).await;
+    connection.close(close_codes::UNKNOWN_ERROR, "unknown").await.unwrap();
+    let action = next_action(&mut shard).await.unwrap();
+    assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Resume))));
+
+    let (mut shard, mut connection) = connect(&gateway).await;
+    connection.close(close_codes::SESSION_TIMEOUT, "timeout").await.unwrap();
+    let action = next_action(&mut shard).await.unwrap();
+    assert!(matches!(action, Some(ShardAction::Reconnect(ReconnectType::Reidentify))));
+    assert!(shard.session_id().is_none());
+    assert!(matches!(shard.reconnection_type(), ReconnectType::Reidentify));
+
+    let (mut shard, mut connection) = connect(&gateway).await;
+    connection.close(close_codes::AUTHENTICATION_FAILED, "token").await.unwrap();
+    let result = next_action(&mut shard).await;
+    assert!(matches!(result, Err(Error::Gateway(GatewayError::InvalidAuthentication))));
+}
+
+#[cfg(feature = "client")]
+mod client {
+    use serenity::async_trait;
+    use serenity::client::ClientBuilder;
+    use serenity::http::routing::Route;
+    use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+    use serenity::http::{LightMethod, StatusCode};
+    use serenity::model::event::ResumedEvent;
+    use serenity::model::gateway::Ready;
+    use serenity::prelude::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::*;
+
+    struct Handler(UnboundedSender<String>);
+
+    #[async_trait]
+    impl EventHandler for Handler {
+        async fn ready(&self, _: Context, ready: Ready) {
+            self.0.send(ready.session_id).unwrap();
+        }
+
+        async fn resume(&self, _: Context, _: ResumedEvent) {
+            self.0.send("resumed".to_string()).unwrap();
+        }
+    }
+
+    #[cfg(feature = "framework")]
+    struct NoFramework;
+
+    #[cfg(feature = "framework")]
+    #[async_trait]
+    impl serenity::framework::Framework for NoFramework {
+        async fn dispatch(&self, _: Context, _: serenity::model::channel::Message) {}
+    }
+
+    #[tokio::test]
+    async fn runs_shards_of_client() {
+        let gateway = FakeGateway::bind().await.unwrap();
+
+        let transport = Arc::new(MockTransport::new());
+        transport.expect(
+            Expectation::new(LightMethod::Get, Route::Gateway),
+            MockResponse::new(StatusCode::OK).json(&serde_json::json!({ "url": gateway.url() })),
+        );
+        transport.expect(
+            Expectation::new(LightMethod::Get, Route::GatewayBot),
+            MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+                "url": gateway.url(),
+                "shards": 1,
+                "session_start_limit": {
+                    "total": 1000,
+                    "remaining": 1000,
+                    "reset_after": 0,
+                    "max_concurrency": 1,
+                },
+            })),
+        );
+
+        let http = common::http("token", &transport);
+        let (tx, mut rx) = unbounded_channel();
+        let builder =
+            ClientBuilder::new_with_http(http, GatewayIntents::GUILDS).event_handler(Handler(tx));
+        #[cfg(feature = "framework")]
+        let builder = builder.framework(NoFramework);
+        let mut client = builder.await.unwrap();
+
+        let shard_manager = Arc::clone(&client.shard_manager);
+        tokio::spawn(async move { client.start().await });
+
+        let mut connection = gateway.accept().await.unwrap();
+        connection.hello(45_000).await.unwrap();
+        assert_eq!(connection.expect_identify().await.unwrap().token, "Bot token");
+
+        connection.ready("session").await.unwrap();
+        assert_eq!(rx.recv().await.as_deref(), Some("session"));
+
+        connection.reconnect().await.unwrap();
+        let mut connection = gateway.accept().await.unwrap();
+        assert_eq!(connection.expect_resume().await.unwrap().seq, 1);
+
+        connection.set_seq(1);
+        connection.resumed().await.unwrap();
+        assert_eq!(rx.recv().await.as_deref(), Some("resumed"));
+
+        shard_manager.lock().await.shutdown_all().await;
+
+        // The session start limit is only fetched once.
+        let requests = transport.requests();
+        assert_eq!(requests.iter().filter(|r| r.route == Route::GatewayBot).count(), 1);
+    }
+}
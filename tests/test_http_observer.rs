@@ -0,0 +1,122 @@
+#![cfg(feature = "http")]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::http::observer::{HttpObserver, RequestInfo, ResponseInfo};
+use serenity::http::routing::Route;
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::{HttpBuilder, LightMethod, RetryPolicy, StatusCode};
+
+#[derive(Default)]
+struct Recorder {
+    requests: Mutex<Vec<RequestInfo>>,
+    responses: Mutex<Vec<ResponseInfo>>,
+}
+
+impl HttpObserver for Recorder {
+    fn before_request(&self, info: &RequestInfo) {
+        self.requests.lock().unwrap().push(info.clone());
+    }
+
+    fn after_request(&self, info: &ResponseInfo) {
+        self.responses.lock().unwrap().push(info.clone());
+    }
+}
+
+#[tokio::test]
+async fn observes_ratelimited_requests() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::ratelimited(0.01));
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::NO_CONTENT)
+            .header("x-ratelimit-bucket", "abcd")
+            .header("x-ratelimit-remaining", "4")
+            .header("x-ratelimit-reset-after", "1.5"),
+    );
+
+    let recorder = Arc::new(Recorder::default());
+    let http =
+        HttpBuilder::new("token").transport(transport).observer(Arc::clone(&recorder) as _).build();
+
+    http.delete_message(5, 10).await.unwrap();
+
+    let requests = recorder.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, LightMethod::Delete);
+    assert_eq!(requests[0].route, Route::ChannelsIdMessagesId(LightMethod::Delete, 10));
+    assert_eq!(requests[0].url.as_str(), "https://discord.com/api/v10/channels/5/messages/10");
+    assert_eq!((requests[0].attempt, requests[0].ratelimit_retries), (1, 0));
+    assert_eq!((requests[1].attempt, requests[1].ratelimit_retries), (1, 1));
+
+    let responses = recorder.responses.lock().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].status, Some(StatusCode::TOO_MANY_REQUESTS));
+    assert_eq!(responses[1].status, Some(StatusCode::NO_CONTENT));
+    assert_eq!(responses[1].bucket.as_deref(), Some("abcd"));
+    assert_eq!(responses[1].remaining, Some(4));
+    assert_eq!(responses[1].reset_after, Some(Duration::from_millis(1500)));
+}
+
+#[tokio::test]
+async fn counts_retries() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::new(StatusCode::BAD_GATEWAY));
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({ "url": "wss://gateway" })),
+    );
+
+    let recorder = Arc::new(Recorder::default());
+    let mut http = HttpBuilder::new("token")
+        .transport(transport)
+        .ratelimiter_disabled(true)
+        .retry_policy(
+            RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(1)),
+        )
+        .build();
+    http.set_observer(Arc::clone(&recorder) as _);
+
+    http.get_gateway().await.unwrap();
+
+    let responses = recorder.responses.lock().unwrap();
+    let attempts = responses
+        .iter()
+        .map(|info| (info.attempt, info.ratelimit_retries, info.status))
+        .collect::<Vec<_>>();
+    assert_eq!(attempts, [(1, 0, Some(StatusCode::BAD_GATEWAY)), (2, 0, Some(StatusCode::OK))]);
+}
+
+#[tokio::test]
+async fn counts_ratelimit_retries_separately() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::new(StatusCode::BAD_GATEWAY));
+    transport.expect(Expectation::any(), MockResponse::ratelimited(0.01));
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({ "url": "wss://gateway" })),
+    );
+
+    let recorder = Arc::new(Recorder::default());
+    let http = HttpBuilder::new("token")
+        .transport(transport)
+        .observer(Arc::clone(&recorder) as _)
+        .retry_policy(
+            RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(1)),
+        )
+        .build();
+
+    http.get_gateway().await.unwrap();
+
+    let responses = recorder.responses.lock().unwrap();
+    let attempts = responses
+        .iter()
+        .map(|info| (info.attempt, info.ratelimit_retries, info.status))
+        .collect::<Vec<_>>();
+    assert_eq!(attempts, [
+        (1, 0, Some(StatusCode::BAD_GATEWAY)),
+        (2, 0, Some(StatusCode::TOO_MANY_REQUESTS)),
+        (2, 1, Some(StatusCode::OK)),
+    ]);
+}
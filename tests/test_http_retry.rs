@@ -0,0 +1,101 @@
+#![cfg(feature = "http")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::http::{HttpBuilder, RetryPolicy, StatusCode};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Starts a stand-in for the Discord API, which answers requests with the
+/// given statuses in order. Returns its address and the number of requests it
+/// received.
+async fn serve(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+
+    tokio::spawn(async move {
+        for status in statuses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 4096];
+            let _ = stream.read(&mut buf).await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let body = if status == 200 { r#"{"url":"wss://gateway.discord.gg"}"# } else { "" };
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body,
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    (addr, requests)
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(10))
+}
+
+#[tokio::test]
+async fn retries_idempotent_requests() {
+    let (addr, requests) = serve(vec![503, 502, 200]).await;
+    let retries = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&retries);
+
+    let mut http = HttpBuilder::new("token")
+        .proxy(addr)
+        .unwrap()
+        .ratelimiter_disabled(true)
+        .retry_policy(policy())
+        .build();
+    http.set_retry_callback(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    let gateway = http.get_gateway().await.unwrap();
+
+    assert_eq!(gateway.url, "wss://gateway.discord.gg");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert_eq!(retries.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let (addr, requests) = serve(vec![503, 503, 503]).await;
+
+    let http = HttpBuilder::new("token")
+        .proxy(addr)
+        .unwrap()
+        .ratelimiter_disabled(true)
+        .retry_policy(policy().max_attempts(2))
+        .build();
+
+    let err = http.get_gateway().await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        serenity::Error::Http(e) if e.status_code() == Some(StatusCode::SERVICE_UNAVAILABLE)
+    ));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_post_requests() {
+    let (addr, requests) = serve(vec![503, 204]).await;
+
+    let http = HttpBuilder::new("token")
+        .proxy(addr)
+        .unwrap()
+        .ratelimiter_disabled(true)
+        .retry_policy(policy())
+        .build();
+
+    assert!(http.broadcast_typing(1).await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,229 @@
+#![cfg(feature = "interactions_endpoint")]
+
+use std::net::{SocketAddr, TcpListener};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+use serenity::async_trait;
+use serenity::builder::CreateInteractionResponse;
+use serenity::interactions_endpoint::{
+    InteractionHandler,
+    InteractionsEndpoint,
+    MAX_BODY_SIZE,
+    SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+use serenity::model::application::interaction::Interaction;
+use serenity::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+const SECRET_KEY: [u8; 32] = [7; 32];
+
+const PING: &str = r#"{
+    "id": "1",
+    "application_id": "2",
+    "type": 1,
+    "token": "ping-token",
+    "version": 1
+}"#;
+
+const COMMAND: &str = r#"{
+    "id": "3",
+    "application_id": "2",
+    "type": 2,
+    "data": { "id": "4", "name": "ping", "type": 1 },
+    "channel_id": "5",
+    "user": { "id": "6", "username": "ferris", "discriminator": "0001", "avatar": null },
+    "token": "command-token",
+    "version": 1,
+    "locale": "en-US"
+}"#;
+
+struct Handler(UnboundedSender<Interaction>);
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn interaction_create(&self, _: Context, interaction: Interaction) {
+        self.0.send(interaction).unwrap();
+    }
+}
+
+struct Responder;
+
+#[async_trait]
+impl InteractionHandler for Responder {
+    async fn respond(
+        &self,
+        _: Context,
+        interaction: &Interaction,
+    ) -> Option<CreateInteractionResponse<'static>> {
+        let command = match interaction {
+            Interaction::ApplicationCommand(command) => command,
+            _ => return None,
+        };
+
+        let mut response = CreateInteractionResponse::default();
+        response
+            .interaction_response_data(|d| d.content(format!("pong from {}", command.data.name)));
+
+        Some(response)
+    }
+}
+
+/// The current time, `offset` seconds ago.
+fn timestamp(offset: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    (now - offset).to_string()
+}
+
+fn public_key() -> String {
+    let secret = SecretKey::from_bytes(&SECRET_KEY).unwrap();
+
+    PublicKey::from(&secret).as_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sign(timestamp: &str, body: &str) -> String {
+    let secret = SecretKey::from_bytes(&SECRET_KEY).unwrap();
+    let public = PublicKey::from(&secret);
+    let message = [timestamp.as_bytes(), body.as_bytes()].concat();
+    let signature = ExpandedSecretKey::from(&secret).sign(&message, &public);
+
+    signature.to_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn start() -> (SocketAddr, UnboundedReceiver<Interaction>) {
+    start_with(|endpoint| endpoint)
+}
+
+fn start_with(
+    f: impl FnOnce(InteractionsEndpoint) -> InteractionsEndpoint,
+) -> (SocketAddr, UnboundedReceiver<Interaction>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = unbounded_channel();
+
+    let endpoint =
+        f(InteractionsEndpoint::new(&public_key(), "token").unwrap().event_handler(Handler(tx)));
+    tokio::spawn(endpoint.serve_with_listener(listener));
+
+    (addr, rx)
+}
+
+async fn post(
+    addr: SocketAddr,
+    signature: &str,
+    timestamp: &str,
+    body: &'static str,
+) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("http://{}/interactions", addr))
+        .header(SIGNATURE_HEADER, signature)
+        .header(TIMESTAMP_HEADER, timestamp)
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn answers_ping() {
+    let (addr, mut rx) = start();
+
+    let timestamp = timestamp(0);
+    let response = post(addr, &sign(&timestamp, PING), &timestamp, PING).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), r#"{"type":1}"#);
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn rejects_invalid_signatures() {
+    let (addr, _rx) = start();
+
+    let forged = post(addr, &sign(&timestamp(1), PING), &timestamp(0), PING).await;
+    assert_eq!(forged.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let malformed = post(addr, "not a signature", &timestamp(0), PING).await;
+    assert_eq!(malformed.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let unsigned = reqwest::Client::new()
+        .post(format!("http://{}/interactions", addr))
+        .body(PING)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unsigned.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn forwards_interactions() {
+    let (addr, mut rx) = start();
+
+    let timestamp = timestamp(0);
+    let response = post(addr, &sign(&timestamp, COMMAND), &timestamp, COMMAND).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    // Without an interaction handler, the response is deferred.
+    assert_eq!(response.text().await.unwrap(), r#"{"type":5}"#);
+
+    match rx.recv().await {
+        Some(Interaction::ApplicationCommand(command)) => {
+            assert_eq!(command.data.name, "ping");
+            assert_eq!(command.token, "command-token");
+        },
+        other => panic!("expected an application command, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn replies_with_responses() {
+    let (addr, mut rx) = start_with(|endpoint| endpoint.interaction_handler(Responder));
+
+    let timestamp = timestamp(0);
+    let response = post(addr, &sign(&timestamp, COMMAND), &timestamp, COMMAND).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        reqwest::header::HeaderValue::from_static("application/json"),
+    );
+
+    let body: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(body, serde_json::json!({ "type": 4, "data": { "content": "pong from ping" } }));
+
+    // The interaction is still sent on to the event handler.
+    assert!(matches!(rx.recv().await, Some(Interaction::ApplicationCommand(_))));
+}
+
+#[tokio::test]
+async fn rejects_replayed_requests() {
+    let (addr, mut rx) = start();
+
+    // Validly signed, but sent ten minutes ago.
+    let timestamp = timestamp(600);
+    let response = post(addr, &sign(&timestamp, COMMAND), &timestamp, COMMAND).await;
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn rejects_oversized_bodies() {
+    let (addr, _rx) = start();
+
+    // The body is rejected by its announced length, before it is sent.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let head = format!(
+        "POST /interactions HTTP/1.1\r\nHost: {}\r\n{}: 00\r\n{}: {}\r\nContent-Length: {}\r\n\r\n",
+        addr,
+        SIGNATURE_HEADER,
+        TIMESTAMP_HEADER,
+        timestamp(0),
+        MAX_BODY_SIZE + 1,
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+
+    let mut response = [0; 12];
+    stream.read_exact(&mut response).await.unwrap();
+    assert_eq!(&response, b"HTTP/1.1 413");
+}
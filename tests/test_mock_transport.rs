@@ -0,0 +1,151 @@
+#![cfg(all(feature = "http", feature = "model"))]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::http::ratelimiting::RatelimitInfo;
+use serenity::http::routing::Route;
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::{LightMethod, StatusCode};
+use serenity::json::json;
+use serenity::model::id::ChannelId;
+use tokio::time::Instant;
+
+fn message(channel_id: u64, content: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": "10",
+        "channel_id": channel_id.to_string(),
+        "author": { "id": "6", "username": "ferris", "discriminator": "0001", "avatar": null },
+        "content": content,
+        "timestamp": "2022-10-05T12:00:00.000000+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+#[tokio::test]
+async fn sends_messages() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::new(LightMethod::Post, Route::ChannelsIdMessages(5))
+            .matching(|request| request.json().map_or(false, |body| body["content"] == "Hello")),
+        MockResponse::new(StatusCode::OK).json(&message(5, "Hello")),
+    );
+
+    let message = ChannelId(5)
+        .send_message(&common::http("token", &transport), |m| m.content("Hello"))
+        .await
+        .unwrap();
+
+    assert_eq!(message.content, "Hello");
+    assert_eq!(message.channel_id, ChannelId(5));
+    assert_eq!(transport.pending(), 0);
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.path(), "/api/v10/channels/5/messages");
+    assert_eq!(requests[0].headers["authorization"], "Bot token");
+}
+
+#[tokio::test]
+async fn matches_json_bodies() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any().json_body(json!({ "name": "general" })),
+        MockResponse::new(StatusCode::BAD_REQUEST).json(&serde_json::json!({
+            "code": 50035,
+            "message": "Invalid Form Body",
+        })),
+    );
+
+    let map = json!({ "name": "general" }).as_object().unwrap().clone();
+    let err = common::http("token", &transport).edit_channel(5, &map, None).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        serenity::Error::Http(e) if e.status_code() == Some(StatusCode::BAD_REQUEST)
+    ));
+}
+
+#[tokio::test]
+async fn retries_ratelimited_requests() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::ratelimited(0.01));
+    transport.expect(
+        Expectation::new(LightMethod::Get, Route::ChannelsIdMessagesId(LightMethod::Get, 5)),
+        MockResponse::new(StatusCode::OK).json(&message(5, "Hello")),
+    );
+
+    let ratelimits = Arc::new(Mutex::new(Vec::<RatelimitInfo>::new()));
+    let recorded = Arc::clone(&ratelimits);
+
+    let mut http = common::http("token", &transport);
+    http.ratelimiter.set_ratelimit_callback(Box::new(move |info| {
+        recorded.lock().unwrap().push(info);
+    }));
+
+    let message = http.get_message(5, 10).await.unwrap();
+
+    assert_eq!(message.content, "Hello");
+    assert_eq!(transport.requests().len(), 2);
+
+    let ratelimits = ratelimits.lock().unwrap();
+    assert_eq!(ratelimits.len(), 1);
+    assert_eq!(ratelimits[0].path, "https://discord.com/api/v10/channels/5/messages/10");
+    assert!(!ratelimits[0].global);
+}
+
+#[tokio::test]
+async fn waits_for_exhausted_buckets() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any().times(2),
+        MockResponse::new(StatusCode::NO_CONTENT)
+            .header("x-ratelimit-limit", "1")
+            .header("x-ratelimit-remaining", "0")
+            .header("x-ratelimit-reset-after", "0.05"),
+    );
+
+    let ratelimits = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&ratelimits);
+
+    let mut http = common::http("token", &transport);
+    http.ratelimiter.set_ratelimit_callback(Box::new(move |_| {
+        *counter.lock().unwrap() += 1;
+    }));
+
+    http.delete_message(5, 10).await.unwrap();
+    assert_eq!(*ratelimits.lock().unwrap(), 0);
+
+    http.delete_message(5, 10).await.unwrap();
+    assert_eq!(*ratelimits.lock().unwrap(), 1);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_for_global_ratelimits() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any(),
+        MockResponse::ratelimited(0.05).header("x-ratelimit-scope", "global"),
+    );
+    transport.expect(Expectation::any().times(2), MockResponse::new(StatusCode::NO_CONTENT));
+
+    let http = common::http("token", &transport);
+    let start = Instant::now();
+
+    http.delete_message(5, 10).await.unwrap();
+    http.delete_message(6, 10).await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(transport.requests().len(), 3);
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "oauth")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serenity::http::oauth::{OAuth2Client, Pkce};
+use serenity::http::routing::Route;
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::{LightMethod, RequestOptions, StatusCode};
+use serenity::model::application::oauth::Scope;
+use url::Url;
+
+fn client() -> OAuth2Client {
+    OAuth2Client::new(1234, "secret").redirect_uri("https://example.com/callback")
+}
+
+fn form(body: &[u8]) -> HashMap<String, String> {
+    url::form_urlencoded::parse(body).into_owned().collect()
+}
+
+#[test]
+fn builds_authorization_url() {
+    let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mJ92K5Btyme5XGVbF9YXn6jMr7jDP8");
+    assert_eq!(pkce.challenge(), "M50cw8jMEtLsHrBgGTza5ekV4Qp4OT1YEeKgBh9kpCg");
+
+    let url = client().authorization_url(&[Scope::Identify, Scope::Guilds], "xyz", Some(&pkce));
+    let url = Url::parse(&url).unwrap();
+    let params = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+
+    assert_eq!(url.path(), "/api/oauth2/authorize");
+    assert_eq!(params["response_type"], "code");
+    assert_eq!(params["client_id"], "1234");
+    assert_eq!(params["scope"], "identify guilds");
+    assert_eq!(params["state"], "xyz");
+    assert_eq!(params["redirect_uri"], "https://example.com/callback");
+    assert_eq!(params["code_challenge"], pkce.challenge());
+    assert_eq!(params["code_challenge_method"], "S256");
+}
+
+#[tokio::test]
+async fn exchanges_code() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::new(LightMethod::Post, Route::Oauth2Token),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "access_token": "access",
+            "token_type": "Bearer",
+            "expires_in": 604800,
+            "refresh_token": "refresh",
+            "scope": "identify guilds unknown",
+        })),
+    );
+    transport.expect(
+        Expectation::new(LightMethod::Get, Route::UsersMe),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "id": "1",
+            "username": "ferris",
+            "discriminator": "0001",
+            "avatar": null,
+            "mfa_enabled": false,
+        })),
+    );
+
+    let http = common::http("bot", &transport);
+    let pkce = Pkce::new();
+    let token = client().exchange_code(&http, "code", Some(&pkce)).await.unwrap();
+
+    assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+    assert_eq!(token.scopes(), [Scope::Identify, Scope::Guilds]);
+
+    let debug = format!("{:?}", token);
+    assert!(!debug.contains("access") && !debug.contains("refresh"));
+
+    let options = RequestOptions::new().token(token.bearer());
+    http.with_options(options, |http| http.get_current_user()).await.unwrap();
+
+    let requests = transport.requests();
+    // base64 of "1234:secret"
+    assert_eq!(requests[0].headers["authorization"], "Basic MTIzNDpzZWNyZXQ=");
+    assert_eq!(requests[0].headers["content-type"], "application/x-www-form-urlencoded");
+
+    let params = form(requests[0].body.as_deref().unwrap());
+    assert_eq!(params["grant_type"], "authorization_code");
+    assert_eq!(params["code"], "code");
+    assert_eq!(params["redirect_uri"], "https://example.com/callback");
+    assert_eq!(params["code_verifier"], pkce.verifier());
+
+    assert_eq!(requests[1].headers["authorization"], "Bearer access");
+}
+
+#[tokio::test]
+async fn refreshes_and_revokes_tokens() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::new(LightMethod::Post, Route::Oauth2Token),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "access_token": "new access",
+            "token_type": "Bearer",
+            "expires_in": 604800,
+            "refresh_token": "new refresh",
+            "scope": "identify",
+        })),
+    );
+    transport.expect(
+        Expectation::new(LightMethod::Post, Route::Oauth2TokenRevoke),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({})),
+    );
+
+    let http = common::http("bot", &transport);
+    let token = client().refresh_token(&http, "refresh").await.unwrap();
+    client().revoke_token(&http, &token.access_token).await.unwrap();
+
+    let requests = transport.requests();
+    let params = form(requests[0].body.as_deref().unwrap());
+    assert_eq!(params["grant_type"], "refresh_token");
+    assert_eq!(params["refresh_token"], "refresh");
+    assert_eq!(form(requests[1].body.as_deref().unwrap())["token"], "new access");
+}
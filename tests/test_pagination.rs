@@ -0,0 +1,154 @@
+#![cfg(all(feature = "http", feature = "model"))]
+
+mod common;
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::StatusCode;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::Timestamp;
+
+fn user(id: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id.to_string(),
+        "username": "ferris",
+        "discriminator": "0001",
+        "avatar": null,
+    })
+}
+
+fn thread(id: u64, archive_timestamp: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id.to_string(),
+        "guild_id": "1",
+        "parent_id": "5",
+        "type": 11,
+        "name": "thread",
+        "position": 0,
+        "thread_metadata": {
+            "archived": true,
+            "auto_archive_duration": 60,
+            "archive_timestamp": archive_timestamp,
+            "locked": false,
+        },
+    })
+}
+
+#[tokio::test]
+async fn streams_reaction_users() {
+    let transport = Arc::new(MockTransport::new());
+    let first_page = (1..=100).map(user).collect::<Vec<_>>();
+    transport.expect(Expectation::any(), MockResponse::new(StatusCode::OK).json(&first_page));
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&vec![user(101), user(102)]),
+    );
+
+    let http = common::http("token", &transport);
+    let users = ChannelId(5)
+        .reaction_users_iter(&http, 10, '👍')
+        .map(|user| user.unwrap().id)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(users, (1..=102).map(UserId).collect::<Vec<_>>());
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].url.query(), Some("limit=100"));
+    assert_eq!(requests[1].url.query(), Some("limit=100&after=100"));
+}
+
+#[tokio::test]
+async fn streams_archived_threads() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "threads": [thread(20, "2022-10-05T12:00:00.000Z")],
+            "members": [],
+            "has_more": true,
+        })),
+    );
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "threads": [thread(10, "2022-10-04T12:00:00.000Z")],
+            "members": [],
+            "has_more": false,
+        })),
+    );
+
+    let http = common::http("token", &transport);
+    let threads = ChannelId(5)
+        .archived_public_threads_iter(&http)
+        .map(|thread| thread.unwrap().id)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(threads, [ChannelId(20), ChannelId(10)]);
+
+    let before = Timestamp::parse("2022-10-05T12:00:00.000Z").unwrap();
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].url.path(), "/api/v10/channels/5/threads/archived/public");
+    assert_eq!(requests[1].url.query(), Some(&*format!("&before={}&limit=100", before)));
+}
+
+#[tokio::test]
+async fn streams_archived_threads_with_shared_timestamps() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "threads": [
+                thread(40, "2022-10-05T12:00:00.000Z"),
+                thread(30, "2022-10-04T12:00:00.000Z"),
+            ],
+            "members": [],
+            "has_more": true,
+        })),
+    );
+    // Threads 30 and 20 were archived at the same time, but only thread 30 fit
+    // on the first page, so the second one starts before the first thread.
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::OK).json(&serde_json::json!({
+            "threads": [
+                thread(30, "2022-10-04T12:00:00.000Z"),
+                thread(20, "2022-10-04T12:00:00.000Z"),
+                thread(10, "2022-10-03T12:00:00.000Z"),
+            ],
+            "members": [],
+            "has_more": false,
+        })),
+    );
+
+    let http = common::http("token", &transport);
+    let threads = ChannelId(5)
+        .archived_public_threads_iter(&http)
+        .map(|thread| thread.unwrap().id)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(threads, [ChannelId(40), ChannelId(30), ChannelId(20), ChannelId(10)]);
+
+    let before = Timestamp::parse("2022-10-05T12:00:00.000Z").unwrap();
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].url.query(), Some(&*format!("&before={}&limit=100", before)));
+}
+
+#[tokio::test]
+async fn stops_after_errors() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::new(StatusCode::FORBIDDEN));
+
+    let http = common::http("token", &transport);
+    let results = ChannelId(5).reaction_users_iter(&http, 10, '👍').collect::<Vec<_>>().await;
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
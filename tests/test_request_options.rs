@@ -0,0 +1,65 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::{HeaderName, HeaderValue};
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::{HttpError, RequestOptions, RetryPolicy, StatusCode};
+
+#[tokio::test]
+async fn adds_headers() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any().times(2), MockResponse::new(StatusCode::NO_CONTENT));
+
+    let http = common::http("token", &transport);
+    let options = RequestOptions::new()
+        .header(HeaderName::from_static("x-trace-id"), HeaderValue::from_static("abc"));
+
+    http.with_options(options, |http| http.delete_message(5, 10)).await.unwrap();
+    http.delete_message(5, 10).await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].headers["x-trace-id"], "abc");
+    assert!(!requests[1].headers.contains_key("x-trace-id"));
+}
+
+#[tokio::test]
+async fn times_out() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any(), MockResponse::ratelimited(10.0));
+
+    let http = common::http("token", &transport);
+    let options = RequestOptions::new().timeout(Duration::from_millis(50));
+
+    let err = options.scope(http.delete_message(5, 10)).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        serenity::Error::Http(e) if matches!(*e, HttpError::Timeout(_))
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn overrides_retry_policy() {
+    let transport = Arc::new(MockTransport::new());
+    transport
+        .expect(Expectation::any().times(3), MockResponse::new(StatusCode::SERVICE_UNAVAILABLE));
+
+    let http = common::http("token", &transport);
+
+    let options = RequestOptions::new()
+        .retry_policy(RetryPolicy::new().max_attempts(2))
+        .ratelimiter_disabled(true);
+    assert!(http.with_options(options, |http| http.get_gateway()).await.is_err());
+    assert_eq!(transport.requests().len(), 2);
+    assert_eq!(transport.pending(), 1);
+
+    let options =
+        RequestOptions::new().retry_policy(RetryPolicy::disabled()).ratelimiter_disabled(true);
+    assert!(http.with_options(options, |http| http.get_gateway()).await.is_err());
+    assert_eq!(transport.requests().len(), 3);
+    assert_eq!(transport.pending(), 0);
+}
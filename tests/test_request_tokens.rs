@@ -0,0 +1,53 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serenity::http::transport::{Expectation, MockResponse, MockTransport};
+use serenity::http::{RequestOptions, StatusCode};
+
+#[tokio::test]
+async fn authorizes_with_token() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(Expectation::any().times(3), MockResponse::new(StatusCode::NO_CONTENT));
+
+    let http = common::http("first", &transport);
+
+    http.delete_message(5, 10).await.unwrap();
+    let options = RequestOptions::new().token("second");
+    http.with_options(options, |http| http.delete_message(5, 10)).await.unwrap();
+    let options = RequestOptions::new().token("Bearer third").ratelimiter_disabled(true);
+    http.with_options(options, |http| http.delete_message(5, 10)).await.unwrap();
+
+    let tokens =
+        transport.requests().iter().map(|r| r.headers["authorization"].clone()).collect::<Vec<_>>();
+    assert_eq!(tokens, ["Bot first", "Bot second", "Bearer third"]);
+}
+
+#[tokio::test]
+async fn ratelimits_tokens_separately() {
+    let transport = Arc::new(MockTransport::new());
+    transport.expect(
+        Expectation::any(),
+        MockResponse::new(StatusCode::NO_CONTENT)
+            .header("x-ratelimit-bucket", "abcd")
+            .header("x-ratelimit-limit", "5")
+            .header("x-ratelimit-remaining", "0")
+            .header("x-ratelimit-reset-after", "10"),
+    );
+    transport.expect(Expectation::any(), MockResponse::new(StatusCode::NO_CONTENT));
+
+    let http = common::http("first", &transport);
+
+    http.delete_message(5, 10).await.unwrap();
+
+    // The first token must wait 10 seconds before its next request, the
+    // second one doesn't.
+    let start = Instant::now();
+    let options = RequestOptions::new().token("second");
+    http.with_options(options, |http| http.delete_message(5, 10)).await.unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+}